@@ -0,0 +1,98 @@
+use std::{future::Future, time::{Duration, Instant}};
+use tauri::async_runtime::{self, Receiver, Sender};
+use tracing::{debug, warn};
+
+/// Jobs which may wait for an actor before the next caller has to wait to hand over another one.
+const QUEUE_LENGTH:usize = 32;
+const STOPPED:&str = "the game has been closed";
+/// Time after which a job is reported as blocking the async runtime.
+const SLOW_JOB:Duration = Duration::from_millis(50);
+
+type Job<S> = Box<dyn FnOnce(&mut S) + Send>;
+
+/// A value owned by a task of its own, which runs the jobs sent to it one after another. The value is built on the
+/// task and never leaves it, callers only get what their jobs return. Jobs run on the async runtime, so they must
+/// not block; searches are handed to a blocking task instead.
+pub struct Actor<S> {
+    jobs: Sender<Job<S>>,
+}
+
+impl<S> Clone for Actor<S> {
+    fn clone(&self) -> Actor<S> {
+        Actor { jobs: self.jobs.clone() }
+    }
+}
+
+impl<S:Send + 'static> Actor<S> {
+    /// Starts the task with the value `init` resolves to. It stops once every handle is dropped and the queued jobs
+    /// ran.
+    pub fn spawn(init:impl Future<Output = S> + Send + 'static) -> Actor<S> {
+        let (jobs, queue) = async_runtime::channel(QUEUE_LENGTH);
+        async_runtime::spawn(run(init, queue));
+        Actor { jobs }
+    }
+
+    /// Runs `f` after the jobs queued before and waits for its result.
+    pub async fn call<T:Send + 'static>(&self, f:impl FnOnce(&mut S) -> T + Send + 'static) -> Result<T, String> {
+        let (reply, mut result) = async_runtime::channel(1);
+        self.jobs.send(Self::job(f, reply)).await.map_err(|_| STOPPED)?;
+        result.recv().await.ok_or(STOPPED.into())
+    }
+
+    /// Like `call`, for callers outside of the async runtime, e.g. synchronous commands.
+    pub fn blocking_call<T:Send + 'static>(&self, f:impl FnOnce(&mut S) -> T + Send + 'static) -> Result<T, String> {
+        let (reply, mut result) = async_runtime::channel(1);
+        self.jobs.blocking_send(Self::job(f, reply)).map_err(|_| STOPPED)?;
+        result.blocking_recv().ok_or(STOPPED.into())
+    }
+
+    /// Queues `f` without waiting for it. Returns false if it was dropped because the queue is full or the task
+    /// has stopped.
+    pub fn post(&self, f:impl FnOnce(&mut S) + Send + 'static) -> bool {
+        self.jobs.try_send(Box::new(f)).is_ok()
+    }
+
+    fn job<T:Send + 'static>(f:impl FnOnce(&mut S) -> T + Send + 'static, reply:Sender<T>) -> Job<S> {
+        Box::new(move |state| {
+            // the reply channel holds the one result, so it only fails without a receiver
+            if reply.try_send(f(state)).is_err() {
+                debug!("job result dropped, the caller is gone");
+            }
+        })
+    }
+}
+
+async fn run<S>(init:impl Future<Output = S>, mut queue:Receiver<Job<S>>) {
+    let mut state = init.await;
+    while let Some(job) = queue.recv().await {
+        let started = Instant::now();
+        job(&mut state);
+        let elapsed = started.elapsed();
+        if elapsed > SLOW_JOB {
+            warn!("a job blocked the async runtime for {} ms", elapsed.as_millis());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actor() {
+        let actor = Actor::spawn(async { Vec::new() });
+        for i in 0..10 {
+            assert!(actor.post(move |v:&mut Vec<i32>| v.push(i)));
+        }
+        assert_eq!(Ok((0..10).collect::<Vec<_>>()), actor.blocking_call(|v| v.clone()));
+        assert_eq!(Ok(10), async_runtime::block_on(actor.call(|v| v.len())));
+
+        // jobs of one actor run in order, even when posted by several threads
+        let threads:Vec<_> = (0..4).map(|_| {
+            let actor = actor.clone();
+            std::thread::spawn(move || actor.blocking_call(|v| v.push(0)).unwrap())
+        }).collect();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+        assert_eq!(Ok(14), actor.blocking_call(|v| v.len()));
+    }
+}
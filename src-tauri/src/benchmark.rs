@@ -0,0 +1,134 @@
+use std::{path::Path, time::Instant};
+
+use serde::Serialize;
+
+use crate::engine::{ConnectFour, EvalWeights, EvaluatorKind, HEIGHT, WIDTH};
+use crate::playfield::Game;
+use crate::presets::PresetManager;
+
+pub const DEFAULT_PERFT_DEPTH:u8 = 7;
+const SEARCH_DEPTH:u8 = 5;
+
+/// Results of `run`, comparable across machines.
+#[derive(Serialize, Clone, Debug)]
+pub struct BenchmarkReport {
+    pub perft_depth: u8,
+    pub perft_nodes: u64,
+    pub perft_millis: u128,
+    pub perft_nodes_per_sec: f64,
+    pub search_depth: u8,
+    pub search_nodes: u128,
+    pub search_millis: u128,
+    pub search_nodes_per_sec: f64,
+}
+
+fn per_sec(nodes:f64, secs:f64) -> f64 {
+    match secs > 0. {
+        true => nodes / secs,
+        false => 0.
+    }
+}
+
+/// Runs perft from the empty board and the reference search after an opening move in the center column.
+pub fn run(perft_depth:u8) -> BenchmarkReport {
+    let mut game = ConnectFour::empty(1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
+    let now = Instant::now();
+    let perft_nodes = game.perft(perft_depth);
+    let perft_elapsed = now.elapsed();
+
+    game.play(3);
+    game.play(3);
+    let now = Instant::now();
+    let search_nodes = game.search(SEARCH_DEPTH, false).map_or(0, |r| r.ops_count);
+    let search_elapsed = now.elapsed();
+
+    BenchmarkReport {
+        perft_depth,
+        perft_nodes,
+        perft_millis: perft_elapsed.as_millis(),
+        perft_nodes_per_sec: per_sec(perft_nodes as f64, perft_elapsed.as_secs_f64()),
+        search_depth: SEARCH_DEPTH,
+        search_nodes,
+        search_millis: search_elapsed.as_millis(),
+        search_nodes_per_sec: per_sec(search_nodes as f64, search_elapsed.as_secs_f64()),
+    }
+}
+
+/// Time spent in one step of the app startup.
+#[derive(Serialize, Clone, Debug)]
+pub struct StartupPhase {
+    pub name: &'static str,
+    pub micros: u128,
+    pub detail: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StartupProfile {
+    pub phases: Vec<StartupPhase>,
+    pub total_micros: u128,
+}
+
+fn timed(name:&'static str, phase:impl FnOnce() -> String) -> StartupPhase {
+    let now = Instant::now();
+    let detail = phase();
+    StartupPhase { name, micros: now.elapsed().as_micros(), detail }
+}
+
+/// Repeats the work done when the app starts and measures each step.
+pub fn profile_startup(weights_file:Option<&Path>, presets_dir:Option<&Path>) -> StartupProfile {
+    let phases = vec![
+        timed("sequence tables", || {
+            ConnectFour::empty(1, EvaluatorKind::default().build(&EvalWeights::default()));
+            format!("{}x{} board", HEIGHT, WIDTH)
+        }),
+        timed("evaluation weights", || match weights_file.filter(|p| p.exists()) {
+            Some(path) => match EvalWeights::load(path) {
+                Ok(_) => format!("loaded {}", path.display()),
+                Err(e) => e,
+            },
+            None => "no weights file".into(),
+        }),
+        timed("presets", || match presets_dir {
+            Some(dir) => format!("{} presets", PresetManager::new(dir.to_path_buf()).list().len()),
+            None => "no presets directory".into(),
+        }),
+        timed("game state", || {
+            Game::new(8);
+            String::new()
+        }),
+    ];
+    let total_micros = phases.iter().map(|p| p.micros).sum();
+    StartupProfile { phases, total_micros }
+}
+
+/// Entry point of `connect-four bench [perft depth]`.
+pub fn run_cli(args:&[String]) -> Result<(), String> {
+    let depth = match args.first() {
+        Some(d) => d.parse().map_err(|_| format!("invalid depth {}", d))?,
+        None => DEFAULT_PERFT_DEPTH,
+    };
+    let report = run(depth);
+    println!(
+        "perft({}): {} nodes in {} ms ({:.0} nodes/s)",
+        report.perft_depth, report.perft_nodes, report.perft_millis, report.perft_nodes_per_sec
+    );
+    println!(
+        "search depth {}: {} nodes in {} ms ({:.0} nodes/s)",
+        report.search_depth, report.search_nodes, report.search_millis, report.search_nodes_per_sec
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_startup() {
+        let profile = profile_startup(None, None);
+        let names: Vec<&str> = profile.phases.iter().map(|p| p.name).collect();
+        assert_eq!(vec!["sequence tables", "evaluation weights", "presets", "game state"], names);
+        assert_eq!("no weights file", profile.phases[1].detail);
+        assert_eq!(profile.total_micros, profile.phases.iter().map(|p| p.micros).sum());
+    }
+}
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use ts_rs::TS;
+
+use crate::capabilities::Capabilities;
+use crate::clock::ClockSettings;
+use crate::engine::{EvaluatorKind, Rules};
+use crate::personality::Personality;
+use crate::playfield::{BoardSnapshot, CancelMode, Cell, ColumnPreview, ErrorPayload, GameState, GameStatus, HandicapCell, MoveRecord, Update};
+use crate::settings::{PausePolicy, Settings, StartMode};
+use crate::NewGameOptions;
+
+/// Where the bindings go when no directory is given, relative to `src-tauri`.
+const DEFAULT_OUT_DIR: &str = "../src/bindings";
+
+/// Writes the TypeScript types of the events, command arguments and results to `dir`, one file per type. The types
+/// they refer to are written as well.
+pub fn export(dir:&PathBuf) -> Result<(), String> {
+    fn write<T:TS + 'static>(dir:&PathBuf) -> Result<(), String> {
+        T::export_all_to(dir).map_err(|e| format!("could not export {}: {}", T::name(), e))
+    }
+    write::<Update>(dir)?;
+    write::<Cell>(dir)?;
+    write::<GameState>(dir)?;
+    write::<ErrorPayload>(dir)?;
+    write::<GameStatus>(dir)?;
+    write::<ColumnPreview>(dir)?;
+    write::<BoardSnapshot>(dir)?;
+    write::<HandicapCell>(dir)?;
+    write::<MoveRecord>(dir)?;
+    write::<CancelMode>(dir)?;
+    write::<ClockSettings>(dir)?;
+    write::<Settings>(dir)?;
+    write::<StartMode>(dir)?;
+    write::<PausePolicy>(dir)?;
+    write::<Rules>(dir)?;
+    write::<EvaluatorKind>(dir)?;
+    write::<Personality>(dir)?;
+    write::<NewGameOptions>(dir)?;
+    write::<Capabilities>(dir)
+}
+
+/// Entry point of `connect-four bindings [dir]`, built with the `ts-bindings` feature.
+pub fn run_cli(args:&[String]) -> Result<(), String> {
+    let dir = PathBuf::from(args.first().map(|d| d.as_str()).unwrap_or(DEFAULT_OUT_DIR));
+    export(&dir)?;
+    println!("bindings written to {}", dir.display());
+    Ok(())
+}
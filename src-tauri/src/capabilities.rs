@@ -0,0 +1,63 @@
+use serde::Serialize;
+
+use crate::engine::{EvaluatorKind, Variant, HEIGHT, MAX_LEVEL, MAX_WIN_LENGTH, MIN_LEVEL, MIN_WIN_LENGTH, WIDTH};
+use crate::personality::Personality;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct BoardSize {
+    pub rows: usize,
+    pub cols: usize,
+}
+
+/// What this backend supports, so the frontend can offer the matching settings.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct Capabilities {
+    /// Version of the app, which the engine is released with.
+    pub engine_version: &'static str,
+    pub board_sizes: Vec<BoardSize>,
+    pub min_win_length: usize,
+    pub max_win_length: usize,
+    pub variants: Vec<Variant>,
+    pub min_level: u8,
+    pub max_level: u8,
+    pub evaluators: Vec<EvaluatorKind>,
+    pub personalities: Vec<Personality>,
+    /// Whether positions can be solved perfectly instead of searched to a depth.
+    pub perfect_solver: bool,
+}
+
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        engine_version: env!("CARGO_PKG_VERSION"),
+        board_sizes: vec![BoardSize { rows: HEIGHT, cols: WIDTH }],
+        min_win_length: MIN_WIN_LENGTH,
+        max_win_length: MAX_WIN_LENGTH,
+        variants: vec![Variant::Standard, Variant::Cylinder, Variant::Misere],
+        min_level: MIN_LEVEL,
+        max_level: MAX_LEVEL,
+        evaluators: vec![EvaluatorKind::Heuristic, EvaluatorKind::Threats, EvaluatorKind::Windows],
+        personalities: vec![Personality::Balanced, Personality::Aggressive, Personality::Defensive, Personality::Trappy],
+        // the engine only searches to a depth limited by the level
+        perfect_solver: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Rules;
+
+    #[test]
+    fn test_capabilities() {
+        let capabilities = capabilities();
+        assert!(capabilities.variants.contains(&Variant::default()));
+        assert!(capabilities.evaluators.contains(&EvaluatorKind::default()));
+        assert!(capabilities.personalities.contains(&Personality::default()));
+        for win_length in capabilities.min_win_length..=capabilities.max_win_length {
+            assert!(Rules { win_length, variant: Variant::Standard }.validate().is_ok());
+        }
+        assert!(Rules { win_length: capabilities.max_win_length + 1, variant: Variant::Standard }.validate().is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use std::{collections::{HashMap, VecDeque}, time::{Duration, Instant}};
+
+use serde::Serialize;
+
+/// Longest message in characters.
+pub const MAX_MESSAGE_LEN: usize = 300;
+/// Messages a sender may post within `RATE_WINDOW`, further ones are rejected until the oldest expires.
+pub const RATE_LIMIT: usize = 5;
+pub const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// A message relayed to every table, emitted as `chatMessage`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ChatMessage {
+    /// Who wrote the message, the label of the sending window.
+    pub sender: String,
+    pub text: String,
+}
+
+/// Checks the text of a message before it is relayed. Returns the text to relay, e.g. with words masked, or `None`
+/// to drop the message.
+pub trait ChatFilter: Send {
+    fn filter(&self, text:&str) -> Option<String>;
+}
+
+impl<F:Fn(&str) -> Option<String> + Send> ChatFilter for F {
+    fn filter(&self, text:&str) -> Option<String> {
+        self(text)
+    }
+}
+
+/// Masks the words of a list, ignoring case and the punctuation around them.
+pub struct WordFilter {
+    words: Vec<String>,
+}
+
+impl WordFilter {
+    /// One word per line, empty lines are skipped.
+    pub fn parse(list:&str) -> WordFilter {
+        let words = list.lines().map(|w| w.trim().to_lowercase()).filter(|w| !w.is_empty()).collect();
+        WordFilter { words }
+    }
+}
+
+impl ChatFilter for WordFilter {
+    fn filter(&self, text:&str) -> Option<String> {
+        let masked: Vec<String> = text.split(' ')
+            .map(|token| {
+                let word = token.trim_matches(|c:char| !c.is_alphanumeric()).to_lowercase();
+                if self.words.contains(&word) { "*".repeat(token.chars().count()) } else { token.to_owned() }
+            })
+            .collect();
+        Some(masked.join(" "))
+    }
+}
+
+/// Table talk relayed through the host, which is the only one to accept or reject a message, so every table shows
+/// the same conversation.
+#[derive(Default)]
+pub struct ChatRelay {
+    filter: Option<Box<dyn ChatFilter>>,
+    /// When each sender posted within the last `RATE_WINDOW`, the oldest first.
+    sent: HashMap<String, VecDeque<Instant>>,
+}
+
+impl ChatRelay {
+    pub fn set_filter(&mut self, filter:Box<dyn ChatFilter>) {
+        self.filter = Some(filter);
+    }
+
+    /// The message to emit to every table, or why it was rejected.
+    pub fn relay(&mut self, sender:&str, text:&str, now:Instant) -> Result<ChatMessage, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("the message is empty".into());
+        }
+        if text.chars().count() > MAX_MESSAGE_LEN {
+            return Err(format!("a message may have at most {} characters", MAX_MESSAGE_LEN));
+        }
+        let sent = self.sent.entry(sender.to_owned()).or_default();
+        while sent.front().is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW) {
+            sent.pop_front();
+        }
+        if sent.len() >= RATE_LIMIT {
+            return Err("too many messages, wait a moment".into());
+        }
+        let text = match &self.filter {
+            Some(filter) => filter.filter(text).ok_or("the message was rejected by the filter")?,
+            None => text.to_owned(),
+        };
+        sent.push_back(now);
+        Ok(ChatMessage { sender: sender.to_owned(), text })
+    }
+
+    /// Forgets a sender whose window was closed.
+    pub fn remove_sender(&mut self, sender:&str) {
+        self.sent.remove(sender);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relay() {
+        let mut relay = ChatRelay::default();
+        let start = Instant::now();
+        assert_eq!(ChatMessage { sender: "main".into(), text: "gg".into() }, relay.relay("main", " gg\n", start).unwrap());
+        assert!(relay.relay("main", "  ", start).is_err());
+        assert!(relay.relay("main", &"x".repeat(MAX_MESSAGE_LEN + 1), start).is_err());
+
+        for _ in 1..RATE_LIMIT {
+            relay.relay("main", "hi", start).unwrap();
+        }
+        assert!(relay.relay("main", "hi", start).is_err());
+        // the limit is kept per sender
+        relay.relay("game-1", "hi", start).unwrap();
+        relay.relay("main", "hi", start + RATE_WINDOW).unwrap();
+
+        relay.set_filter(Box::new(|text:&str| (!text.contains("spam")).then(|| text.replace("darn", "****"))));
+        assert_eq!("****", relay.relay("game-1", "darn", start).unwrap().text);
+        assert!(relay.relay("game-1", "buy spam", start).is_err());
+    }
+
+    #[test]
+    fn test_word_filter() {
+        let filter = WordFilter::parse("darn\n\n  Heck \n");
+        assert_eq!(Some("oh ***** it, what the heckler".to_owned()), filter.filter("oh DARN! it, what the heckler"));
+        assert_eq!(Some("*****".to_owned()), filter.filter("heck."));
+    }
+}
@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+use serde::{Serialize, Deserialize};
+
+use crate::playfield::CellState;
+
+/// Time control of a game: the budget of each player and the time added after each of their moves.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct ClockSettings {
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub budget_millis: u64,
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub increment_millis: u64,
+}
+
+impl ClockSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.budget_millis {
+            0 => Err("the time budget must not be zero".into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Chess style clock, the time of at most one player runs at once.
+#[derive(Clone, Debug)]
+pub struct Clock {
+    settings: ClockSettings,
+    /// Time left of player 1 and 2 when their time was last stopped.
+    remaining: [Duration; 2],
+    /// The player whose time runs, and since when.
+    running: Option<(CellState, Instant)>,
+}
+
+fn index(player:CellState) -> usize {
+    (player != CellState::P1) as usize
+}
+
+impl Clock {
+    pub fn new(settings:ClockSettings) -> Clock {
+        let budget = Duration::from_millis(settings.budget_millis);
+        Clock { settings, remaining: [budget; 2], running: None }
+    }
+
+    pub fn settings(&self) -> ClockSettings {
+        self.settings
+    }
+
+    /// Time left of `player`, including the move in progress.
+    pub fn remaining(&self, player:CellState) -> Duration {
+        let left = self.remaining[index(player)];
+        match self.running {
+            Some((p, since)) if p == player => left.saturating_sub(since.elapsed()),
+            _ => left,
+        }
+    }
+
+    pub fn running(&self) -> Option<CellState> {
+        self.running.map(|(player, _)| player)
+    }
+
+    /// Starts the time of `player`, the time of the other player is stopped without an increment.
+    pub fn start(&mut self, player:CellState) {
+        self.pause();
+        self.running = Some((player, Instant::now()));
+    }
+
+    /// Stops the time after a move of `player`, who gets the increment. Returns false without an increment
+    /// if the time had already run out.
+    pub fn complete_move(&mut self, player:CellState) -> bool {
+        let left = self.remaining(player);
+        self.running = None;
+        if left.is_zero() {
+            self.remaining[index(player)] = Duration::ZERO;
+            return false;
+        }
+        self.remaining[index(player)] = left + Duration::from_millis(self.settings.increment_millis);
+        true
+    }
+
+    /// Stops the running time without an increment.
+    pub fn pause(&mut self) {
+        if let Some(player) = self.running() {
+            self.remaining[index(player)] = self.remaining(player);
+            self.running = None;
+        }
+    }
+
+    /// The player whose time runs and has run out.
+    pub fn flagged(&self) -> Option<CellState> {
+        self.running().filter(|player| self.remaining(*player).is_zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+
+    use super::*;
+
+    #[test]
+    fn test_clock() {
+        let (x, o) = (CellState::P1, CellState::P2);
+        let mut clock = Clock::new(ClockSettings { budget_millis: 1000, increment_millis: 500 });
+        assert!(ClockSettings { budget_millis: 0, increment_millis: 0 }.validate().is_err());
+        assert_eq!(None, clock.running());
+
+        clock.start(x);
+        sleep(Duration::from_millis(20));
+        assert!(clock.remaining(x) <= Duration::from_millis(980));
+        assert_eq!(Duration::from_millis(1000), clock.remaining(o));
+        assert!(clock.complete_move(x));
+        assert!(clock.remaining(x) > Duration::from_millis(1000));
+        assert_eq!(None, clock.running());
+
+        clock.start(o);
+        clock.start(x);
+        assert_eq!(Some(x), clock.running());
+        assert!(clock.remaining(o) < Duration::from_millis(1000));
+        clock.pause();
+        let left = clock.remaining(x);
+        sleep(Duration::from_millis(5));
+        assert_eq!(left, clock.remaining(x));
+    }
+
+    #[test]
+    fn test_flagged() {
+        let (x, o) = (CellState::P1, CellState::P2);
+        let mut clock = Clock::new(ClockSettings { budget_millis: 10, increment_millis: 1000 });
+        clock.start(o);
+        assert_eq!(None, clock.flagged());
+        sleep(Duration::from_millis(20));
+        assert_eq!(Some(o), clock.flagged());
+        assert!(!clock.complete_move(o));
+        assert_eq!(Duration::ZERO, clock.remaining(o));
+        assert_eq!(Duration::from_millis(10), clock.remaining(x));
+    }
+}
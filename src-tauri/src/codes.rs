@@ -0,0 +1,240 @@
+use serde::Serialize;
+
+use crate::engine::BoardError;
+use crate::minimax::Termination;
+use crate::playfield::GameError;
+
+// Events emitted to the frontend. Cell updates carry the coordinates as suffix: `updateCell-{row}-{col}`.
+pub const EVENT_CELL: &str = "updateCell";
+pub const EVENT_STATE: &str = "updateState";
+pub const EVENT_BALANCE: &str = "updateBalance";
+pub const EVENT_THINKING: &str = "updateThinking";
+pub const EVENT_ANALYSIS: &str = "updateAnalysis";
+pub const EVENT_PROGRESS: &str = "updateProgress";
+pub const EVENT_MOVE: &str = "updateMove";
+pub const EVENT_SEARCHED: &str = "updateSearched";
+pub const EVENT_CLOCK: &str = "updateClock";
+pub const EVENT_SERIES: &str = "updateSeries";
+pub const EVENT_ANNOTATION: &str = "updateAnnotation";
+pub const EVENT_LAST_MOVE: &str = "updateLastMove";
+pub const EVENT_SEARCH_PROGRESS: &str = "searchProgress";
+pub const EVENT_BOARD: &str = "updateBoard";
+pub const EVENT_CHAT_MESSAGE: &str = "chatMessage";
+
+// Errors of a rejected board or move, see `BoardError`.
+pub const ERR_DIMENSIONS: &str = "board.dimensions";
+pub const ERR_CELL_VALUE: &str = "board.cellValue";
+pub const ERR_FLOATING_PIECE: &str = "board.floatingPiece";
+pub const ERR_PIECE_COUNT: &str = "board.pieceCount";
+pub const ERR_PLAYER: &str = "board.player";
+pub const ERR_NOT_PLAYERS_TURN: &str = "board.notPlayersTurn";
+pub const ERR_ALREADY_DECIDED: &str = "board.alreadyDecided";
+pub const ERR_COLUMN_FULL: &str = "board.columnFull";
+pub const ERR_INVALID_COLUMN: &str = "board.invalidColumn";
+
+// Errors of a rejected command, see `GameError`. Errors of the engine keep their board codes.
+pub const ERR_GAME_COLUMN_FULL: &str = "game.columnFull";
+pub const ERR_GAME_INVALID_COLUMN: &str = "game.invalidColumn";
+pub const ERR_GAME_OUT_OF_TURN: &str = "game.outOfTurn";
+pub const ERR_GAME_FINISHED: &str = "game.finished";
+pub const ERR_GAME_NOT_STARTED: &str = "game.notStarted";
+pub const ERR_GAME_ENGINE_BUSY: &str = "game.engineBusy";
+pub const ERR_GAME_TIME_UP: &str = "game.timeUp";
+pub const ERR_GAME_HOTSEAT: &str = "game.hotseat";
+pub const ERR_GAME_NO_COMPUTER_REPLY: &str = "game.noComputerReply";
+pub const ERR_GAME_SEARCH_DISCARDED: &str = "game.searchDiscarded";
+pub const ERR_GAME_CANCELLED: &str = "game.cancelled";
+pub const ERR_GAME_EDITING: &str = "game.editing";
+pub const ERR_GAME_UNKNOWN: &str = "game.unknown";
+pub const ERR_GAME_OTHER: &str = "game.other";
+
+// Reasons a search stopped, see `Termination`.
+pub const REASON_EXHAUSTED: &str = "search.exhausted";
+pub const REASON_DEPTH_REACHED: &str = "search.depthReached";
+pub const REASON_TIME_OUT: &str = "search.timeOut";
+pub const REASON_NODE_BUDGET: &str = "search.nodeBudget";
+pub const REASON_CANCELLED: &str = "search.cancelled";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeKind {
+    Event,
+    Error,
+    Reason,
+}
+
+/// An identifier the frontend can rely on. Codes are only ever added, never renamed or reused.
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct Code {
+    pub name: &'static str,
+    pub kind: CodeKind,
+    pub description: &'static str,
+}
+
+const fn code(name:&'static str, kind:CodeKind, description:&'static str) -> Code {
+    Code { name, kind, description }
+}
+
+pub const CODES: &[Code] = &[
+    code(EVENT_CELL, CodeKind::Event, "a cell changed, suffixed with -{row}-{col}"),
+    code(EVENT_STATE, CodeKind::Event, "the game state changed"),
+    code(EVENT_BALANCE, CodeKind::Event, "the evaluation of the position changed"),
+    code(EVENT_THINKING, CodeKind::Event, "the computer entered another phase of its move"),
+    code(EVENT_ANALYSIS, CodeKind::Event, "the best moves of the computer's search"),
+    code(EVENT_PROGRESS, CodeKind::Event, "the computer completed a search depth"),
+    code(EVENT_MOVE, CodeKind::Event, "a move was played, with its classification"),
+    code(EVENT_SEARCHED, CodeKind::Event, "the computer's search ended, with the reason it stopped"),
+    code(EVENT_CLOCK, CodeKind::Event, "the time left of both players, emitted while the clock runs"),
+    code(EVENT_SERIES, CodeKind::Event, "the score of the series changed"),
+    code(EVENT_ANNOTATION, CodeKind::Event, "a move of the human was judged by the points it lost"),
+    code(EVENT_LAST_MOVE, CodeKind::Event, "the cells of the latest move of each player"),
+    code(EVENT_SEARCH_PROGRESS, CodeKind::Event, "the computer is still searching, emitted periodically"),
+    code(EVENT_BOARD, CodeKind::Event, "several cells changed at once, e.g. on a reset or when a game was loaded"),
+    code(EVENT_CHAT_MESSAGE, CodeKind::Event, "a chat message was relayed to every window"),
+    code(ERR_DIMENSIONS, CodeKind::Error, "the board has the wrong size"),
+    code(ERR_CELL_VALUE, CodeKind::Error, "a cell holds an unknown value"),
+    code(ERR_FLOATING_PIECE, CodeKind::Error, "a piece is not supported by the one below"),
+    code(ERR_PIECE_COUNT, CodeKind::Error, "the players' piece counts cannot occur in a game"),
+    code(ERR_PLAYER, CodeKind::Error, "unknown player"),
+    code(ERR_NOT_PLAYERS_TURN, CodeKind::Error, "the player is not to move"),
+    code(ERR_ALREADY_DECIDED, CodeKind::Error, "the game is already won"),
+    code(ERR_COLUMN_FULL, CodeKind::Error, "the column is full or does not exist"),
+    code(ERR_INVALID_COLUMN, CodeKind::Error, "the column of the evaluated move does not exist or holds no piece"),
+    code(ERR_GAME_COLUMN_FULL, CodeKind::Error, "the column is full, params: col"),
+    code(ERR_GAME_INVALID_COLUMN, CodeKind::Error, "the column does not exist, params: col"),
+    code(ERR_GAME_OUT_OF_TURN, CodeKind::Error, "the other player is to move"),
+    code(ERR_GAME_FINISHED, CodeKind::Error, "the game is already finished"),
+    code(ERR_GAME_NOT_STARTED, CodeKind::Error, "no move was played yet"),
+    code(ERR_GAME_ENGINE_BUSY, CodeKind::Error, "the computer is searching its move"),
+    code(ERR_GAME_TIME_UP, CodeKind::Error, "the player's time ran out"),
+    code(ERR_GAME_HOTSEAT, CodeKind::Error, "the computer does not play in games of two humans"),
+    code(ERR_GAME_NO_COMPUTER_REPLY, CodeKind::Error, "the last move is not a reply of the computer"),
+    code(ERR_GAME_SEARCH_DISCARDED, CodeKind::Error, "the game changed while the computer searched"),
+    code(ERR_GAME_CANCELLED, CodeKind::Error, "the search or preview was cancelled"),
+    code(ERR_GAME_EDITING, CodeKind::Error, "the position is being edited"),
+    code(ERR_GAME_UNKNOWN, CodeKind::Error, "no game with this id is open, params: game"),
+    code(ERR_GAME_OTHER, CodeKind::Error, "any other reason, only described in English, params: detail"),
+    code(REASON_EXHAUSTED, CodeKind::Reason, "every line was searched until the end of the game"),
+    code(REASON_DEPTH_REACHED, CodeKind::Reason, "the maximum depth was reached"),
+    code(REASON_TIME_OUT, CodeKind::Reason, "the time limit was reached"),
+    code(REASON_NODE_BUDGET, CodeKind::Reason, "the node budget was used up"),
+    code(REASON_CANCELLED, CodeKind::Reason, "the search was cancelled"),
+];
+
+const fn str_eq(a:&str, b:&str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn all_unique(codes:&[Code]) -> bool {
+    let mut i = 0;
+    while i < codes.len() {
+        let mut j = i + 1;
+        while j < codes.len() {
+            if str_eq(codes[i].name, codes[j].name) {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+const _: () = assert!(all_unique(CODES), "codes must be unique");
+
+pub fn board_error(e:&BoardError) -> &'static str {
+    match e {
+        BoardError::Dimensions { .. } => ERR_DIMENSIONS,
+        BoardError::CellValue { .. } => ERR_CELL_VALUE,
+        BoardError::FloatingPiece { .. } => ERR_FLOATING_PIECE,
+        BoardError::PieceCount { .. } => ERR_PIECE_COUNT,
+        BoardError::Player(_) => ERR_PLAYER,
+        BoardError::NotPlayersTurn(_) => ERR_NOT_PLAYERS_TURN,
+        BoardError::AlreadyDecided(_) => ERR_ALREADY_DECIDED,
+        BoardError::ColumnFull(_) => ERR_COLUMN_FULL,
+        BoardError::InvalidColumn(_) => ERR_INVALID_COLUMN,
+    }
+}
+
+pub fn game_error(e:&GameError) -> &'static str {
+    match e {
+        GameError::ColumnFull(_) => ERR_GAME_COLUMN_FULL,
+        GameError::InvalidColumn(_) => ERR_GAME_INVALID_COLUMN,
+        GameError::OutOfTurn => ERR_GAME_OUT_OF_TURN,
+        GameError::GameFinished => ERR_GAME_FINISHED,
+        GameError::NotStarted => ERR_GAME_NOT_STARTED,
+        GameError::EngineBusy => ERR_GAME_ENGINE_BUSY,
+        GameError::TimeUp => ERR_GAME_TIME_UP,
+        GameError::Hotseat => ERR_GAME_HOTSEAT,
+        GameError::NoComputerReply => ERR_GAME_NO_COMPUTER_REPLY,
+        GameError::SearchDiscarded => ERR_GAME_SEARCH_DISCARDED,
+        GameError::Cancelled => ERR_GAME_CANCELLED,
+        GameError::Editing => ERR_GAME_EDITING,
+        GameError::UnknownGame(_) => ERR_GAME_UNKNOWN,
+        GameError::Board(e) => board_error(e),
+        GameError::Other(_) => ERR_GAME_OTHER,
+    }
+}
+
+pub fn termination(t:Termination) -> &'static str {
+    match t {
+        Termination::Exhausted => REASON_EXHAUSTED,
+        Termination::DepthReached => REASON_DEPTH_REACHED,
+        Termination::TimeOut => REASON_TIME_OUT,
+        Termination::NodeBudget => REASON_NODE_BUDGET,
+        Termination::Cancelled => REASON_CANCELLED,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registered() {
+        let registered = |name:&str| CODES.iter().any(|c| c.name == name);
+        let errors = [
+            BoardError::Dimensions { rows: 0, cols: 0 },
+            BoardError::CellValue { row: 0, col: 0, value: 2 },
+            BoardError::FloatingPiece { row: 1, col: 0 },
+            BoardError::PieceCount { p1: 3, p2: 0 },
+            BoardError::Player(0),
+            BoardError::NotPlayersTurn(1),
+            BoardError::AlreadyDecided(1),
+            BoardError::ColumnFull(7),
+            BoardError::InvalidColumn(7),
+        ];
+        assert!(errors.iter().all(|e| registered(board_error(e))));
+        let game_errors = [
+            GameError::ColumnFull(0),
+            GameError::InvalidColumn(7),
+            GameError::OutOfTurn,
+            GameError::GameFinished,
+            GameError::NotStarted,
+            GameError::EngineBusy,
+            GameError::TimeUp,
+            GameError::Hotseat,
+            GameError::NoComputerReply,
+            GameError::SearchDiscarded,
+            GameError::Cancelled,
+            GameError::Editing,
+            GameError::UnknownGame(1),
+            GameError::Board(BoardError::Player(0)),
+            GameError::Other(String::new()),
+        ];
+        assert!(game_errors.iter().all(|e| registered(game_error(e))));
+        let reasons = [Termination::Exhausted, Termination::DepthReached, Termination::TimeOut, Termination::NodeBudget, Termination::Cancelled];
+        assert!(reasons.iter().all(|t| registered(termination(*t))));
+        assert!(!all_unique(&[code("a", CodeKind::Event, ""), code("a", CodeKind::Error, "")]));
+    }
+}
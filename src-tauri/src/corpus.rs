@@ -0,0 +1,74 @@
+use std::{fs, path::Path};
+
+use crate::engine::{HEIGHT, WIDTH};
+
+/// A position given by the moves leading to it, labeled with its game-theoretic value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabeledPosition {
+    /// 0-based columns in the order they were played, starting with player 1.
+    pub moves: Vec<usize>,
+    /// 1 if player 1 wins with perfect play, -1 if player 2 wins, 0 for a draw.
+    pub value: i8,
+}
+
+/// Parses a corpus line of the form `<moves> <value>`, e.g. `4453 1`.
+/// Moves are 1-based column digits, the value is seen from player 1.
+/// Returns `None` for empty lines and `#` comments.
+pub fn parse_line(line:&str) -> Result<Option<LabeledPosition>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let (moves, value) = line.split_once(char::is_whitespace).ok_or("expected <moves> <value>")?;
+    let value: i8 = value.trim().parse().map_err(|_| format!("invalid value {}", value.trim()))?;
+    if !(-1..=1).contains(&value) {
+        return Err(format!("value must be -1, 0 or 1, got {}", value));
+    }
+
+    let mut heights = [0; WIDTH];
+    let moves = moves.chars().map(|c| {
+        let col = c.to_digit(10)
+            .filter(|d| (1..=WIDTH as u32).contains(d))
+            .ok_or(format!("invalid column {}", c))? as usize - 1;
+        heights[col] += 1;
+        match heights[col] > HEIGHT {
+            true => Err(format!("column {} is already full", col + 1)),
+            false => Ok(col)
+        }
+    }).collect::<Result<Vec<usize>, String>>()?;
+
+    Ok(Some(LabeledPosition { moves, value }))
+}
+
+pub fn load_corpus(path:&Path) -> Result<Vec<LabeledPosition>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut corpus = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        match parse_line(line) {
+            Ok(Some(position)) => corpus.push(position),
+            Ok(None) => {},
+            Err(e) => return Err(format!("line {}: {}", i + 1, e)),
+        }
+    }
+    Ok(corpus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("4453 1").unwrap(),
+            Some(LabeledPosition { moves: vec![3, 3, 4, 2], value: 1 })
+        );
+        assert_eq!(parse_line("  # comment").unwrap(), None);
+        assert_eq!(parse_line("").unwrap(), None);
+        assert!(parse_line("4453").is_err());
+        assert!(parse_line("4483 1").is_err());
+        assert!(parse_line("44 2").is_err());
+        assert!(parse_line("1111111 0").is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use rand::Rng;
+use serde::Serialize;
+
+use crate::engine::WIDTH;
+
+/// Points for a win in the fewest possible moves...
+const WIN_POINTS:u32 = 100;
+const FASTEST_WIN:u32 = 4;
+/// ...reduced for each further move of the human.
+const MOVE_PENALTY:u32 = 5;
+const GUESS_BONUS:u32 = 25;
+const WRONG_GUESS_PENALTY:u32 = 10;
+
+/// A practice game in which the engine never plays into one secret column.
+/// The human has to find the column and exploit it as fast as possible.
+pub struct Drill {
+    column: usize,
+    wrong_guesses: u32,
+    guessed_after: Option<u32>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DrillScore {
+    /// The secret column, revealed once it was guessed or the game is over.
+    pub column: Option<usize>,
+    pub won: bool,
+    pub moves: u32,
+    /// Number of own moves after which the column was guessed correctly.
+    pub guessed_after: Option<u32>,
+    pub wrong_guesses: u32,
+    pub points: u32,
+}
+
+impl Drill {
+    pub fn generate(rng:&mut impl Rng) -> Drill {
+        Drill {
+            column: rng.gen_range(0..WIDTH),
+            wrong_guesses: 0,
+            guessed_after: None,
+        }
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Records a guess made after the human's `moves`-th move. Guesses after the first correct one are not counted.
+    pub fn guess(&mut self, col:usize, moves:u32) -> bool {
+        if self.guessed_after.is_some() {
+            return col == self.column;
+        }
+        match col == self.column {
+            true => self.guessed_after = Some(moves),
+            false => self.wrong_guesses += 1,
+        }
+        col == self.column
+    }
+
+    pub fn score(&self, won:bool, finished:bool, moves:u32) -> DrillScore {
+        let win_points = match won {
+            true => WIN_POINTS.saturating_sub(MOVE_PENALTY * moves.saturating_sub(FASTEST_WIN)),
+            false => 0,
+        };
+        let guess_points = self.guessed_after.map_or(0, |_| GUESS_BONUS);
+        DrillScore {
+            column: (finished || self.guessed_after.is_some()).then_some(self.column),
+            won,
+            moves,
+            guessed_after: self.guessed_after,
+            wrong_guesses: self.wrong_guesses,
+            points: (win_points + guess_points).saturating_sub(WRONG_GUESS_PENALTY * self.wrong_guesses),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drill(column:usize) -> Drill {
+        Drill { column, wrong_guesses: 0, guessed_after: None }
+    }
+
+    #[test]
+    fn test_generate() {
+        let mut rng = rand::thread_rng();
+        assert!((0..50).map(|_| Drill::generate(&mut rng).column()).all(|c| c < WIDTH));
+    }
+
+    #[test]
+    fn test_score() {
+        let mut d = drill(2);
+        let score = d.score(false, false, 3);
+        assert_eq!(None, score.column);
+        assert_eq!(0, score.points);
+
+        assert!(!d.guess(5, 3));
+        assert!(d.guess(2, 4));
+        assert!(d.guess(2, 6));
+        assert!(!d.guess(1, 6));
+        assert_eq!(DrillScore {
+            column: Some(2),
+            won: true,
+            moves: 10,
+            guessed_after: Some(4),
+            wrong_guesses: 1,
+            points: 100 - 6 * 5 + 25 - 10,
+        }, d.score(true, true, 10));
+
+        let d = drill(0);
+        assert_eq!(Some(0), d.score(false, true, 21).column);
+        assert_eq!(100, d.score(true, true, 4).points);
+        assert_eq!(0, d.score(true, true, 40).points);
+    }
+}
@@ -0,0 +1,62 @@
+use tauri::Window;
+
+use crate::engine::{HEIGHT, WIDTH};
+use crate::playfield::{emit_update, CellState, Position, Update};
+
+/// A board set up cell by cell, which becomes a game once it is finished.
+pub struct Editor {
+    /// Cell states in row major order, row 0 is the bottom row.
+    board: Vec<i8>,
+}
+
+impl Editor {
+    /// Starts editing from `board`, usually the one of the current game.
+    pub fn new(board:Vec<i8>) -> Editor {
+        Editor { board }
+    }
+
+    pub fn set_cell(&mut self, row:usize, col:usize, state:CellState, window:Option<&Window>) -> Result<(), String> {
+        if row >= HEIGHT || col >= WIDTH {
+            return Err(format!("cell ({}, {}) is not on the board", row, col));
+        }
+        self.board[row * WIDTH + col] = state as i8;
+        if let Some(window) = window {
+            emit_update(Update::Cell { row: row as u8, col: col as u8, state: state as i8, winning: false, drop: None }, window)?;
+        }
+        Ok(())
+    }
+
+    /// The edited position with `to_move` to move. Whether it can occur in a game is checked when it is set up.
+    pub fn finish(&self, to_move:CellState) -> Result<Position, String> {
+        let moves = self.board.iter().filter(|v| **v != 0).count();
+        let position = Position { board: self.board.clone(), to_move, moves };
+        position.validate()?;
+        Ok(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::Rules;
+
+    use super::*;
+
+    #[test]
+    fn test_editor() {
+        let mut editor = Editor::new(vec![0; WIDTH * HEIGHT]);
+        assert!(editor.set_cell(HEIGHT, 0, CellState::P1, None).is_err());
+        editor.set_cell(0, 3, CellState::P1, None).unwrap();
+        editor.set_cell(1, 3, CellState::P2, None).unwrap();
+        editor.set_cell(0, 4, CellState::P1, None).unwrap();
+        assert!(editor.finish(CellState::P1).is_err());
+        assert!(editor.finish(CellState::Blank).is_err());
+        let position = editor.finish(CellState::P2).unwrap();
+        assert_eq!(3, position.moves);
+        assert_eq!(3, position.move_order(Rules::default()).unwrap().len());
+
+        // a floating piece passes the count, but no game leads to it
+        editor.set_cell(0, 3, CellState::Blank, None).unwrap();
+        editor.set_cell(2, 0, CellState::P1, None).unwrap();
+        assert!(editor.finish(CellState::P2).unwrap().move_order(Rules::default()).is_err());
+    }
+}
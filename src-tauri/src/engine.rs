@@ -1,95 +1,130 @@
 use std::cmp::{max, min};
+use std::fmt;
+use std::fs;
+use std::mem::size_of;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::str::FromStr;
 use array2d::Array2D;
-use minimax::{Environment, minimize, maximize};
+use minimax::{Environment, ForkableEnvironment, minimize, maximize};
+use serde::{Serialize, Deserialize};
 
-use crate::minimax::{self, Config, StateEvaluation};
+use crate::memory::{vec_bytes, MemoryUsage};
+use crate::minimax::{self, CancelToken, Config, ConfigBuilder, ConfigSnapshot, Progress, Score, SearchHandle, StateEvaluation, TieBreak};
 
 pub const WIDTH:usize = 7;
 pub const HEIGHT:usize = 6;
 pub const TOTAL_FIELDS:usize = WIDTH * HEIGHT;
 
+pub const MIN_LEVEL:u8 = 1;
+pub const MAX_LEVEL:u8 = 20;
+
+/// Pieces in a row needed to win the standard game.
+pub const WIN_LENGTH:usize = 4;
+pub const MIN_WIN_LENGTH:usize = 3;
+pub const MAX_WIN_LENGTH:usize = 5;
+
 const P1:i8 = 1;
 const P2:i8 = -1;
 
 const FIELDS:[usize;WIDTH] = [3,2,4,1,5,0,6];
-const COL_BONUS:[f32;WIDTH] = [0., 0.5, 1.0, 1.5, 1.0, 0.5, 0.];
 
-const MAX_SCORE:f32 = 127.;
-const MIN_SCORE:f32 = -127.;
+/// Fixed point scale of scores: a point, about one piece in a row, is worth `SCORE_SCALE`.
+pub const SCORE_SCALE:Score = 100;
+const MAX_SCORE:Score = 127 * SCORE_SCALE;
+const MIN_SCORE:Score = -MAX_SCORE;
+/// Points of a won position.
+pub const WIN_POINTS:f32 = MAX_SCORE as f32 / SCORE_SCALE as f32;
+/// Points a move may lose against the best one and still count as the best, as searches differ a little...
+const BEST_SWING:f32 = 0.5;
+/// ...and the points lost by a good move and an inaccuracy, more are a blunder.
+const GOOD_SWING:f32 = 3.;
+const INACCURACY_SWING:f32 = 10.;
 const EPSILON:f32 = 0.95;
+/// Zobrist key of player 2 being to move.
+const SIDE_KEY:u64 = 0x9e37_79b9_7f4a_7c15;
 
-macro_rules! gather {
-    ($values:expr, $coord_vec:expr) => (
-        match $coord_vec.len() > 0 {
-            true => Option::Some(($coord_vec).iter().map(|x| &mut $values[*x] as *mut i8).collect()),
-            false => Option::None
-        }
-    );
+/// Cells of the row through `row`, `col` which can be part of a window of `len` containing it.
+fn h_tup_seq(row:usize, col:usize, len:usize) -> Vec<(usize, usize)> {
+    let start = col.saturating_sub(len - 1);
+    let end = min(WIDTH, col + len);
+    (start..end).map(|c| (row, c)).collect()
 }
 
-macro_rules! h_tup_seq {
-    ($row:expr, $col:expr) => ({
-        let start:usize = max(0, ($col as i8)-3) as usize;
-        let end = min(WIDTH, $col+4);
-        (start..end).map(|c| ($row, c as usize)).collect::<Vec<(usize, usize)>>()
-    });
+/// Cells of the column through `row`, `col`, see `h_tup_seq`.
+fn v_tup_seq(row:usize, col:usize, len:usize) -> Vec<(usize, usize)> {
+    let start = row.saturating_sub(len - 1);
+    let end = min(HEIGHT, row + len);
+    (start..end).map(|r| (r, col)).collect()
 }
 
-macro_rules! v_tup_seq {
-    ($row:expr, $col:expr) => ({
-        let start:usize = max(0, ($row as i8)-3) as usize;
-        let end = min(HEIGHT, $row+4);
-        (start..end).map(|r| (r as usize, $col)).collect::<Vec<(usize, usize)>>()
-    });
+/// Cells of the rising diagonal through `row`, `col`, empty if it is shorter than `len`.
+fn rdiag_tup_seq(row:usize, col:usize, len:usize) -> Vec<(usize, usize)> {
+    let d = min(min(row, col), len - 1);
+    let mut r = row - d;
+    let mut c = col - d;
+
+    let mut values: Vec<(usize, usize)> = Vec::new();
+    for _ in 0..d+len {
+        if r >= HEIGHT || c >= WIDTH {
+            break;
+        }
+        values.push((r, c));
+        r += 1;
+        c += 1;
+    }
+
+    if values.len() < len {
+        values.clear();
+    }
+    values
 }
 
-macro_rules! rdiag_tup_seq {
-    ($row:expr, $col:expr) => ({
-        let d = min(min($row, $col), 3);
-        let mut r = $row - d;
-        let mut c = $col - d;
+/// Cells of the falling diagonal through `row`, `col`, see `rdiag_tup_seq`.
+fn ldiag_tup_seq(row:usize, col:usize, len:usize) -> Vec<(usize, usize)> {
+    rdiag_tup_seq(row, WIDTH-1-col, len).iter().map(|(r,c)| (*r, WIDTH-1-c)).collect()
+}
 
-        let mut values: Vec<(usize, usize)> = Vec::new();
-        for _ in 0..d+4 {
-            if r >= HEIGHT || c >= WIDTH {
-                break;
-            }
-            values.push((r, c));
-            r += 1;
-            c += 1;
-        }
+/// Cells of the row through `row`, `col` on a cylinder, where the columns wrap around the edges.
+fn h_wrapped_seq(row:usize, col:usize, len:usize) -> Vec<(usize, usize)> {
+    (0..2*len-1).map(|i| (row, (col + WIDTH + i - (len - 1)) % WIDTH)).collect()
+}
 
-        if values.len() < 4 {
-            values.clear();
-        }
-        values
-    });
+/// Cells of the diagonal through `row`, `col` on a cylinder, rising to the right for `dc` 1 and to the left for -1.
+/// Rows do not wrap, so the diagonal is empty if it is shorter than `len`.
+fn diag_wrapped_seq(row:usize, col:usize, len:usize, dc:isize) -> Vec<(usize, usize)> {
+    let below = min(row, len - 1) as isize;
+    let above = min(HEIGHT - 1 - row, len - 1) as isize;
+    if below + above + 1 < len as isize {
+        return Vec::new();
+    }
+    (-below..=above).map(|i| ((row as isize + i) as usize, (col as isize + dc * i).rem_euclid(WIDTH as isize) as usize)).collect()
 }
 
-macro_rules! ldiag_tup_seq {
-    ($row:expr, $col:expr) => ({
-        let col_ = WIDTH-1-$col;
-        let values:Vec<(usize, usize)> = rdiag_tup_seq!($row, col_).iter().map(|(r,c)| (*r, WIDTH-1-c)).collect();
-        values
-    });
+/// The lines through a cell which can hold a win: the column, the row and both diagonals.
+fn tup_seqs(row:usize, col:usize, rules:Rules) -> [Vec<(usize, usize)>; 4] {
+    let len = rules.win_length;
+    match rules.variant {
+        Variant::Standard | Variant::Misere => [v_tup_seq(row, col, len), h_tup_seq(row, col, len), rdiag_tup_seq(row, col, len), ldiag_tup_seq(row, col, len)],
+        Variant::Cylinder => [v_tup_seq(row, col, len), h_wrapped_seq(row, col, len), diag_wrapped_seq(row, col, len, 1), diag_wrapped_seq(row, col, len, -1)],
+    }
 }
 
-fn check(val:i8, values:&Vec<*mut i8>) -> u8 {
+/// The most pieces of `val` in a window of `len` of the sequence which holds none of the opponent.
+fn check(val:i8, values:&Array2D<i8>, seq:&[(usize, usize)], len:usize) -> u8 {
     let mut best_score: u8 = 0;
-    for i in 4..=values.len() {
+    for i in len..=seq.len() {
         let mut score: u8 = 0;
 
-        for v_ref in values[i-4..i].iter() {
-            unsafe {
-                let v = *(*v_ref);
-                if v == -val {
-                    score = 0;
-                    break;
-                }
+        for cell in seq[i-len..i].iter() {
+            let v = values[*cell];
+            if v == -val {
+                score = 0;
+                break;
+            }
 
-                if v == val {
-                    score += 1;
-                }
+            if v == val {
+                score += 1;
             }
         }
         best_score = max(score, best_score);
@@ -97,9 +132,39 @@ fn check(val:i8, values:&Vec<*mut i8>) -> u8 {
     best_score
 }
 
+/// Counts the cells of a window held by `val` and by its opponent.
+fn count_window(val:i8, values:&Array2D<i8>, window:&[(usize, usize)]) -> (u8, u8) {
+    let mut own: u8 = 0;
+    let mut other: u8 = 0;
+    for cell in window.iter() {
+        let v = values[*cell];
+        if v == val {
+            own += 1;
+        } else if v == -val {
+            other += 1;
+        }
+    }
+    (own, other)
+}
+
+/// Converts a score of the search to points.
+pub fn to_points(score:Score) -> f32 {
+    score as f32 / SCORE_SCALE as f32
+}
+
+/// Think time of a level, which is given in tenths of a second.
+pub fn level_time_millis(level:u8) -> u128 {
+    100 * level as u128
+}
+
+/// Converts points, e.g. weights or settings, to a score of the search.
+pub fn to_score(points:f32) -> Score {
+    (points * SCORE_SCALE as f32).round() as Score
+}
+
 #[derive(Clone)]
 pub struct Eval {
-    pub score: f32,
+    pub score: Score,
     pub finished: bool,
     pub winner: Option<i8>,
 }
@@ -109,66 +174,349 @@ pub struct ActionEvaluation {
     pub winning_cells: Option<Vec<(usize, usize)>>,
 }
 
-struct ConnectFour {
-    current_player: i8,
-    values: Array2D<i8>,
-    col_heights: [usize; WIDTH],
-    evaluation_result: Option<Eval>,
-    set_fields: usize,
-    last_action: Option<usize>,
+/// Scores the position reached by the last move played into `col`.
+/// The search only sees the returned `Eval`, so evaluators can be exchanged without touching minimax.
+pub trait Evaluator: Send {
+    fn evaluate(&self, game:&ConnectFour, col:usize) -> Eval;
 
-    /**
-     * when acessing field sequences[(1,2)], a vector containing sequences of references to cells obtained.
-     * for each sequence of the vector, its references are to be iterated and checked for victory condition (four in a row).
-     */
-    sequences: Array2D<Vec<Vec<*mut i8>>>,
+    /// A copy for a forked game, see `ConnectFour::fork`.
+    fn fork(&self) -> Box<dyn Evaluator>;
 }
 
-impl ConnectFour {
-    fn calculate_state(&self, col:usize) -> Eval {
-        let row = self.col_heights[col] - 1;
-        let val = self.values[(row, col)];
-        let mut total_score = 0.;
+/// Available evaluation backends, selectable per game. Further backends, e.g. a neural network or an endgame
+/// tablebase, implement `Evaluator`, get a variant here and are created in `build` and parsed in `from_str`; the
+/// search only calls the `Evaluator` methods and needs no change. `build` cannot fail, so a backend which needs a
+/// model or tables loads them once beforehand and shares them with the evaluators it builds, e.g. through an `Arc`.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub enum EvaluatorKind {
+    #[default]
+    Heuristic,
+    Threats,
+    Windows,
+}
+
+impl EvaluatorKind {
+    pub fn build(&self, weights:&EvalWeights) -> Box<dyn Evaluator> {
+        match self {
+            EvaluatorKind::Heuristic => Box::new(HeuristicEvaluator { weights: weights.into() }),
+            EvaluatorKind::Threats => Box::new(ThreatEvaluator { weights: weights.into() }),
+            EvaluatorKind::Windows => Box::new(WindowEvaluator { weights: weights.into() }),
+        }
+    }
+}
+
+impl FromStr for EvaluatorKind {
+    type Err = String;
+
+    fn from_str(s:&str) -> Result<EvaluatorKind, String> {
+        match s.to_lowercase().as_str() {
+            "heuristic" => Ok(EvaluatorKind::Heuristic),
+            "threats" => Ok(EvaluatorKind::Threats),
+            "windows" => Ok(EvaluatorKind::Windows),
+            e => Err(format!("unknown evaluator {}", e)),
+        }
+    }
+}
+
+/// Board variants, see `Rules`.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub enum Variant {
+    #[default]
+    Standard,
+    /// Rows and diagonals wrap around the left and right edges of the board.
+    Cylinder,
+    /// Completing a row loses the game.
+    Misere,
+}
+
+/// Rules of the variant played.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct Rules {
+    /// Pieces in a row needed to win, from `MIN_WIN_LENGTH` to `MAX_WIN_LENGTH`.
+    pub win_length: usize,
+    #[serde(default)]
+    pub variant: Variant,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules { win_length: WIN_LENGTH, variant: Variant::Standard }
+    }
+}
+
+impl Rules {
+    pub fn validate(&self) -> Result<(), String> {
+        match (MIN_WIN_LENGTH..=MAX_WIN_LENGTH).contains(&self.win_length) {
+            true => Ok(()),
+            false => Err(format!("win length must be between {} and {}", MIN_WIN_LENGTH, MAX_WIN_LENGTH)),
+        }
+    }
+
+    /// The player winning the game when `owner` completes a row.
+    fn winner(&self, owner:i8) -> i8 {
+        match self.variant {
+            Variant::Misere => -owner,
+            Variant::Standard | Variant::Cylinder => owner,
+        }
+    }
+}
+
+/// Tunable weights shared by the evaluators.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+pub struct EvalWeights {
+    pub col_bonus: [f32; WIDTH],
+    pub open_two: f32,
+    pub open_three: f32,
+    pub blocked_three: f32,
+}
+
+impl Default for EvalWeights {
+    fn default() -> EvalWeights {
+        EvalWeights {
+            col_bonus: [0., 0.5, 1.0, 1.5, 1.0, 0.5, 0.],
+            open_two: 1.,
+            open_three: 4.,
+            blocked_three: 3.,
+        }
+    }
+}
+
+impl EvalWeights {
+    pub const LEN:usize = WIDTH + 3;
+
+    /// Flattens the weights into a parameter vector, e.g. for tuning.
+    pub fn to_vec(self) -> Vec<f32> {
+        let mut params = self.col_bonus.to_vec();
+        params.extend([self.open_two, self.open_three, self.blocked_three]);
+        params
+    }
+
+    pub fn from_slice(params:&[f32]) -> EvalWeights {
+        assert_eq!(params.len(), EvalWeights::LEN, "unexpected number of weights");
+        let mut col_bonus = [0.; WIDTH];
+        col_bonus.copy_from_slice(&params[..WIDTH]);
+        EvalWeights {
+            col_bonus,
+            open_two: params[WIDTH],
+            open_three: params[WIDTH + 1],
+            blocked_three: params[WIDTH + 2],
+        }
+    }
+
+    /// Rejects weights which are not finite or would reach the score of a won game.
+    pub fn validate(&self) -> Result<(), String> {
+        let limit = to_points(MAX_SCORE) / 4.;
+        match self.to_vec().iter().all(|w| w.is_finite() && w.abs() < limit) {
+            true => Ok(()),
+            false => Err(format!("weights must be finite and within +-{}", limit))
+        }
+    }
+
+    pub fn load(path:&Path) -> Result<EvalWeights, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let weights: EvalWeights = toml::from_str(&content).map_err(|e| e.to_string())?;
+        weights.validate()?;
+        Ok(weights)
+    }
+
+    pub fn save(&self, path:&Path) -> Result<(), String> {
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, content).map_err(|e| e.to_string())
+    }
+}
+
+/// `EvalWeights` converted to scores once, so evaluating a position takes no float arithmetic.
+#[derive(Clone, Copy)]
+struct FixedWeights {
+    col_bonus: [Score; WIDTH],
+    open_two: Score,
+    open_three: Score,
+    blocked_three: Score,
+}
+
+impl FixedWeights {
+    /// On a cylinder no column is closer to the center than another, so all get the same bonus.
+    fn col_bonus(&self, rules:Rules, col:usize) -> Score {
+        match rules.variant {
+            Variant::Cylinder => 0,
+            Variant::Standard | Variant::Misere => self.col_bonus[col],
+        }
+    }
+}
+
+impl From<&EvalWeights> for FixedWeights {
+    fn from(weights:&EvalWeights) -> FixedWeights {
+        FixedWeights {
+            col_bonus: weights.col_bonus.map(to_score),
+            open_two: to_score(weights.open_two),
+            open_three: to_score(weights.open_three),
+            blocked_three: to_score(weights.blocked_three),
+        }
+    }
+}
+
+/// The original hand-crafted heuristic: longest own run per line through the played cell plus a column bonus.
+pub struct HeuristicEvaluator {
+    weights: FixedWeights,
+}
+
+impl Evaluator for HeuristicEvaluator {
+    fn evaluate(&self, game:&ConnectFour, col:usize) -> Eval {
+        let row = game.col_heights[col] - 1;
+        let val = game.values[(row, col)];
+        let win_length = game.rules.win_length;
+        let mut total_score = 0;
         let mut len: u8 = 0;
-        for seq in self.sequences[(row, col)].iter() {
-            let score = check(val, seq);
+        for seq in game.sequences[(row, col)].iter() {
+            let score = check(val, &game.values, seq, win_length);
             if score > 0 {
                 len += 1;
             }
-            if score > 3 {
-                return Eval {
-                    score: MAX_SCORE * val as f32,
-                    finished: true,
-                    winner: Some(val)
-                };
+            if score as usize >= win_length {
+                return Eval::won(val);
             }
-            total_score += score as f32;
+            total_score += score as Score * SCORE_SCALE;
         }
         
         // make sure the played field itself counts as only 1
         if len > 1 {
-            total_score -= (len - 1) as f32;
+            total_score -= (len - 1) as Score * SCORE_SCALE;
         }
-        total_score += COL_BONUS[col];
-        total_score *= val as f32;
+        total_score += self.weights.col_bonus(game.rules, col);
+        total_score *= val as Score;
         Eval {
             score: total_score,
-            finished: self.set_fields >= TOTAL_FIELDS,
+            finished: game.set_fields >= TOTAL_FIELDS,
+            winner: None
+        }
+    }
+
+    fn fork(&self) -> Box<dyn Evaluator> {
+        Box::new(HeuristicEvaluator { weights: self.weights })
+    }
+}
+
+/// Counts open twos and threes created through the played cell as well as opponent threes it blocks.
+/// With another win length, threes are windows one piece short of a win and twos two pieces short.
+pub struct ThreatEvaluator {
+    weights: FixedWeights,
+}
+
+impl Evaluator for ThreatEvaluator {
+    fn evaluate(&self, game:&ConnectFour, col:usize) -> Eval {
+        let row = game.col_heights[col] - 1;
+        let val = game.values[(row, col)];
+        let len = game.rules.win_length;
+        let mut total_score = self.weights.col_bonus(game.rules, col);
+        for seq in game.sequences[(row, col)].iter() {
+            for window in seq.windows(len) {
+                let (own, other) = count_window(val, &game.values, window);
+                total_score += match (own as usize, other as usize) {
+                    (own, _) if own == len => return Eval::won(val),
+                    (own, 0) if own == len - 1 => self.weights.open_three,
+                    (own, 0) if own == len - 2 => self.weights.open_two,
+                    (1, other) if other == len - 1 => self.weights.blocked_three,
+                    _ => 0
+                };
+            }
+        }
+        Eval {
+            score: total_score.min(MAX_SCORE - 1) * val as Score,
+            finished: game.set_fields >= TOTAL_FIELDS,
+            winner: None
+        }
+    }
+
+    fn fork(&self) -> Box<dyn Evaluator> {
+        Box::new(ThreatEvaluator { weights: self.weights })
+    }
+}
+
+/// Scores the whole board by its open twos and threes, see `ThreatEvaluator` for other win lengths.
+/// The windows are counted incrementally by `ConnectFour`, so evaluating a position takes constant time.
+pub struct WindowEvaluator {
+    weights: FixedWeights,
+}
+
+impl Evaluator for WindowEvaluator {
+    fn evaluate(&self, game:&ConnectFour, _col:usize) -> Eval {
+        let len = game.rules.win_length;
+        for val in [P1, P2] {
+            if game.open_windows(val, len) > 0 {
+                return Eval::won(val);
+            }
+        }
+        let balance = |pieces| (game.open_windows(P1, pieces) - game.open_windows(P2, pieces)) as Score;
+        let score = self.weights.open_two * balance(len - 2) + self.weights.open_three * balance(len - 1);
+        Eval {
+            score: score.clamp(1 - MAX_SCORE, MAX_SCORE - 1),
+            finished: game.set_fields >= TOTAL_FIELDS,
             winner: None
         }
     }
 
+    fn fork(&self) -> Box<dyn Evaluator> {
+        Box::new(WindowEvaluator { weights: self.weights })
+    }
+}
+
+pub struct ConnectFour {
+    current_player: i8,
+    values: Array2D<i8>,
+    col_heights: [usize; WIDTH],
+    evaluation_result: Option<Eval>,
+    set_fields: usize,
+    last_action: Option<usize>,
+
+    /**
+     * when acessing field sequences[(1,2)], a vector containing sequences of cells obtained.
+     * for each sequence of the vector, its cells are to be iterated and checked for victory condition (four in a row).
+     */
+    sequences: Array2D<Vec<Vec<(usize, usize)>>>,
+    rules: Rules,
+    evaluator: Box<dyn Evaluator>,
+    /// A column the given player must not play, see `ban_column`.
+    banned: Option<(i8, usize)>,
+    /// Zobrist hash of the pieces and the player to move, updated incrementally.
+    hash: u64,
+    /// Pieces of player 1 and 2 in each window of `board_windows`, updated incrementally.
+    window_counts: Vec<[u8; 2]>,
+    /// Number of windows holding pieces of only one player, by player and number of pieces.
+    open_windows: [[i16; MAX_WIN_LENGTH + 1]; 2],
+}
+
+impl Eval {
+    fn won(val:i8) -> Eval {
+        Eval {
+            score: MAX_SCORE * val as Score,
+            finished: true,
+            winner: Some(val)
+        }
+    }
+}
+
+impl ConnectFour {
     fn eval(&mut self) -> Eval {
         match &self.evaluation_result {
             Some(res) => res.clone(),
             None => {
                 self.last_action.map_or(
                     Eval {
-                        score: 0.,
+                        score: 0,
                         winner: None,
                         finished: false,
                     },
-                    |a| self.calculate_state(a)
+                    |a| {
+                        // the evaluators score rows for their owner, which loses them in misère games
+                        let eval = self.evaluator.evaluate(self, a);
+                        match eval.winner {
+                            Some(owner) if self.rules.variant == Variant::Misere => Eval::won(self.rules.winner(owner)),
+                            _ => eval,
+                        }
+                    }
                 )
             }
         }
@@ -176,8 +524,8 @@ impl ConnectFour {
 }
 
 impl Environment for ConnectFour {
-    fn evaluate(&mut self) -> f32 {
-        (self.current_player as f32) * self.eval().score
+    fn evaluate(&mut self) -> Score {
+        (self.current_player as Score) * self.eval().score
     }
  
     fn apply(&mut self, action:&usize) {        
@@ -185,6 +533,8 @@ impl Environment for ConnectFour {
         let h = self.col_heights[col];
 
         self.values[(h, col)] = self.current_player;
+        self.hash ^= zobrist_key(h, col, self.current_player);
+        self.count_windows(h, col, self.current_player, 1);
 
         self.col_heights[col] = h + 1;
         self.set_fields += 1;
@@ -197,6 +547,8 @@ impl Environment for ConnectFour {
         let col = *action;
         let h = self.col_heights[col] - 1;
 
+        self.hash ^= zobrist_key(h, col, self.values[(h, col)]);
+        self.count_windows(h, col, self.values[(h, col)], -1);
         self.values[(h, col)] = 0;
 
         self.col_heights[col] = h;
@@ -211,27 +563,204 @@ impl Environment for ConnectFour {
     }
     
     fn actions(&self) -> Vec<usize> {
-        FIELDS.iter().filter_map(|i| match self.col_heights[*i] < HEIGHT {
+        let actions: Vec<usize> = FIELDS.iter().filter_map(|i| match self.col_heights[*i] < HEIGHT {
             false => Option::None,
             true => Option::Some(*i)
-        }).collect()
+        }).collect();
+        match self.banned {
+            Some((player, col)) if player == self.current_player && actions.iter().any(|a| *a != col) => {
+                actions.into_iter().filter(|a| *a != col).collect()
+            },
+            _ => actions
+        }
     }
     
     fn swap_players(&mut self) {
         self.current_player *= -1;
+        self.hash ^= SIDE_KEY;
+    }
+
+    fn state_hash(&self) -> Option<u64> {
+        Some(self.hash)
+    }
+
+    /// Winning moves first, then moves blocking a win of the opponent, otherwise center first.
+    fn ordered_actions(&self) -> Vec<usize> {
+        let player = self.current_player;
+        let mut actions = self.actions();
+        actions.sort_by_cached_key(|col| match (self.wins_with(player, *col), self.wins_with(-player, *col)) {
+            (true, _) => 0,
+            (false, true) => 1,
+            _ => 2,
+        });
+        actions
+    }
+
+    fn is_draw(&mut self) -> bool {
+        let eval = self.eval();
+        eval.finished && eval.winner.is_none()
     }
+
+    /// Threats which already existed before the last action are not counted, otherwise
+    /// every move ignoring a threat would extend the search again.
+    fn is_forcing(&mut self) -> bool {
+        let Some(last) = self.last_action else {
+            return false;
+        };
+        let player = self.current_player;
+        let last_cell = (self.col_heights[last] - 1, last);
+        self.actions().into_iter().filter(|col| *col != last).any(|col| {
+            if !self.wins_with(player, col) {
+                return false;
+            }
+            self.values[last_cell] = 0;
+            let existed = self.wins_with(player, col);
+            self.values[last_cell] = player;
+            !existed
+        }) || (self.col_heights[last] < HEIGHT && self.wins_with(player, last))
+    }
+
+    fn split(&self) -> Option<Box<dyn Environment + Send>> {
+        Some(Box::new(self.fork()))
+    }
+}
+
+impl ForkableEnvironment for ConnectFour {
+    fn fork(&self) -> Self {
+        let mut fork = ConnectFour::build(self.values.clone(), self.current_player, self.evaluator.fork(), self.rules);
+        fork.evaluation_result = self.evaluation_result.clone();
+        fork.last_action = self.last_action;
+        fork.banned = self.banned;
+        fork
+    }
+}
+
+/// Cells of each window, see `board_windows`.
+type Windows = Vec<Vec<(usize, usize)>>;
+
+/// Cells of every possible row of the win length.
+fn board_windows(rules:Rules) -> &'static [Vec<(usize, usize)>] {
+    static WINDOWS: [[OnceLock<Windows>; MAX_WIN_LENGTH + 1]; 2] = [const { [const { OnceLock::new() }; MAX_WIN_LENGTH + 1] }; 2];
+    let (len, wrap) = (rules.win_length, rules.variant == Variant::Cylinder);
+    WINDOWS[wrap as usize][len].get_or_init(|| {
+        let cell = |row:usize, col:usize, (dr, dc):(isize, isize), i:isize| {
+            let (r, mut c) = (row as isize + dr * i, col as isize + dc * i);
+            if wrap {
+                c = c.rem_euclid(WIDTH as isize);
+            }
+            ((0..HEIGHT as isize).contains(&r) && (0..WIDTH as isize).contains(&c)).then_some((r as usize, c as usize))
+        };
+        let mut windows = Vec::new();
+        for row in 0..HEIGHT {
+            for col in 0..WIDTH {
+                for direction in [(0, 1), (1, 0), (1, 1), (1, -1)] {
+                    if let Some(window) = (0..len as isize).map(|i| cell(row, col, direction, i)).collect() {
+                        windows.push(window);
+                    }
+                }
+            }
+        }
+        windows
+    })
+}
+
+/// Indices into `board_windows` of the windows containing a cell.
+fn cell_windows(rules:Rules) -> &'static Array2D<Vec<usize>> {
+    static CELL_WINDOWS: [[OnceLock<Array2D<Vec<usize>>>; MAX_WIN_LENGTH + 1]; 2] = [const { [const { OnceLock::new() }; MAX_WIN_LENGTH + 1] }; 2];
+    let (len, wrap) = (rules.win_length, rules.variant == Variant::Cylinder);
+    CELL_WINDOWS[wrap as usize][len].get_or_init(|| {
+        let mut cells = Array2D::filled_with(Vec::new(), HEIGHT, WIDTH);
+        for (i, window) in board_windows(rules).iter().enumerate() {
+            window.iter().for_each(|cell| cells[*cell].push(i));
+        }
+        cells
+    })
+}
+
+/// Pseudo random key of a piece of `player` on a field, derived by splitmix64 from the field index
+/// so no table has to be set up for every board.
+fn zobrist_key(row:usize, col:usize, player:i8) -> u64 {
+    let index = (row * WIDTH + col) * 2 + (player == P1) as usize;
+    let mut z = (index as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
 }
 
 impl ConnectFour {
-    pub fn new(values: Option<Array2D<i8>>, current_player:i8) -> ConnectFour {
+    /// Number of windows holding `pieces` pieces of `player` and none of the opponent, the win length for completed ones.
+    pub fn open_windows(&self, player:i8, pieces:usize) -> i16 {
+        self.open_windows[(player != P1) as usize][pieces]
+    }
+
+    /// Adds (`change` 1) or removes (`change` -1) the piece of `player` at `row`, `col` from the window counts.
+    fn count_windows(&mut self, row:usize, col:usize, player:i8, change:i8) {
+        let p = (player != P1) as usize;
+        for window in cell_windows(self.rules)[(row, col)].iter() {
+            self.tally_window(*window, -1);
+            self.window_counts[*window][p] = (self.window_counts[*window][p] as i8 + change) as u8;
+            self.tally_window(*window, 1);
+        }
+    }
+
+    fn tally_window(&mut self, window:usize, sign:i16) {
+        match self.window_counts[window] {
+            [0, 0] => {},
+            [n, 0] => self.open_windows[0][n as usize] += sign,
+            [0, n] => self.open_windows[1][n as usize] += sign,
+            _ => {},
+        }
+    }
+
+    /// Whether `val` would complete a row of the win length by playing into `col`, which must not be full.
+    fn wins_with(&self, val:i8, col:usize) -> bool {
+        let row = self.col_heights[col];
+        self.sequences[(row, col)].iter().any(|seq| seq.windows(self.rules.win_length).any(|window| {
+            window.iter().all(|cell| *cell == (row, col) || self.values[*cell] == val)
+        }))
+    }
+
+    /// Sets up a game from a possibly untrusted board, rejecting boards which cannot occur in a real game. Player 1
+    /// got `handicap` pieces more than player 2 before the first move, negative if player 2 got more.
+    pub fn new(values: Option<Array2D<i8>>, current_player:i8, handicap:i8, evaluator:Box<dyn Evaluator>) -> Result<ConnectFour, BoardError> {
+        match values {
+            Some(values) => {
+                validate_board(&values, current_player, handicap)?;
+                Ok(ConnectFour::build(values, current_player, evaluator, Rules::default()))
+            },
+            None if current_player == P1 || current_player == P2 => Ok(ConnectFour::empty(current_player, evaluator)),
+            None => Err(BoardError::Player(current_player))
+        }
+    }
+
+    /// An empty board. `current_player` has to be `P1` or `P2`.
+    pub fn empty(current_player:i8, evaluator:Box<dyn Evaluator>) -> ConnectFour {
+        ConnectFour::build(Array2D::filled_with(0, HEIGHT, WIDTH), current_player, evaluator, Rules::default())
+    }
+
+    /// The same position played by other rules. The rules have to be valid, see `Rules::validate`.
+    pub fn with_rules(self, rules:Rules) -> ConnectFour {
+        let mut game = ConnectFour::build(self.values, self.current_player, self.evaluator, rules);
+        game.last_action = self.last_action;
+        game.banned = self.banned;
+        game
+    }
+
+    fn build(values: Array2D<i8>, current_player:i8, evaluator:Box<dyn Evaluator>, rules:Rules) -> ConnectFour {
         let mut p = ConnectFour {
             current_player: current_player,
-            values: values.unwrap_or(Array2D::filled_with(0, HEIGHT, WIDTH)),
+            evaluator,
+            values,
             col_heights: [0; WIDTH],
             sequences: Array2D::filled_with(vec![vec![]], HEIGHT, WIDTH),
+            rules,
             evaluation_result: Option::None,
             set_fields: 0,
-            last_action: Option::None
+            last_action: Option::None,
+            banned: None,
+            hash: if current_player == P2 { SIDE_KEY } else { 0 },
+            window_counts: vec![[0, 0]; board_windows(rules).len()],
+            open_windows: [[0; MAX_WIN_LENGTH + 1]; 2],
         };
 
         for row in 0..HEIGHT {
@@ -239,46 +768,360 @@ impl ConnectFour {
                 if p.values[(row,col)] != 0 {
                     p.col_heights[col] += 1;
                     p.set_fields += 1;
+                    p.hash ^= zobrist_key(row, col, p.values[(row,col)]);
+                    p.count_windows(row, col, p.values[(row,col)], 1);
                 }
 
-                let mut sequences = Vec::new();
-                gather!(p.values, v_tup_seq!(row,col)).map(|refs| sequences.push(refs));
-                gather!(p.values, h_tup_seq!(row,col)).map(|refs| sequences.push(refs));
-                gather!(p.values, rdiag_tup_seq!(row,col)).map(|refs| sequences.push(refs));
-                gather!(p.values, ldiag_tup_seq!(row,col)).map(|refs| sequences.push(refs));
-
-                p.sequences[(row,col)] = sequences;
+                p.sequences[(row,col)] = tup_seqs(row, col, rules).into_iter().filter(|seq| !seq.is_empty()).collect();
             }
         }
         p
     }
+
+    /// Returns the winner by a row of the win length anywhere on the board.
+    fn winner_on_board(&self) -> Option<i8> {
+        let len = self.rules.win_length;
+        for row in 0..HEIGHT {
+            for col in 0..WIDTH {
+                let val = self.values[(row, col)];
+                if val != 0 && self.sequences[(row, col)].iter().any(|seq| check(val, &self.values, seq, len) as usize >= len) {
+                    return Some(self.rules.winner(val));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Reasons for rejecting a board passed into the engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardError {
+    Dimensions { rows: usize, cols: usize },
+    CellValue { row: usize, col: usize, value: i8 },
+    FloatingPiece { row: usize, col: usize },
+    PieceCount { p1: usize, p2: usize },
+    Player(i8),
+    NotPlayersTurn(i8),
+    AlreadyDecided(i8),
+    ColumnFull(usize),
+    /// The column of a move to evaluate does not exist or holds no piece.
+    InvalidColumn(usize),
 }
 
-pub fn evaluate_state(values: Option<Array2D<i8>>, current_player:i8, level:u8, randomized:bool) -> Result<StateEvaluation,String> {
-    let mut g = ConnectFour::new(values, current_player);
-    let config = Config::new(
-        Some(100*(level as u128)),
-        None,
-        randomized,
-        MIN_SCORE,
-        EPSILON
-    );
-    match g.current_player {
-        P1 => maximize(&mut g, &config).ok_or("Player 1 has no legal move.".into()),
-        P2 => minimize(&mut g, &config).ok_or("Player 2 has no legal move.".into()),
-        _ => Err("unknown player".into())
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardError::Dimensions { rows, cols } => write!(f, "board must be {}x{}, got {}x{}", HEIGHT, WIDTH, rows, cols),
+            BoardError::CellValue { row, col, value } => write!(f, "invalid value {} in cell ({}, {})", value, row, col),
+            BoardError::FloatingPiece { row, col } => write!(f, "piece in cell ({}, {}) is not supported", row, col),
+            BoardError::PieceCount { p1, p2 } => write!(f, "impossible piece count: {} for player 1, {} for player 2", p1, p2),
+            BoardError::Player(player) => write!(f, "unknown player {}", player),
+            BoardError::NotPlayersTurn(player) => write!(f, "it is not the turn of player {}", player),
+            BoardError::AlreadyDecided(winner) => write!(f, "player {} has already won", winner),
+            BoardError::ColumnFull(col) => write!(f, "column {} is full or does not exist", col),
+            BoardError::InvalidColumn(col) => write!(f, "column {} does not exist or holds no piece", col),
+        }
+    }
+}
+
+impl From<BoardError> for String {
+    fn from(e:BoardError) -> String {
+        e.to_string()
+    }
+}
+
+/// Checks that `values` is a board reachable by alternating moves with `current_player` to move next, after player 1
+/// was given `handicap` pieces more than player 2.
+pub fn validate_board(values:&Array2D<i8>, current_player:i8, handicap:i8) -> Result<(), BoardError> {
+    if values.num_rows() != HEIGHT || values.num_columns() != WIDTH {
+        return Err(BoardError::Dimensions { rows: values.num_rows(), cols: values.num_columns() });
+    }
+    if current_player != P1 && current_player != P2 {
+        return Err(BoardError::Player(current_player));
+    }
+
+    let (mut p1, mut p2) = (0, 0);
+    for col in 0..WIDTH {
+        for row in 0..HEIGHT {
+            match values[(row, col)] {
+                0 => {},
+                P1 => p1 += 1,
+                P2 => p2 += 1,
+                value => return Err(BoardError::CellValue { row, col, value }),
+            }
+            if row > 0 && values[(row, col)] != 0 && values[(row - 1, col)] == 0 {
+                return Err(BoardError::FloatingPiece { row, col });
+            }
+        }
+    }
+
+    // either player may have started, so the counts differ by at most one and the player behind moves next
+    match p1 as i32 - p2 as i32 - handicap as i32 {
+        0 => Ok(()),
+        1 if current_player == P2 => Ok(()),
+        -1 if current_player == P1 => Ok(()),
+        1 | -1 => Err(BoardError::NotPlayersTurn(current_player)),
+        _ => Err(BoardError::PieceCount { p1, p2 }),
     }
 }
 
-pub fn evaluate_action(values: Option<Array2D<i8>>, current_player:i8, action:usize) -> ActionEvaluation {
+impl ConnectFour {
+    /// Plays `col` for the current player and hands the turn over to the opponent.
+    pub fn play(&mut self, col:usize) {
+        self.apply(&col);
+        self.swap_players();
+    }
+
+    pub fn current_player(&self) -> i8 {
+        self.current_player
+    }
+
+    /// Forbids `player` to play into `col` for the rest of the game, unless it is the only column left.
+    pub fn ban_column(&mut self, player:i8, col:usize) {
+        self.banned = Some((player, col));
+    }
+
+    pub fn set_evaluator(&mut self, evaluator:Box<dyn Evaluator>) {
+        self.evaluator = evaluator;
+        self.evaluation_result = None;
+    }
+
+    /// Static score of the current position in points from the perspective of player 1.
+    pub fn score(&mut self) -> f32 {
+        to_points(self.eval().score)
+    }
+
+    /// Returns the outcome of the last move, `None` as long as the game is running.
+    pub fn outcome(&mut self) -> Option<Option<i8>> {
+        let eval = self.eval();
+        match eval.finished {
+            true => Some(eval.winner),
+            false => None
+        }
+    }
+
+    /// Searches the current position up to `max_depth` plies for the player to move.
+    /// Returns `None` if the game is finished or `max_depth` is 0.
+    pub fn search(&mut self, max_depth:u8, randomized:bool) -> Option<StateEvaluation> {
+        self.search_within(Some(max_depth), None, randomized).ok()
+    }
+
+    /// Searches the current position until the depth or the time limit is reached, at least one of them is needed.
+    pub fn search_within(&mut self, max_depth:Option<u8>, time_limit_millis:Option<u128>, randomized:bool) -> Result<StateEvaluation, String> {
+        let mut builder = ConfigBuilder::new(MIN_SCORE, EPSILON)
+            .randomized(randomized)
+            .temperature(minimax::DEFAULT_TEMPERATURE * SCORE_SCALE as f32);
+        if let Some(depth) = max_depth {
+            builder = builder.max_depth(depth);
+        }
+        if let Some(millis) = time_limit_millis {
+            builder = builder.time_limit(millis);
+        }
+        let config = builder.build().map_err(|e| e.to_string())?;
+        let result = match self.current_player {
+            P1 => maximize(self, &config),
+            _ => minimize(self, &config),
+        };
+        result.ok_or("the game is already finished".into())
+    }
+
+
+    /// Counts the positions reachable in exactly `depth` plies. Finished games are counted but not expanded.
+    pub fn perft(&mut self, depth:u8) -> u64 {
+        if depth == 0 || self.is_finished() {
+            return 1;
+        }
+
+        let mut nodes = 0;
+        for col in self.actions() {
+            self.play(col);
+            nodes += self.perft(depth - 1);
+            self.swap_players();
+            self.revert(&col);
+        }
+        nodes
+    }
+}
+
+/// Options of a single search started by `evaluate_state_async` or `prepare_search`.
+pub struct SearchOptions {
+    /// Think time in tenths of a second.
+    pub level: u8,
+    /// Caps the think time of the level, e.g. to the share of a game clock.
+    pub time_limit_millis: Option<u128>,
+    /// Caps the depth in addition to the think time.
+    pub max_depth: Option<u8>,
+    pub randomized: bool,
+    /// In points, see `ConfigBuilder::temperature`.
+    pub temperature: f32,
+    pub evaluator: Box<dyn Evaluator>,
+    /// Called when a depth starts and after it completed, see `Config::set_on_progress`.
+    pub on_progress: Option<Box<dyn Fn(Progress) + Send>>,
+    /// Number of best root moves to report in `StateEvaluation::top_moves`.
+    pub multi_pv: usize,
+    /// Aborts the search once set, see `Config::set_cancel_token`.
+    pub cancel: Option<CancelToken>,
+    /// See `Config::set_node_budget`.
+    pub max_nodes: Option<u128>,
+    /// Threads searching in parallel, see `Config::set_threads`.
+    pub threads: usize,
+    /// See `Config::set_seed`.
+    pub seed: Option<u64>,
+    /// A column the player to move must not use, see `ConnectFour::ban_column`.
+    pub banned_column: Option<usize>,
+    pub rules: Rules,
+    /// Pieces player 1 got more than player 2 before the first move, see `ConnectFour::new`.
+    pub handicap: i8,
+    /// Probability of the opponent finding its best reply, see `Config::set_expectimax`.
+    pub opponent_accuracy: Option<f32>,
+    /// Search transpositions only once, see `Config::set_memoization`.
+    pub memoize: bool,
+    /// In points, see `Config::set_draw_score`.
+    pub draw_score: f32,
+    /// Per ply factor of heuristic scores, 1 to turn it off, see `ConfigBuilder::new`.
+    pub discount: f32,
+    /// Choice among equally scored moves of a search which is not randomized.
+    pub tie_break: TieBreak,
+    /// In points, see `Config::set_futility_pruning`.
+    pub futility_margin: Option<f32>,
+    /// See `Config::set_late_move_reduction`.
+    pub late_move_reduction: bool,
+    /// See `Config::set_forced_move_extensions`.
+    pub forced_move_extensions: bool,
+    /// See `Config::set_move_ordering`.
+    pub move_ordering: bool,
+    /// See `Config::set_tree_recording`.
+    pub record_tree: bool,
+    /// Seed of a reproducible search, which ignores the think time and requires `max_depth`, see `ConfigBuilder::deterministic`.
+    pub deterministic: Option<u64>,
+}
+
+impl SearchOptions {
+    pub fn new(level:u8, evaluator:Box<dyn Evaluator>) -> SearchOptions {
+        SearchOptions {
+            level,
+            time_limit_millis: None,
+            max_depth: None,
+            randomized: false,
+            temperature: minimax::DEFAULT_TEMPERATURE,
+            evaluator,
+            on_progress: None,
+            multi_pv: 0,
+            cancel: None,
+            max_nodes: None,
+            threads: 1,
+            seed: None,
+            banned_column: None,
+            rules: Rules::default(),
+            handicap: 0,
+            opponent_accuracy: None,
+            memoize: false,
+            draw_score: 0.,
+            discount: EPSILON,
+            tie_break: TieBreak::default(),
+            futility_margin: None,
+            late_move_reduction: false,
+            forced_move_extensions: false,
+            move_ordering: false,
+            record_tree: false,
+            deterministic: None,
+        }
+    }
+}
+
+/// Starts searching the best move for `current_player` on a dedicated thread, see `minimax::eval_async`.
+/// Invalid boards are rejected right away.
+pub fn evaluate_state_async(values: Option<Array2D<i8>>, current_player:i8, options:SearchOptions) -> Result<SearchHandle,String> {
+    let PreparedSearch { game, current_player, options } = prepare_search(values, current_player, options)?;
+    let cancel = options.cancel.clone().unwrap_or_default();
+    minimax::eval_async(&game, move || search_config(options), current_player as Score, cancel)
+}
+
+/// Sets up a search for `current_player` without starting it, to run it on a thread of the caller's choice.
+/// Invalid boards are rejected right away.
+pub fn prepare_search(values: Option<Array2D<i8>>, current_player:i8, options:SearchOptions) -> Result<PreparedSearch,String> {
+    options.rules.validate()?;
+    let mut game = ConnectFour::new(values, current_player, options.handicap, options.evaluator.fork())?.with_rules(options.rules);
+    if let Some(winner) = game.winner_on_board() {
+        return Err(BoardError::AlreadyDecided(winner).into());
+    }
+    if let Some(col) = options.banned_column {
+        game.ban_column(current_player, col);
+    }
+    Ok(PreparedSearch { game, current_player, options })
+}
+
+/// A search set up by `prepare_search`.
+pub struct PreparedSearch {
+    game: ConnectFour,
+    current_player: i8,
+    options: SearchOptions,
+}
+
+impl PreparedSearch {
+    /// Searches on the calling thread until the search has ended. The result is `None` if the game is already
+    /// finished.
+    pub fn run(mut self) -> Result<Option<StateEvaluation>, String> {
+        let config = search_config(self.options)?;
+        Ok(match self.current_player {
+            P1 => maximize(&mut self.game, &config),
+            _ => minimize(&mut self.game, &config),
+        })
+    }
+}
+
+fn search_config(options:SearchOptions) -> Result<Config, String> {
+    let level_time = level_time_millis(options.level);
+    let settings = ConfigSnapshot {
+        time_limit_millis: Some(options.time_limit_millis.map_or(level_time, |limit| limit.min(level_time))),
+        max_depth: options.max_depth,
+        randomized: options.randomized,
+        temperature: options.temperature * SCORE_SCALE as f32,
+        min_score: MIN_SCORE,
+        epsilon: options.discount,
+        multi_pv: options.multi_pv,
+        max_nodes: options.max_nodes,
+        threads: options.threads,
+        futility_margin: options.futility_margin.map(to_score),
+        late_move_reduction: options.late_move_reduction,
+        forced_move_extensions: options.forced_move_extensions,
+        move_ordering: options.move_ordering,
+        opponent_accuracy: options.opponent_accuracy,
+        tie_break: options.tie_break,
+        memoize: options.memoize,
+        draw_score: to_score(options.draw_score),
+        deterministic: options.deterministic,
+    };
+    let mut config = settings.build()?;
+    if let Some(on_progress) = options.on_progress {
+        config.set_on_progress(on_progress);
+    }
+    if let Some(cancel) = options.cancel {
+        config.set_cancel_token(cancel);
+    }
+    if let Some(seed) = options.seed {
+        config.set_seed(seed);
+    }
+    config.set_tree_recording(options.record_tree);
+    Ok(config)
+}
+
+pub fn evaluate_action(values: Option<Array2D<i8>>, current_player:i8, action:usize, evaluator:Box<dyn Evaluator>, rules:Rules, handicap:i8) -> Result<ActionEvaluation, BoardError> {
     let mut g = ConnectFour::new(
         values,
-        current_player
-    );
+        current_player,
+        handicap,
+        evaluator
+    )?.with_rules(rules);
+    let len = rules.win_length;
+    if action >= WIDTH || g.col_heights[action] == 0 {
+        return Err(BoardError::InvalidColumn(action));
+    }
     g.last_action = Option::Some(action);
     let result = g.eval();
 
-    let winning_cells = result.winner.map(|val| {
+    let winning_cells = result.winner.map(|winner| {
+        let val = rules.winner(winner);
         let check_ = |tup_seq:Vec<(usize,usize)>| {
             let mut seq:Vec<(usize,usize)> = Vec::new();
             for rc in tup_seq {
@@ -288,21 +1131,106 @@ pub fn evaluate_action(values: Option<Array2D<i8>>, current_player:i8, action:us
                     seq.clear();
                 }
     
-                if seq.len() == 4 {
+                if seq.len() == len {
                     return Option::Some(seq);
                 }
             }
             Option::None
         };
         let row = g.col_heights[action] - 1;
-        check_(rdiag_tup_seq!(row, action))
-        .or_else(|| check_(ldiag_tup_seq!(row, action)))
-        .or_else(|| check_(h_tup_seq!(row, action)))
-        .or_else(|| check_(v_tup_seq!(row, action))).expect("no sequence of four found")
-    });
-    ActionEvaluation {
+        // a win not completed by the move was there before it
+        tup_seqs(row, action, rules).into_iter().find_map(check_).ok_or(BoardError::AlreadyDecided(winner))
+    }).transpose()?;
+    Ok(ActionEvaluation {
         eval: result,
         winning_cells
+    })
+}
+
+/// Quick, search free judgement of a move, e.g. to pick a sound for it.
+#[derive(Clone, Copy, Serialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub enum MoveKind {
+    Quiet,
+    CreatesThreat,
+    BlocksThreat,
+    Winning,
+    /// Allows the opponent to win with the next move, or loses right away in misère games.
+    Blunder,
+}
+
+/// Judgement of a move by the points it lost compared to the evaluation before it.
+#[derive(Clone, Copy, Serialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub enum MoveQuality {
+    Best,
+    Good,
+    Inaccuracy,
+    Blunder,
+}
+
+impl MoveQuality {
+    /// Classifies a move which lost `swing` points from the perspective of its player.
+    pub fn from_swing(swing:f32) -> MoveQuality {
+        match swing {
+            s if s <= BEST_SWING => MoveQuality::Best,
+            s if s <= GOOD_SWING => MoveQuality::Good,
+            s if s <= INACCURACY_SWING => MoveQuality::Inaccuracy,
+            _ => MoveQuality::Blunder,
+        }
+    }
+}
+
+/// Classifies playing `col` by `current_player`. A move fitting several kinds gets the first of
+/// winning, blunder, blocks threat and creates threat. Threats mean little when completing a row loses,
+/// so misère moves are only told apart into blunders and quiet ones.
+pub fn classify_move(values:Array2D<i8>, current_player:i8, col:usize, rules:Rules, handicap:i8) -> Result<MoveKind, BoardError> {
+    let mut g = ConnectFour::new(Some(values), current_player, handicap, EvaluatorKind::default().build(&EvalWeights::default()))?.with_rules(rules);
+    if let Some(winner) = g.winner_on_board() {
+        return Err(BoardError::AlreadyDecided(winner));
+    }
+    if col >= WIDTH || g.col_heights[col] >= HEIGHT {
+        return Err(BoardError::ColumnFull(col));
+    }
+    Ok(g.classify(col))
+}
+
+impl ConnectFour {
+    fn classify(&mut self, col:usize) -> MoveKind {
+        let player = self.current_player;
+        if self.rules.variant == Variant::Misere {
+            return match self.wins_with(player, col) {
+                true => MoveKind::Blunder,
+                false => MoveKind::Quiet,
+            };
+        }
+        if self.wins_with(player, col) {
+            return MoveKind::Winning;
+        }
+        let blocks = self.wins_with(-player, col);
+        self.apply(&col);
+        let blunder = self.actions().into_iter().any(|c| self.wins_with(-player, c));
+        let creates = self.is_forcing();
+        self.revert(&col);
+
+        match (blunder, blocks, creates) {
+            (true, _, _) => MoveKind::Blunder,
+            (false, true, _) => MoveKind::BlocksThreat,
+            (false, false, true) => MoveKind::CreatesThreat,
+            _ => MoveKind::Quiet,
+        }
+    }
+}
+
+impl MemoryUsage for ConnectFour {
+    fn heap_bytes(&self) -> usize {
+        let sequences: usize = self.sequences.elements_row_major_iter()
+            .map(|seqs| vec_bytes(seqs) + seqs.iter().map(vec_bytes).sum::<usize>())
+            .sum();
+        self.values.num_elements() * size_of::<i8>()
+            + self.sequences.num_elements() * size_of::<Vec<Vec<(usize, usize)>>>()
+            + sequences
+            + vec_bytes(&self.window_counts)
     }
 }
 
@@ -310,21 +1238,29 @@ pub fn evaluate_action(values: Option<Array2D<i8>>, current_player:i8, action:us
 mod tests {
     use super::*; 
     use std::time::Instant;
+    use crate::minimax::Termination;
 
     #[test]
     fn test_macros() {
-        assert_eq!(rdiag_tup_seq!(2,1), vec![(1,0),(2,1),(3,2),(4,3),(5,4)]);
-        assert_eq!(rdiag_tup_seq!(4,4), vec![(1,1),(2,2),(3,3),(4,4),(5,5)]);
-        assert_eq!(rdiag_tup_seq!(4,6), vec![(1,3),(2,4),(3,5),(4,6)]);
-        assert_eq!(rdiag_tup_seq!(2,3), vec![(0,1),(1,2),(2,3),(3,4),(4,5),(5,6)]);
+        assert_eq!(rdiag_tup_seq(2,1,4), vec![(1,0),(2,1),(3,2),(4,3),(5,4)]);
+        assert_eq!(rdiag_tup_seq(4,4,4), vec![(1,1),(2,2),(3,3),(4,4),(5,5)]);
+        assert_eq!(rdiag_tup_seq(4,6,4), vec![(1,3),(2,4),(3,5),(4,6)]);
+        assert_eq!(rdiag_tup_seq(2,3,4), vec![(0,1),(1,2),(2,3),(3,4),(4,5),(5,6)]);
+
+        assert_eq!(ldiag_tup_seq(4,1,4), vec![(1,4),(2,3),(3,2),(4,1),(5,0)]);
+        assert_eq!(ldiag_tup_seq(1,5,4), vec![(0,6),(1,5),(2,4),(3,3),(4,2)]);
 
-        assert_eq!(ldiag_tup_seq!(4,1), vec![(1,4),(2,3),(3,2),(4,1),(5,0)]);
-        assert_eq!(ldiag_tup_seq!(1,5), vec![(0,6),(1,5),(2,4),(3,3),(4,2)]);
+        assert_eq!(h_tup_seq(1,5,4), vec![(1,2),(1,3),(1,4),(1,5),(1,6)]);
 
-        assert_eq!(h_tup_seq!(1,5), vec![(1,2),(1,3),(1,4),(1,5),(1,6)]);
+        assert_eq!(v_tup_seq(2,5,4), vec![(0,5),(1,5),(2,5),(3,5),(4,5),(5,5)]);
+        assert_eq!(v_tup_seq(0,0,4), vec![(0,0),(1,0),(2,0),(3,0)]);
 
-        assert_eq!(v_tup_seq!(2,5), vec![(0,5),(1,5),(2,5),(3,5),(4,5),(5,5)]);
-        assert_eq!(v_tup_seq!(0,0), vec![(0,0),(1,0),(2,0),(3,0)]);
+        assert_eq!(h_tup_seq(1,5,3), vec![(1,3),(1,4),(1,5),(1,6)]);
+        assert_eq!(v_tup_seq(2,5,5), vec![(0,5),(1,5),(2,5),(3,5),(4,5),(5,5)]);
+        assert_eq!(rdiag_tup_seq(4,6,3), vec![(2,4),(3,5),(4,6)]);
+        assert_eq!(rdiag_tup_seq(4,6,5), vec![(0,2),(1,3),(2,4),(3,5),(4,6)]);
+        assert!(rdiag_tup_seq(0,5,3).is_empty());
+        assert!(ldiag_tup_seq(0,0,3).is_empty());
     }
 
     #[test]
@@ -366,12 +1302,12 @@ mod tests {
     fn play_col(p:&mut ConnectFour, col:&usize) -> f32 {
         p.apply(col);
         p.swap_players();
-        p.evaluate()
+        to_points(p.evaluate())
     }
 
     #[test]
     fn test_2() {
-        let mut p = ConnectFour::new(Option::None, P1);
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
 
         assert_eq!(play_col(&mut p, &0), 1.);
         assert_eq!(play_col(&mut p, &0), -1.);
@@ -387,9 +1323,122 @@ mod tests {
         assert_eq!(play_col(&mut p, &5), -2.5);
     }
 
+    #[test]
+    fn test_threat_evaluator() {
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Threats.build(&EvalWeights::default()));
+        let mut score_col = |col|  {
+            p.apply(col);
+            p.swap_players();
+            to_points(p.eval().score)
+        };
+
+        assert_eq!(score_col(&3), 1.5);
+        assert_eq!(score_col(&0), 0.);
+        assert_eq!(score_col(&4), 4.);
+        assert_eq!(score_col(&0), -1.);
+        assert_eq!(score_col(&2), 9.);
+        assert_eq!(score_col(&0), -5.);
+        assert_eq!(score_col(&5), 127.);
+    }
+
+    fn random_board(rng:&mut impl rand::Rng, moves:usize) -> (Array2D<i8>, i8) {
+        let mut values = Array2D::filled_with(0, HEIGHT, WIDTH);
+        let mut heights = [0; WIDTH];
+        let mut player = P1;
+        for _ in 0..moves {
+            let open: Vec<usize> = (0..WIDTH).filter(|c| heights[*c] < HEIGHT).collect();
+            let col = open[rng.gen_range(0..open.len())];
+            values[(heights[col], col)] = player;
+            heights[col] += 1;
+            player = -player;
+        }
+        (values, player)
+    }
+
+    #[test]
+    fn test_validate_board() {
+        let heuristic = || EvaluatorKind::Heuristic.build(&EvalWeights::default());
+        let mut values = Array2D::filled_with(0, HEIGHT, WIDTH);
+        assert!(ConnectFour::new(Some(values.clone()), P1, 0, heuristic()).is_ok());
+        assert_eq!(Some(BoardError::Player(0)), ConnectFour::new(Some(values.clone()), 0, 0, heuristic()).err());
+
+        values[(0, 3)] = P1;
+        assert_eq!(Some(BoardError::NotPlayersTurn(P1)), ConnectFour::new(Some(values.clone()), P1, 0, heuristic()).err());
+        values[(2, 3)] = P2;
+        assert_eq!(Some(BoardError::FloatingPiece { row: 2, col: 3 }), ConnectFour::new(Some(values.clone()), P1, 0, heuristic()).err());
+        values[(2, 3)] = 0;
+        values[(0, 4)] = 5;
+        assert_eq!(Some(BoardError::CellValue { row: 0, col: 4, value: 5 }), ConnectFour::new(Some(values.clone()), P1, 0, heuristic()).err());
+        values[(0, 4)] = P1;
+        assert_eq!(Some(BoardError::PieceCount { p1: 2, p2: 0 }), ConnectFour::new(Some(values.clone()), P2, 0, heuristic()).err());
+        assert!(ConnectFour::new(Some(values.clone()), P1, 2, heuristic()).is_ok());
+        assert!(ConnectFour::new(Some(values.clone()), P2, 1, heuristic()).is_ok());
+
+        let small = Array2D::filled_with(0, HEIGHT - 1, WIDTH);
+        assert_eq!(Some(BoardError::Dimensions { rows: HEIGHT - 1, cols: WIDTH }), ConnectFour::new(Some(small), P1, 0, heuristic()).err());
+
+        let mut won = Array2D::filled_with(0, HEIGHT, WIDTH);
+        for col in 0..4 {
+            won[(0, col)] = P1;
+            won[(1, col)] = P2;
+        }
+        assert!(prepare_search(Some(won), P1, SearchOptions::new(1, heuristic())).is_err());
+
+        let values = Array2D::filled_with(0, HEIGHT, WIDTH);
+        assert_eq!(Some(BoardError::InvalidColumn(3)), evaluate_action(Some(values.clone()), P1, 3, heuristic(), Rules::default(), 0).err());
+        assert_eq!(Some(BoardError::InvalidColumn(WIDTH)), evaluate_action(Some(values), P1, WIDTH, heuristic(), Rules::default(), 0).err());
+    }
+
+    #[test]
+    fn test_load_weights() {
+        let path = std::env::temp_dir().join(format!("connect-four-weights-{}.toml", std::process::id()));
+        let weights = EvalWeights::default();
+        weights.save(&path).unwrap();
+        assert_eq!(Ok(weights), EvalWeights::load(&path));
+
+        let invalid = fs::read_to_string(&path).unwrap().replacen("open_two = ", "open_two = nan #", 1);
+        fs::write(&path, invalid).unwrap();
+        assert!(EvalWeights::load(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_fuzz_boards() {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        let heuristic = || EvaluatorKind::Heuristic.build(&EvalWeights::default());
+
+        for _ in 0..500 {
+            // arbitrary grids must never panic
+            let rows = if rng.gen_bool(0.1) { rng.gen_range(0..10) } else { HEIGHT };
+            let cols = if rng.gen_bool(0.1) { rng.gen_range(0..10) } else { WIDTH };
+            let values = Array2D::filled_by_row_major(|| rng.gen_range(-2..=2), rows, cols);
+            let player = rng.gen_range(-2..=2);
+            if let Ok(mut g) = ConnectFour::new(Some(values), player, 0, heuristic()) {
+                if let Some(col) = g.search_within(Some(2), None, false).ok().and_then(|r| r.best_action) {
+                    assert!(g.col_heights[col] < HEIGHT);
+                }
+            }
+
+            // boards reached by legal play are accepted and only legal moves are suggested
+            let moves = rng.gen_range(0..TOTAL_FIELDS);
+            let (mut values, player) = random_board(&mut rng, moves);
+            let mut g = ConnectFour::new(Some(values.clone()), player, 0, heuristic()).unwrap();
+            if let Some(col) = g.search_within(Some(2), None, false).ok().and_then(|r| r.best_action) {
+                assert!(g.col_heights[col] < HEIGHT);
+            }
+
+            // a single corrupted cell must not panic either
+            let (row, col) = (rng.gen_range(0..HEIGHT), rng.gen_range(0..WIDTH));
+            values[(row, col)] = rng.gen_range(-3..=3);
+            let _ = ConnectFour::new(Some(values.clone()), player, 0, heuristic()).map(|mut g| g.search_within(Some(1), None, false).map(|r| r.best_action));
+            let _ = evaluate_action(Some(values), -player, rng.gen_range(0..=WIDTH), heuristic(), Rules::default(), 0);
+        }
+    }
+
     #[test]
     fn test_col_height() {
-        let mut p = ConnectFour::new(Option::None, P1);
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
         let mut play_col = |col|  {
             p.apply(col);
             p.swap_players();
@@ -424,15 +1473,9 @@ mod tests {
 
     #[test]
     fn test_case_1() {
-        let mut p = ConnectFour::new(Option::None, P1);
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
         
-        let config = Config::new(
-            None,
-            Some(5),
-            false,
-            MIN_SCORE,
-            EPSILON
-        );
+        let config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(5).build().unwrap();
 
         play_col(&mut p, &3);
         play_col(&mut p, &3);
@@ -446,15 +1489,9 @@ mod tests {
 
     #[test]
     fn test_case_2() {
-        let mut p = ConnectFour::new(Option::None, P1);
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
         
-        let config = Config::new(
-            None,
-            Some(5),
-            false,
-            MIN_SCORE,
-            EPSILON
-        );
+        let config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(5).build().unwrap();
 
         play_col(&mut p, &3);
         play_col(&mut p, &3);
@@ -466,18 +1503,389 @@ mod tests {
         assert_eq!(5, result.best_action.unwrap())
     }
 
+    #[test]
+    fn test_perft() {
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
+        for depth in 0..=5 {
+            assert_eq!(7u64.pow(depth as u32), p.perft(depth));
+        }
+        assert_eq!(0, p.set_fields);
+        assert_eq!(P1, p.current_player);
+    }
+
+    #[test]
+    fn test_cancel_search() {
+        use std::{sync::atomic::Ordering, thread, time::Duration};
+
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
+        let mut config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(30).build().unwrap();
+        let cancel = CancelToken::default();
+        config.set_cancel_token(cancel.clone());
+
+        let canceller = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(200));
+            cancel.store(true, Ordering::Relaxed);
+        });
+        let now = Instant::now();
+        let result = maximize(&mut p, &config).unwrap();
+        canceller.join().unwrap();
+
+        assert!(now.elapsed() < Duration::from_secs(5));
+        assert!(p.actions().contains(&result.best_action.unwrap()));
+        assert_eq!(Termination::Cancelled, result.termination);
+        assert_eq!(0, p.set_fields);
+    }
+
+    #[test]
+    fn test_forced_move_extensions() {
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
+        for col in [0, 6, 1, 6] {
+            p.play(col);
+        }
+        p.apply(&2);
+        assert!(p.is_forcing());
+        p.revert(&2);
+        p.apply(&5);
+        assert!(!p.is_forcing());
+        p.revert(&5);
+
+        let mut config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(2).build().unwrap();
+        let plain = maximize(&mut p, &config).unwrap();
+        config.set_forced_move_extensions(true);
+        let extended = maximize(&mut p, &config).unwrap();
+        assert!(extended.ops_count > plain.ops_count);
+        assert_eq!(4, p.set_fields);
+    }
+
+    #[test]
+    fn test_classify_move() {
+        let board = |moves:&[usize]| {
+            let mut p = ConnectFour::empty(P1, EvaluatorKind::default().build(&EvalWeights::default()));
+            moves.iter().for_each(|col| p.play(*col));
+            (p.values.clone(), p.current_player)
+        };
+
+        let (values, player) = board(&[0, 6, 1, 6]);
+        assert_eq!(Ok(MoveKind::CreatesThreat), classify_move(values.clone(), player, 2, Rules::default(), 0));
+        assert_eq!(Ok(MoveKind::Quiet), classify_move(values.clone(), player, 4, Rules::default(), 0));
+
+        let (values, player) = board(&[0, 6, 1, 6, 2]);
+        assert_eq!(Err(BoardError::NotPlayersTurn(P1)), classify_move(values.clone(), P1, 3, Rules::default(), 0));
+        assert_eq!(Ok(MoveKind::BlocksThreat), classify_move(values.clone(), player, 3, Rules::default(), 0));
+        assert_eq!(Ok(MoveKind::Blunder), classify_move(values.clone(), player, 5, Rules::default(), 0));
+
+        let (values, player) = board(&[0, 6, 1, 6, 2, 5]);
+        assert_eq!(Ok(MoveKind::Winning), classify_move(values.clone(), player, 3, Rules::default(), 0));
+
+        // playing 3 lets player 2 complete the second row on top of it
+        let (values, player) = board(&[0, 2, 1, 0, 6, 1, 6, 2]);
+        assert_eq!(Ok(MoveKind::Blunder), classify_move(values.clone(), player, 3, Rules::default(), 0));
+        assert_eq!(Ok(MoveKind::Quiet), classify_move(values, player, 5, Rules::default(), 0));
+    }
+
+    #[test]
+    fn test_ban_column() {
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
+        p.ban_column(P1, 3);
+        assert_eq!(vec![2, 4, 1, 5, 0, 6], p.actions());
+        p.play(2);
+        assert_eq!(7, p.actions().len());
+        p.play(4);
+
+        let result = maximize(&mut p, &ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(3).build().unwrap()).unwrap();
+        assert_ne!(Some(3), result.best_action);
+
+        let mut values = Array2D::filled_with(0, HEIGHT, WIDTH);
+        for col in [0, 1, 2, 4, 5, 6] {
+            for row in 0..HEIGHT {
+                values[(row, col)] = if (row + col / 3) % 2 == 0 { P1 } else { P2 };
+            }
+        }
+        let mut p = ConnectFour::build(values, P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()), Rules::default());
+        p.ban_column(P1, 3);
+        assert_eq!(vec![3], p.actions());
+    }
+
+    #[test]
+    fn test_pruning() {
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
+        for col in [0, 0, 1, 1, 2, 6] {
+            p.play(col);
+        }
+        let mut config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(5).build().unwrap();
+        let plain = maximize(&mut p, &config).unwrap();
+
+        config.set_futility_pruning(Some(to_score(10.)));
+        config.set_late_move_reduction(true);
+        let pruned = maximize(&mut p, &config).unwrap();
+
+        assert_eq!(Some(3), plain.best_action);
+        assert_eq!(Some(3), pruned.best_action);
+        assert!(pruned.ops_count < plain.ops_count, "{} >= {}", pruned.ops_count, plain.ops_count);
+    }
+
+    #[test]
+    fn test_state_hash() {
+        let evaluator = || EvaluatorKind::Heuristic.build(&EvalWeights::default());
+        let mut a = ConnectFour::empty(P1, evaluator());
+        let mut b = ConnectFour::empty(P1, evaluator());
+        let empty = a.state_hash();
+        for col in [3, 2, 4] {
+            a.play(col);
+        }
+        for col in [4, 2, 3] {
+            b.play(col);
+        }
+        assert_eq!(a.state_hash(), b.state_hash());
+        assert_eq!(a.state_hash(), ConnectFour::new(Some(a.values.clone()), P2, 0, evaluator()).unwrap().state_hash());
+
+        b.swap_players();
+        assert_ne!(a.state_hash(), b.state_hash());
+        b.swap_players();
+        for col in [4, 2, 3] {
+            b.swap_players();
+            b.revert(&col);
+        }
+        assert_eq!(empty, b.state_hash());
+    }
+
+    #[test]
+    fn test_ordered_actions() {
+        let mut g = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
+        assert_eq!(g.actions(), g.ordered_actions());
+        for col in [1, 6, 2, 6, 3] {
+            g.play(col);
+        }
+        assert_eq!(vec![4, 0, 3, 2, 1, 5, 6], g.ordered_actions());
+        g.play(6);
+        assert_eq!(vec![4, 0, 6, 3, 2, 1, 5], g.ordered_actions());
+
+        let mut config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(4).build().unwrap();
+        let plain = maximize(&mut g, &config).unwrap();
+        config.set_move_ordering(true);
+        let ordered = maximize(&mut g, &config).unwrap();
+        assert_eq!(plain.best_action, ordered.best_action);
+        assert_ne!(plain.ops_count, ordered.ops_count);
+    }
+
+    #[test]
+    fn test_window_counters() {
+        let evaluator = || EvaluatorKind::Windows.build(&EvalWeights::default());
+        let mut g = ConnectFour::empty(P1, evaluator());
+        assert_eq!(69, g.window_counts.len());
+        let moves = [3, 3, 4, 2, 4, 4, 5, 1];
+        for col in moves {
+            g.play(col);
+        }
+        let fresh = ConnectFour::new(Some(g.values.clone()), g.current_player, 0, evaluator()).unwrap();
+        assert_eq!(fresh.window_counts, g.window_counts);
+        assert_eq!(fresh.open_windows, g.open_windows);
+        assert!(g.open_windows(P1, 3) > 0);
+        assert!(g.eval().score > 0);
+
+        g.play(6);
+        assert_eq!(1, g.open_windows(P1, 4));
+        assert_eq!(Some(P1), g.eval().winner);
+
+        for col in [6].iter().chain(moves.iter().rev()) {
+            g.swap_players();
+            g.revert(col);
+        }
+        assert!(g.window_counts.iter().all(|c| *c == [0, 0]));
+        assert_eq!([[0; MAX_WIN_LENGTH + 1]; 2], g.open_windows);
+    }
+
+    #[test]
+    fn test_win_length() {
+        assert!(Rules { win_length: 2, ..Rules::default() }.validate().is_err());
+        assert!(Rules { win_length: 6, ..Rules::default() }.validate().is_err());
+        for kind in [EvaluatorKind::Heuristic, EvaluatorKind::Threats, EvaluatorKind::Windows] {
+            let empty = |win_length| ConnectFour::empty(P1, kind.build(&EvalWeights::default())).with_rules(Rules { win_length, ..Rules::default() });
+            let mut three = empty(3);
+            for col in [0, 0, 1, 1] {
+                three.play(col);
+                assert!(!three.is_finished(), "{:?}", kind);
+            }
+            three.play(2);
+            assert_eq!(Some(P1), three.eval().winner, "{:?}", kind);
+
+            let mut five = empty(5);
+            for col in [0, 6, 1, 6, 2, 6, 3, 5] {
+                five.play(col);
+                assert!(!five.is_finished(), "{:?}", kind);
+            }
+            five.play(4);
+            assert_eq!(Some(P1), five.eval().winner, "{:?}", kind);
+        }
+
+        let mut g = ConnectFour::empty(P1, EvaluatorKind::Windows.build(&EvalWeights::default())).with_rules(Rules { win_length: 3, ..Rules::default() });
+        assert_eq!(98, g.window_counts.len());
+        for col in [3, 3, 4, 4] {
+            g.play(col);
+        }
+        let mut options = SearchOptions::new(3, EvaluatorKind::Windows.build(&EvalWeights::default()));
+        options.rules = Rules { win_length: 3, ..Rules::default() };
+        let result = prepare_search(Some(g.values.clone()), P1, options).unwrap().run().unwrap().unwrap();
+        assert!([2, 5].contains(&result.best_action.unwrap()));
+    }
+
+    #[test]
+    fn test_cylinder() {
+        assert_eq!(h_wrapped_seq(2,1,4), vec![(2,5),(2,6),(2,0),(2,1),(2,2),(2,3),(2,4)]);
+        assert_eq!(diag_wrapped_seq(0,5,4,1), vec![(0,5),(1,6),(2,0),(3,1)]);
+        assert_eq!(diag_wrapped_seq(1,0,4,-1), vec![(0,1),(1,0),(2,6),(3,5),(4,4)]);
+        assert_eq!(diag_wrapped_seq(5,2,3,-1), vec![(3,4),(4,3),(5,2)]);
+
+        let cylinder = Rules { variant: Variant::Cylinder, ..Rules::default() };
+        assert_eq!(105, board_windows(cylinder).len());
+        for kind in [EvaluatorKind::Heuristic, EvaluatorKind::Threats, EvaluatorKind::Windows] {
+            let empty = |rules| ConnectFour::empty(P1, kind.build(&EvalWeights::default())).with_rules(rules);
+            let (mut standard, mut wrapped) = (empty(Rules::default()), empty(cylinder));
+            for col in [5, 5, 6, 6, 0, 0, 1] {
+                standard.play(col);
+                wrapped.play(col);
+            }
+            assert!(!standard.is_finished(), "{:?}", kind);
+            assert_eq!(Some(P1), wrapped.eval().winner, "{:?}", kind);
+
+            // no column is better than another for the first piece
+            let first = |col| {
+                let mut g = empty(cylinder);
+                g.play(col);
+                g.eval().score
+            };
+            assert_eq!(first(0), first(3), "{:?}", kind);
+        }
+
+        let mut g = ConnectFour::empty(P1, EvaluatorKind::default().build(&EvalWeights::default())).with_rules(cylinder);
+        for col in [5, 5, 6, 6, 0, 0, 1] {
+            g.play(col);
+        }
+        let result = evaluate_action(Some(g.values.clone()), P2, 1, EvaluatorKind::default().build(&EvalWeights::default()), cylinder, 0).unwrap();
+        assert_eq!(Some(vec![(0,5),(0,6),(0,0),(0,1)]), result.winning_cells);
+    }
+
+    #[test]
+    fn test_misere() {
+        let misere = Rules { variant: Variant::Misere, ..Rules::default() };
+        let moves = [0, 6, 1, 6, 2, 6];
+        for kind in [EvaluatorKind::Heuristic, EvaluatorKind::Threats, EvaluatorKind::Windows] {
+            let mut g = ConnectFour::empty(P1, kind.build(&EvalWeights::default())).with_rules(misere);
+            moves.iter().for_each(|col| g.play(*col));
+            assert!(!g.is_finished(), "{:?}", kind);
+            g.play(3);
+            assert_eq!(Some(P2), g.eval().winner, "{:?}", kind);
+            assert_eq!(-MAX_SCORE, g.eval().score, "{:?}", kind);
+        }
+
+        let mut g = ConnectFour::empty(P1, EvaluatorKind::default().build(&EvalWeights::default())).with_rules(misere);
+        moves.iter().for_each(|col| g.play(*col));
+        assert_eq!(Ok(MoveKind::Blunder), classify_move(g.values.clone(), P1, 3, misere, 0));
+        assert_eq!(Ok(MoveKind::Quiet), classify_move(g.values.clone(), P1, 4, misere, 0));
+
+        let mut options = SearchOptions::new(2, EvaluatorKind::default().build(&EvalWeights::default()));
+        options.rules = misere;
+        assert_ne!(Some(3), prepare_search(Some(g.values.clone()), P1, options).unwrap().run().unwrap().unwrap().best_action);
+
+        g.play(3);
+        let result = evaluate_action(Some(g.values.clone()), P2, 3, EvaluatorKind::default().build(&EvalWeights::default()), misere, 0).unwrap();
+        assert_eq!(Some(P2), result.eval.winner);
+        assert_eq!(Some(vec![(0,0),(0,1),(0,2),(0,3)]), result.winning_cells);
+    }
+
+    #[test]
+    fn test_move_quality() {
+        assert_eq!(MoveQuality::Best, MoveQuality::from_swing(-2.));
+        assert_eq!(MoveQuality::Best, MoveQuality::from_swing(BEST_SWING));
+        assert_eq!(MoveQuality::Good, MoveQuality::from_swing(1.));
+        assert_eq!(MoveQuality::Inaccuracy, MoveQuality::from_swing(5.));
+        assert_eq!(MoveQuality::Blunder, MoveQuality::from_swing(WIN_POINTS));
+    }
+
+    #[test]
+    fn test_parallel_search() {
+        let mut g = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
+        for col in [3, 3, 2, 4] {
+            g.play(col);
+        }
+        let mut config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(6).build().unwrap();
+        config.set_multi_pv(WIDTH);
+        let sequential = maximize(&mut g, &config).unwrap();
+        config.set_threads(4);
+        let parallel = maximize(&mut g, &config).unwrap();
+        assert_eq!(sequential.top_moves, parallel.top_moves);
+        assert_eq!(sequential.best_action, parallel.best_action);
+        assert_eq!(4, g.set_fields);
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut g = ConnectFour::empty(P1, EvaluatorKind::Threats.build(&EvalWeights::default()));
+        for col in [3, 3, 4, 4, 5, 5] {
+            g.play(col);
+        }
+        let mut fork = g.fork();
+        assert_eq!(g.state_hash(), fork.state_hash());
+        assert_eq!(g.evaluate(), fork.evaluate());
+
+        let mut fork = std::thread::spawn(move || {
+            fork.play(6);
+            fork
+        }).join().unwrap();
+        assert!(fork.is_finished());
+        assert!(!g.is_finished());
+        assert_eq!(0, g.values[(0, 6)]);
+
+        fork.revert(&6);
+        g.play(2);
+        assert!(g.is_finished());
+        assert!(!fork.is_finished());
+    }
+
+    #[test]
+    fn test_memoization() {
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
+        for col in [0, 0, 1, 1, 2, 6] {
+            p.play(col);
+        }
+        let mut config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(6).build().unwrap();
+        let plain = maximize(&mut p, &config).unwrap();
+
+        config.set_memoization(true);
+        let memoized = maximize(&mut p, &config).unwrap();
+
+        assert_eq!(plain.best_action, memoized.best_action);
+        assert!(memoized.ops_count < plain.ops_count, "{} >= {}", memoized.ops_count, plain.ops_count);
+        assert_eq!(0, plain.stats.tt_hits);
+        assert!(memoized.stats.tt_hits > 0);
+        assert!(plain.stats.cutoffs > 0);
+        assert_eq!(6, memoized.stats.depth);
+        assert_eq!(plain.ops_count, plain.stats.root_nodes.iter().map(|(_, n)| n).sum());
+    }
+
+    #[test]
+    fn test_node_budget() {
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
+        p.apply(&3);
+        let mut config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(30).build().unwrap();
+        config.set_node_budget(20_000);
+
+        let first = minimize(&mut p, &config).unwrap();
+        let second = minimize(&mut p, &config).unwrap();
+        assert_eq!(Termination::NodeBudget, first.termination);
+        assert!(first.ops_count <= 20_000);
+        assert_eq!((first.best_action, first.ops_count, first.score), (second.best_action, second.ops_count, second.score));
+
+        let config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(2).build().unwrap();
+        assert_eq!(Termination::DepthReached, minimize(&mut p, &config).unwrap().termination);
+    }
+
     #[test]
     fn benchmark() {
-        let mut p = ConnectFour::new(Option::None, P1);
+        let mut p = ConnectFour::empty(P1, EvaluatorKind::Heuristic.build(&EvalWeights::default()));
         p.apply(&3);
         
-        let config = Config::new(
-            None,
-            Some(5),
-            false,
-            MIN_SCORE,
-            EPSILON
-        );
+        let config = ConfigBuilder::new(MIN_SCORE, EPSILON).max_depth(5).build().unwrap();
 
         let now = Instant::now();
         let result = maximize(&mut p, &config).unwrap();
@@ -0,0 +1,342 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{engine::{self, EvalWeights, EvaluatorKind, MAX_LEVEL, MIN_LEVEL}, memory::{vec_bytes, MemoryUsage}, selfplay::{self, Contender}};
+
+/// Games of an engine vs engine knockout pairing played at most before giving up on a decision.
+const MAX_KNOCKOUT_REPLAYS:usize = 10;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum EntrantKind {
+    Human,
+    Engine,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Entrant {
+    pub name: String,
+    pub kind: EntrantKind,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum Format {
+    RoundRobin,
+    Knockout,
+}
+
+/// Settings enforced for every game of a round. They determine the strength of engine entrants.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct RoundSettings {
+    pub level: u8,
+    pub evaluator: EvaluatorKind,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    P1Wins,
+    P2Wins,
+    Draw,
+}
+
+/// A scheduled game. `p1` moves first, a pairing without `p2` is a bye.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Pairing {
+    pub id: usize,
+    pub round: usize,
+    pub p1: usize,
+    pub p2: Option<usize>,
+    pub result: Option<Outcome>,
+}
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+pub struct Standing {
+    pub entrant: usize,
+    pub name: String,
+    pub played: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub points: f32,
+    pub eliminated: bool,
+}
+
+pub struct Tournament {
+    format: Format,
+    entrants: Vec<Entrant>,
+    round_settings: Vec<RoundSettings>,
+    pairings: Vec<Pairing>,
+}
+
+impl Tournament {
+    /// Creates a tournament and schedules its first round (all rounds for round robin).
+    /// The last entry of `round_settings` also applies to all further rounds.
+    pub fn new(format:Format, entrants:Vec<Entrant>, round_settings:Vec<RoundSettings>) -> Result<Tournament, String> {
+        if entrants.len() < 2 {
+            return Err("a tournament needs at least two entrants".into());
+        }
+        if round_settings.is_empty() {
+            return Err("at least one round setting is required".into());
+        }
+        if round_settings.iter().any(|s| !(MIN_LEVEL..=MAX_LEVEL).contains(&s.level)) {
+            return Err(format!("level must be between {} and {}", MIN_LEVEL, MAX_LEVEL));
+        }
+
+        let mut tournament = Tournament {
+            format,
+            entrants,
+            round_settings,
+            pairings: Vec::new(),
+        };
+        match format {
+            Format::RoundRobin => tournament.schedule_round_robin(),
+            Format::Knockout => {
+                let all: Vec<usize> = (0..tournament.entrants.len()).collect();
+                tournament.schedule_round(0, &all);
+            }
+        }
+        Ok(tournament)
+    }
+
+    /// Circle method: every entrant meets every other entrant exactly once.
+    fn schedule_round_robin(&mut self) {
+        let mut slots: Vec<Option<usize>> = (0..self.entrants.len()).map(Some).collect();
+        if slots.len() % 2 == 1 {
+            slots.push(None);
+        }
+        let n = slots.len();
+        for round in 0..n - 1 {
+            for i in 0..n / 2 {
+                // alternate who moves first between rounds
+                let (a, b) = match round % 2 {
+                    0 => (slots[i], slots[n - 1 - i]),
+                    _ => (slots[n - 1 - i], slots[i]),
+                };
+                // whoever is paired with the empty slot sits out this round
+                if let (Some(p1), Some(p2)) = (a, b) {
+                    self.add_pairing(round, p1, Some(p2));
+                }
+            }
+            slots[1..].rotate_right(1);
+        }
+    }
+
+    fn schedule_round(&mut self, round:usize, players:&[usize]) {
+        for pair in players.chunks(2) {
+            self.add_pairing(round, pair[0], pair.get(1).copied());
+        }
+    }
+
+    fn add_pairing(&mut self, round:usize, p1:usize, p2:Option<usize>) {
+        let result = match p2 {
+            Some(_) => None,
+            None => Some(Outcome::P1Wins)
+        };
+        self.pairings.push(Pairing { id: self.pairings.len(), round, p1, p2, result });
+    }
+
+    pub fn entrants(&self) -> &[Entrant] {
+        &self.entrants
+    }
+
+    pub fn pairings(&self) -> &[Pairing] {
+        &self.pairings
+    }
+
+    pub fn pairing(&self, id:usize) -> Result<&Pairing, String> {
+        self.pairings.get(id).ok_or(format!("unknown pairing {}", id))
+    }
+
+    pub fn settings_for(&self, pairing_id:usize) -> Result<RoundSettings, String> {
+        let round = self.pairing(pairing_id)?.round;
+        Ok(self.round_settings[round.min(self.round_settings.len() - 1)])
+    }
+
+    pub fn record_result(&mut self, pairing_id:usize, outcome:Outcome) -> Result<(), String> {
+        let format = self.format;
+        let pairing = self.pairings.get_mut(pairing_id).ok_or(format!("unknown pairing {}", pairing_id))?;
+        if pairing.result.is_some() {
+            return Err("result already recorded".into());
+        }
+        if format == Format::Knockout && outcome == Outcome::Draw {
+            return Err("knockout games cannot end in a draw, replay the game".into());
+        }
+        pairing.result = Some(outcome);
+
+        let round = pairing.round;
+        if format == Format::Knockout && self.round_complete(round) {
+            let winners: Vec<usize> = self.pairings.iter()
+                .filter(|p| p.round == round)
+                .filter_map(|p| match p.result {
+                    Some(Outcome::P2Wins) => p.p2,
+                    _ => Some(p.p1),
+                })
+                .collect();
+            if winners.len() > 1 {
+                self.schedule_round(round + 1, &winners);
+            }
+        }
+        Ok(())
+    }
+
+    fn round_complete(&self, round:usize) -> bool {
+        self.pairings.iter().filter(|p| p.round == round).all(|p| p.result.is_some())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pairings.iter().all(|p| p.result.is_some())
+    }
+
+    /// The game of an engine vs engine pairing with the round settings, to be played without holding the tournament.
+    pub fn engine_match(&self, pairing_id:usize) -> Result<EngineMatch, String> {
+        let pairing = self.pairing(pairing_id)?;
+        let p2 = pairing.p2.ok_or("pairing is a bye")?;
+        if self.entrants[pairing.p1].kind != EntrantKind::Engine || self.entrants[p2].kind != EntrantKind::Engine {
+            return Err("only engine vs engine pairings can be played automatically".into());
+        }
+
+        let settings = self.settings_for(pairing_id)?;
+        let contender = Contender {
+            evaluator: settings.evaluator,
+            weights: EvalWeights::default(),
+            max_depth: None,
+            time_limit_millis: Some(engine::level_time_millis(settings.level)),
+            randomized: true,
+        };
+        Ok(EngineMatch { contender, knockout: self.format == Format::Knockout })
+    }
+
+    /// Standings sorted by points. Byes count as wins.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self.entrants.iter().enumerate().map(|(i, e)| Standing {
+            entrant: i,
+            name: e.name.clone(),
+            played: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            points: 0.,
+            eliminated: false,
+        }).collect();
+
+        for pairing in self.pairings.iter() {
+            let Some(outcome) = pairing.result else { continue };
+            let (winner, loser) = match (outcome, pairing.p2) {
+                (Outcome::P1Wins, p2) => (Some(pairing.p1), p2),
+                (Outcome::P2Wins, p2) => (p2, Some(pairing.p1)),
+                (Outcome::Draw, _) => (None, None),
+            };
+            for entrant in [Some(pairing.p1), pairing.p2].into_iter().flatten() {
+                let s = &mut standings[entrant];
+                s.played += 1;
+                if winner == Some(entrant) {
+                    s.wins += 1;
+                    s.points += 1.;
+                } else if loser == Some(entrant) {
+                    s.losses += 1;
+                    s.eliminated |= self.format == Format::Knockout;
+                } else {
+                    s.draws += 1;
+                    s.points += 0.5;
+                }
+            }
+        }
+
+        standings.sort_by(|a, b| b.points.total_cmp(&a.points).then(a.entrant.cmp(&b.entrant)));
+        standings
+    }
+}
+
+/// See `Tournament::engine_match`.
+pub struct EngineMatch {
+    contender: Contender,
+    knockout: bool,
+}
+
+impl EngineMatch {
+    /// Plays the game out. A knockout game ending in a draw is replayed, up to `MAX_KNOCKOUT_REPLAYS` games.
+    pub fn play(&self) -> Result<Outcome, String> {
+        let games = match self.knockout {
+            true => MAX_KNOCKOUT_REPLAYS,
+            false => 1,
+        };
+        for _ in 0..games {
+            match selfplay::play_game(&self.contender, &self.contender)? {
+                Some(1) => return Ok(Outcome::P1Wins),
+                Some(_) => return Ok(Outcome::P2Wins),
+                None => {},
+            }
+        }
+        match self.knockout {
+            true => Err(format!("{} games in a row ended in a draw, replay the pairing", games)),
+            false => Ok(Outcome::Draw),
+        }
+    }
+}
+
+impl MemoryUsage for Tournament {
+    fn heap_bytes(&self) -> usize {
+        vec_bytes(&self.entrants)
+            + self.entrants.iter().map(|e| e.name.capacity()).sum::<usize>()
+            + vec_bytes(&self.round_settings)
+            + vec_bytes(&self.pairings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrants(n:usize) -> Vec<Entrant> {
+        (0..n).map(|i| Entrant { name: format!("player {}", i), kind: EntrantKind::Human }).collect()
+    }
+
+    fn settings() -> Vec<RoundSettings> {
+        vec![RoundSettings { level: 2, evaluator: EvaluatorKind::Heuristic }]
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let t = Tournament::new(Format::RoundRobin, entrants(5), settings()).unwrap();
+        assert!(Tournament::new(Format::RoundRobin, entrants(5), vec![RoundSettings { level: 0, evaluator: EvaluatorKind::Heuristic }]).is_err());
+        let mut met = std::collections::HashSet::new();
+        for p in t.pairings().iter().filter(|p| p.p2.is_some()) {
+            let (a, b) = (p.p1.min(p.p2.unwrap()), p.p1.max(p.p2.unwrap()));
+            assert!(met.insert((a, b)), "{:?} scheduled twice", (a, b));
+        }
+        assert_eq!(10, met.len());
+        assert!(!t.pairings().iter().any(|p| p.p2.is_none()));
+    }
+
+    #[test]
+    fn test_knockout() {
+        let mut t = Tournament::new(Format::Knockout, entrants(3), settings()).unwrap();
+        assert_eq!(2, t.pairings().len());
+        assert!(t.record_result(0, Outcome::Draw).is_err());
+        t.record_result(0, Outcome::P2Wins).unwrap();
+        assert!(t.record_result(0, Outcome::P2Wins).is_err());
+
+        // winner of game 0 meets player 2 who had a bye
+        let final_ = t.pairings()[2].clone();
+        assert_eq!((1, Some(2)), (final_.p1, final_.p2));
+        t.record_result(2, Outcome::P1Wins).unwrap();
+        assert!(t.is_finished());
+
+        let standings = t.standings();
+        assert_eq!(1, standings[0].entrant);
+        assert_eq!(2., standings[0].points);
+        assert!(standings.iter().filter(|s| s.entrant != 1).all(|s| s.eliminated));
+    }
+
+    #[test]
+    fn test_engine_pairing() {
+        let mut players = entrants(1);
+        players.push(Entrant { name: "engine a".into(), kind: EntrantKind::Engine });
+        players.push(Entrant { name: "engine b".into(), kind: EntrantKind::Engine });
+        let mut t = Tournament::new(Format::RoundRobin, players, settings()).unwrap();
+        let engine_pairing = t.pairings().iter().find(|p| p.p1 != 0 && p.p2.is_some_and(|p2| p2 != 0)).unwrap().id;
+        let human_pairing = t.pairings().iter().find(|p| p.p1 == 0 || p.p2 == Some(0)).unwrap().id;
+        let outcome = t.engine_match(engine_pairing).unwrap().play().unwrap();
+        t.record_result(engine_pairing, outcome).unwrap();
+        assert!(t.pairing(engine_pairing).unwrap().result.is_some());
+        assert!(t.engine_match(human_pairing).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use std::{path::{Path, PathBuf}, str::FromStr};
+
+use tracing::Subscriber;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{filter::LevelFilter, fmt, prelude::*, reload, Registry};
+
+pub const SEARCH_LOG_FILE: &str = "search.log";
+
+/// File log of the engine searches. Disabled until a level is set, e.g. to capture a search for a bug report.
+pub struct SearchLog {
+    filter: reload::Handle<LevelFilter, Registry>,
+    path: PathBuf,
+    // flushes the log file when dropped
+    _guard: WorkerGuard,
+}
+
+impl SearchLog {
+    /// Sets up the subscriber writing to `dir`, without installing it.
+    fn build(dir:&Path) -> (impl Subscriber + Send + Sync, SearchLog) {
+        let (writer, guard) = tracing_appender::non_blocking(tracing_appender::rolling::never(dir, SEARCH_LOG_FILE));
+        let (filter, handle) = reload::Layer::new(LevelFilter::OFF);
+        let subscriber = tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer().with_writer(writer).with_ansi(false));
+        let log = SearchLog { filter: handle, path: dir.join(SEARCH_LOG_FILE), _guard: guard };
+        (subscriber, log)
+    }
+
+    /// Installs the log as the global subscriber.
+    pub fn init(dir:&Path) -> Result<SearchLog, String> {
+        let (subscriber, log) = SearchLog::build(dir);
+        tracing::subscriber::set_global_default(subscriber).map_err(|e| e.to_string())?;
+        Ok(log)
+    }
+
+    /// Accepts the level names of `tracing`, e.g. "debug" for every completed depth or "trace" for every cutoff, and "off".
+    pub fn set_level(&self, level:&str) -> Result<(), String> {
+        let level = LevelFilter::from_str(level).map_err(|e| e.to_string())?;
+        self.filter.modify(|filter| *filter = level).map_err(|e| e.to_string())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_set_level() {
+        let dir = std::env::temp_dir().join(format!("connect-four-log-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (subscriber, log) = SearchLog::build(&dir);
+        let path = log.path().to_owned();
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("not captured");
+            log.set_level("debug").unwrap();
+            tracing::debug!(depth = 3, "captured");
+            tracing::trace!("too detailed");
+            log.set_level("verbose").unwrap_err();
+        });
+        drop(log);
+
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(!content.contains("not captured"));
+        assert!(content.contains("captured depth=3"));
+        assert!(!content.contains("too detailed"));
+    }
+}
@@ -1,61 +1,1358 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod actor;
+mod benchmark;
+#[cfg(feature = "ts-bindings")]
+mod bindings;
+mod capabilities;
+mod chat;
+mod clock;
+mod codes;
+mod corpus;
+mod drills;
+mod editor;
 mod engine;
+mod local_tournament;
+mod logging;
+mod memory;
 mod minimax;
+mod mistakes;
+mod objective;
+mod openings;
+mod personality;
 mod playfield;
+mod power;
+mod presets;
+mod puzzles;
+mod rubber_band;
+mod savegame;
+mod selfplay;
+mod series;
+mod session;
+mod settings;
+mod stats;
+mod tuner;
+mod worker;
 
-use std::sync::Mutex;
-use playfield::{Game, GameState};
-use tauri::Window;
+use std::collections::HashMap;
+use std::sync::{atomic::{AtomicU32, Ordering}, Mutex};
+use std::time::{Duration, Instant};
+use actor::Actor;
+use chat::{ChatRelay, WordFilter};
+use clock::ClockSettings;
+use drills::{Drill, DrillScore};
+use editor::Editor;
+use engine::{EvalWeights, EvaluatorKind, Rules, Variant};
+use logging::SearchLog;
+use personality::Personality;
+use local_tournament::{Entrant, EntrantKind, Format, Outcome, Pairing, RoundSettings, Standing, Tournament};
+use playfield::{BoardSnapshot, ColumnPreview, Game, GameError, GameState, GameStatus, HandicapCell, MoveRecord, Position, Replay};
+use presets::{Preset, PresetManager};
+use puzzles::{Puzzle, PuzzleAttempt, PuzzleResult};
+use savegame::{Autosave, SavedGame};
+use series::Series;
+use session::{Command, ReplayReport, SessionRecorder, Step};
+use settings::{PausePolicy, Settings, SettingsStore, StartMode, DEFAULT_LEVEL};
+use stats::{ArchiveFormat, GameRecord, Stats, StatsStore};
+use std::path::PathBuf;
+use serde::Deserialize;
+use tauri::{async_runtime, AppHandle, ClipboardManager, Manager, Window, WindowEvent};
+use tracing::warn;
+use worker::EngineWorker;
+
+const WEIGHTS_FILE: &str = "eval_weights.toml";
+/// Words masked in chat messages, one per line, see `WordFilter`.
+const CHAT_FILTER_FILE: &str = "chat_filter.txt";
+const PRESETS_DIR: &str = "presets";
+const STATS_FILE: &str = "stats.json";
+const SETTINGS_FILE: &str = "settings.json";
+const AUTOSAVE_FILE: &str = "autosave.json";
+const MAIN_WINDOW: &str = "main";
+/// Interval of the clock updates while a timed game runs.
+const CLOCK_TICK: Duration = Duration::from_millis(200);
+/// Interval of the checks whether the computer was plugged in or out, see `pause_background`.
+const POWER_POLL: Duration = Duration::from_secs(10);
+
+/// Identifies one of the games played at the same time, see `create_game`.
+type GameId = u32;
+/// The game which exists from the start, commands which are not given a game refer to it.
+const MAIN_GAME: GameId = 0;
+
+/// A game together with the modes it is played in.
+struct GameSlot {
+    id: GameId,
+    game: Game,
+    /// The side of the human, the computer plays the other one.
+    human_player: playfield::CellState,
+    drill: Option<Drill>,
+    series: Option<Series>,
+    editor: Option<Editor>,
+    puzzle: Option<PuzzleAttempt>,
+    replay: Option<Replay>,
+}
+
+impl GameSlot {
+    fn new(id:GameId, game:Game) -> GameSlot {
+        GameSlot {
+            id,
+            game,
+            human_player: playfield::CellState::P1,
+            drill: None,
+            series: None,
+            editor: None,
+            puzzle: None,
+            replay: None,
+        }
+    }
+
+    fn computer(&self) -> playfield::CellState {
+        self.human_player.opponent()
+    }
+
+    /// Ends the drill, series and puzzle, when a game is set up which is not part of them.
+    fn leave_modes(&mut self) {
+        self.drill = None;
+        self.series = None;
+        self.puzzle = None;
+    }
+
+    /// Counts a finished game in the running series and emits the new score.
+    fn update_series(&mut self, window:&Window) {
+        let human_won = self.game.winner().map(|winner| winner == self.human_player as i8);
+        let Some(series) = self.series.as_mut() else {
+            return;
+        };
+        if series.record(human_won) {
+            emit_series(series, window);
+        }
+    }
+
+    /// Appends a step to the session log if recording is enabled. Only the main game is recorded, as a session
+    /// is replayed against a single game.
+    fn record<T>(&self, window:&Window, command:Command, result:&Result<T, GameError>) {
+        if self.id != MAIN_GAME {
+            return;
+        }
+        if let Some(recorder) = window.state::<PlayfieldState>().recorder.lock().unwrap().as_mut() {
+            if let Err(e) = recorder.record(&Step::new(command, &self.game, result.as_ref().err().map(|e| e.to_string()))) {
+                warn!("could not record session: {}", e);
+            }
+        }
+    }
+
+    /// Writes the main game to the autosave file after a move, the file is removed once there is no game in progress.
+    /// Like in `record` the other games are left out.
+    fn autosave(&self, window:&Window) {
+        if self.id != MAIN_GAME {
+            return;
+        }
+        let result = autosave_store(&window.app_handle()).and_then(|autosave| match self.game.state() {
+            GameState::Running => autosave.store(&self.game.to_saved()?),
+            // saved once the computer's move is played
+            GameState::Calculating => Ok(()),
+            GameState::Blank | GameState::Finished | GameState::Draw => autosave.clear(),
+        });
+        if let Err(e) = result {
+            warn!("could not autosave: {}", e);
+        }
+    }
+
+    /// Counts a game which has just ended in the running series and the statistics.
+    fn game_over(&mut self, window:&Window) {
+        if !self.game.state().is_over() {
+            return;
+        }
+        self.update_series(window);
+        // puzzles start from the middle of a game and are not part of the record
+        if self.game.hotseat() || self.puzzle.is_some() {
+            return;
+        }
+        let record = GameRecord::new(self.game.level(), self.human_player as i8, self.game.winner(), self.game.moves_played())
+            .with_history(self.game.history());
+        if let Err(e) = stats_store(&window.app_handle()).and_then(|store| store.add(record)) {
+            warn!("could not record statistics: {}", e);
+        }
+    }
+}
 
 // Mutex for interior mutability
 struct PlayfieldState {
-    playfield: Mutex<Game>,
-    human_player: playfield::CellState,
-    computer_player: playfield::CellState,
+    /// Only locked to look a game up, each game is owned by an actor of its own.
+    games: Mutex<HashMap<GameId, GameHandle>>,
+    /// The game of each window by its label, which commands of the window refer to unless they name another one.
+    window_games: Mutex<HashMap<String, GameId>>,
+    next_game_id: AtomicU32,
+    tournament: Mutex<Option<Tournament>>,
+    recorder: Mutex<Option<SessionRecorder>>,
+    search_log: Mutex<Option<SearchLog>>,
+    engine: EngineWorker,
+    /// From the settings, kept here to not read them on every window event.
+    live_eval_pause: Mutex<PausePolicy>,
+    chat: Mutex<ChatRelay>,
+}
+
+/// The actor owning a game. Commands queue their work on it, so a command waits for the ones before on the same game
+/// but never for another game, and reads are not held up by a search, which runs on the engine thread.
+struct GameHandle {
+    /// Label of the window showing the game, which receives the updates not caused by a command.
+    window: String,
+    actor: Actor<GameSlot>,
+}
+
+impl PlayfieldState {
+    /// Runs `f` on the game `id`, the game of `window` if none is given, and waits for the result. `f` gets a copy
+    /// of `window`. For synchronous commands, async ones use `with_game_async`.
+    fn with_game<T:Send + 'static>(
+        &self,
+        window:&Window,
+        id:Option<GameId>,
+        f:impl FnOnce(&mut GameSlot, Window) -> Result<T, GameError> + Send + 'static,
+    ) -> Result<T, GameError> {
+        let window = window.clone();
+        self.actor(self.game_id(&window, id))?.blocking_call(move |slot| f(slot, window))?
+    }
+
+    async fn with_game_async<T:Send + 'static>(
+        &self,
+        window:&Window,
+        id:Option<GameId>,
+        f:impl FnOnce(&mut GameSlot, Window) -> Result<T, GameError> + Send + 'static,
+    ) -> Result<T, GameError> {
+        let window = window.clone();
+        self.actor(self.game_id(&window, id))?.call(move |slot| f(slot, window)).await?
+    }
+
+    fn with_game_id<T:Send + 'static>(&self, id:GameId, f:impl FnOnce(&mut GameSlot) -> Result<T, GameError> + Send + 'static) -> Result<T, GameError> {
+        self.actor(id)?.blocking_call(f)?
+    }
+
+    /// `id` if given, the game of `window` otherwise. A window without a game yet gets a new one.
+    fn game_id(&self, window:&Window, id:Option<GameId>) -> GameId {
+        match id {
+            Some(id) => id,
+            None => *self.window_games.lock().unwrap().entry(window.label().to_owned())
+                .or_insert_with(|| self.open_game(window.label(), DEFAULT_LEVEL)),
+        }
+    }
+
+    fn actor(&self, id:GameId) -> Result<Actor<GameSlot>, GameError> {
+        self.games.lock().unwrap().get(&id).map(|handle| handle.actor.clone()).ok_or(GameError::UnknownGame(id))
+    }
+
+    /// Adds a game whose updates are sent to the window `label` and returns its id.
+    fn open_game(&self, label:&str, level:u8) -> GameId {
+        let id = self.next_game_id.fetch_add(1, Ordering::Relaxed);
+        let main = self.actor(MAIN_GAME).ok();
+        let actor = Actor::spawn(async move {
+            let mut game = Game::new(level);
+            // the engine plays with the same weights and style on every board
+            let style = match main {
+                Some(main) => Some(main.call(|slot| (slot.game.personality(), slot.game.weights())).await),
+                None => None,
+            };
+            match style {
+                Some(Ok((Some(personality), _))) => game.set_personality(personality),
+                Some(Ok((None, weights))) => game.set_weights(weights),
+                Some(Err(_)) | None => (),
+            }
+            GameSlot::new(id, game)
+        });
+        self.games.lock().unwrap().insert(id, GameHandle { window: label.to_owned(), actor });
+        id
+    }
+
+    /// Lets the computer move, unless both players are human. The game's actor runs other commands while the engine
+    /// thread searches, one which changes the game in the meantime discards the search.
+    async fn auto_play(&self, id:Option<GameId>, window:&Window) -> Result<(), GameError> {
+        let pending = self.with_game_async(window, id, |slot, window| match slot.game.hotseat() {
+            true => Ok(None),
+            false => slot.game.start_auto_play(slot.computer(), Some(&window)).map(Some),
+        }).await?;
+        let Some(pending) = pending else {
+            return Ok(());
+        };
+        let searched = self.engine.search(pending).await?;
+        self.with_game_async(window, id, |slot, window| {
+            let result = slot.game.finish_auto_play(searched, Some(&window));
+            if let Ok(col) = result {
+                slot.record(&window, Command::PlayCol { col, player: slot.computer() }, &result);
+            }
+            result?;
+            slot.autosave(&window);
+            slot.game_over(&window);
+            Ok(())
+        }).await
+    }
+}
+
+fn emit_series(series:&Series, window:&Window) {
+    if let Err(e) = window.emit(codes::EVENT_SERIES, series.score()) {
+        warn!("could not emit series score: {}", e);
+    }
+}
+
+/// Rejects a level the engine does not play, like `Settings::validate`.
+fn validate_level(level:u8) -> Result<(), GameError> {
+    match (engine::MIN_LEVEL..=engine::MAX_LEVEL).contains(&level) {
+        true => Ok(()),
+        false => Err(format!("level must be between {} and {}", engine::MIN_LEVEL, engine::MAX_LEVEL).into()),
+    }
 }
 
+/// Opens another game next to the one of the calling window, e.g. an analysis board, and returns its id. Its updates
+/// are sent to the calling window.
 #[tauri::command]
-fn play_col(
+fn create_game(state:tauri::State<'_, PlayfieldState>, window:Window, level:Option<u8>) -> Result<GameId, GameError> {
+    let level = level.unwrap_or(DEFAULT_LEVEL);
+    validate_level(level)?;
+    Ok(state.open_game(window.label(), level))
+}
+
+/// Closes a game opened with `create_game`, a search for it is stopped.
+#[tauri::command]
+fn close_game(state:tauri::State<'_, PlayfieldState>, game:GameId) -> Result<(), GameError> {
+    if game == MAIN_GAME {
+        return Err("the main game cannot be closed".into());
+    }
+    let handle = state.games.lock().unwrap().remove(&game).ok_or(GameError::UnknownGame(game))?;
+    // the actor stops after the jobs queued before, the search is not one of them
+    handle.actor.blocking_call(|slot| slot.game.cancel_token().store(true, Ordering::Relaxed))?;
+    // a window whose game was closed gets a new one with its next command
+    state.window_games.lock().unwrap().retain(|_, id| *id != game);
+    Ok(())
+}
+
+/// Plays a move of the human. In hotseat games `player` names which of the two humans moves, who have to alternate.
+#[tauri::command]
+async fn play_col(
     state:tauri::State<'_, PlayfieldState>,
     window: Window,
-    col:usize
-) -> Result<(), String> {
-    let mut playfield = state.playfield.lock().unwrap();
-    let game_state = playfield.play_col(col, state.human_player, Some(&window))?;
+    col:usize,
+    player:Option<playfield::CellState>,
+    game:Option<GameId>,
+) -> Result<(), GameError> {
+    // while the computer calculates the move is rejected as `EngineBusy`, the search runs outside of the game's actor
+    let running = state.with_game_async(&window, game, move |slot, window| {
+        if slot.editor.is_some() {
+            return Err(GameError::Editing);
+        }
+        let player = player.unwrap_or(slot.human_player);
+        if !slot.game.hotseat() && player != slot.human_player {
+            return Err("the computer's moves cannot be played".into());
+        }
+        let result = slot.game.play_annotated(col, player, Some(&window));
+        slot.record(&window, Command::PlayCol { col, player }, &result);
+        slot.autosave(&window);
+
+        match result? {
+            GameState::Finished | GameState::Draw => {
+                slot.game_over(&window);
+                Ok(false)
+            },
+            GameState::Blank | GameState::Calculating => Err("Cannot be blank or calculating".into()),
+            GameState::Running => Ok(true),
+        }
+    }).await?;
+    if running {
+        state.auto_play(game, &window).await?;
+        state.with_game_async(&window, game, |slot, window| {
+            slot.game.start_live_eval(Some(&window));
+            Ok(())
+        }).await?;
+    }
+    Ok(())
+}
 
-    match game_state {
-        GameState::Finished => Ok(()),
-        GameState::Blank | GameState::Calculating => Err("Cannot be blank or calculating".into()),
-        GameState::Running => playfield.auto_play(state.computer_player, Some(&window))
+/// Exchanges the sides of the human and the computer, which moves right away if it is its turn now.
+/// Returns the human's new side.
+#[tauri::command]
+async fn swap_sides(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<playfield::CellState, GameError> {
+    let (human, computer_to_move) = state.with_game_async(&window, game, |slot, _| {
+        slot.human_player = slot.human_player.opponent();
+        let computer_to_move = matches!(slot.game.state(), GameState::Blank | GameState::Running)
+            && slot.game.position().to_move == slot.computer();
+        Ok((slot.human_player, computer_to_move))
+    }).await?;
+    if computer_to_move {
+        state.auto_play(game, &window).await?;
     }
+    Ok(human)
+}
+
+/// Takes back the human's last move together with the computer's reply, only the last move in hotseat games.
+#[tauri::command]
+fn undo(
+    state:tauri::State<'_, PlayfieldState>,
+    window: Window,
+    game:Option<GameId>,
+) -> Result<Vec<usize>, GameError> {
+    state.with_game(&window, game, move |slot, window| {
+        let player = match slot.game.hotseat() {
+            true => slot.game.position().to_move.opponent(),
+            false => slot.human_player,
+        };
+        let result = slot.game.undo(player, Some(&window));
+        slot.record(&window, Command::Undo { player }, &result);
+        slot.autosave(&window);
+        slot.game.start_live_eval(Some(&window));
+        result
+    })
+}
+
+/// Takes back the computer's last reply and the human's move before it, in one step.
+#[tauri::command]
+fn takeback(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<Vec<usize>, GameError> {
+    state.with_game(&window, game, move |slot, window| {
+        let result = slot.game.takeback(slot.human_player, Some(&window));
+        slot.record(&window, Command::Undo { player: slot.human_player }, &result);
+        slot.autosave(&window);
+        slot.game.start_live_eval(Some(&window));
+        result
+    })
 }
 
+/// Gives the game up, the computer is recorded as the winner. In hotseat games the player to move resigns.
 #[tauri::command]
-fn new_game(
+fn resign(
     state:tauri::State<'_, PlayfieldState>,
     window: Window,
-    level:u8,
-    starting_player:i8,
-) -> Result<(), String> {
-    let mut playfield = state.playfield.lock().unwrap();
-    playfield.reset(level, Some(&window))?;
+    game:Option<GameId>,
+) -> Result<(), GameError> {
+    state.with_game(&window, game, move |slot, window| {
+        let player = match slot.game.hotseat() {
+            true => slot.game.position().to_move,
+            false => slot.human_player,
+        };
+        let result = slot.game.resign(player, Some(&window));
+        slot.record(&window, Command::Resign { player }, &result);
+        slot.autosave(&window);
+        result?;
+        slot.game_over(&window);
+        Ok(())
+    })
+}
+
+/// Settings of `new_game`, all but `level` and `starting_player` may be left out.
+/// `start_mode` decides who moves first, by default `starting_player` does. `best_of` starts a series of that many
+/// games, `continue_series` starts the next game of the running series instead, in which the starting player
+/// alternates and overrides both. With `adaptive` the level matching the human's rating replaces `level`.
+#[derive(Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+struct NewGameOptions {
+    level: u8,
+    starting_player: i8,
+    start_mode: Option<StartMode>,
+    evaluator: Option<EvaluatorKind>,
+    rubber_band: Option<bool>,
+    mistakes: Option<bool>,
+    personality: Option<Personality>,
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number | null"))]
+    seed: Option<u64>,
+    win_length: Option<usize>,
+    variant: Option<Variant>,
+    hotseat: Option<bool>,
+    clock: Option<ClockSettings>,
+    best_of: Option<u32>,
+    continue_series: Option<bool>,
+    adaptive: Option<bool>,
+    handicap: Option<Vec<HandicapCell>>,
+}
+
+/// Starts a game, see `NewGameOptions`.
+#[tauri::command]
+async fn new_game(
+    state:tauri::State<'_, PlayfieldState>,
+    window: Window,
+    options:NewGameOptions,
+    game:Option<GameId>,
+) -> Result<(), GameError> {
+    let NewGameOptions {
+        level, starting_player, start_mode, evaluator, rubber_band, mistakes, personality, seed, win_length, variant,
+        hotseat, clock, best_of, continue_series, adaptive, handicap,
+    } = options;
+    let handicap = handicap.unwrap_or_default();
+    let level = match adaptive.unwrap_or(false) {
+        true => stats::adaptive_level(stats::rating(&stats_store(&window.app_handle())?.games()?)),
+        false => level,
+    };
+    // checked before the game is touched, so invalid settings leave it as it was
+    validate_level(level)?;
+    clock.as_ref().map_or(Ok(()), ClockSettings::validate)?;
+    let evaluator = evaluator.unwrap_or_default();
+    let rubber_band = rubber_band.unwrap_or(false);
+    let hotseat = hotseat.unwrap_or(false);
+    let rules = Rules { win_length: win_length.unwrap_or(engine::WIN_LENGTH), variant: variant.unwrap_or_default() };
+    rules.validate()?;
+    let settings = settings_store(&window.app_handle())?;
+    let last_human_started = settings.load().map(|s| s.last_human_started).unwrap_or_else(|e| {
+        warn!("could not load settings: {}", e);
+        None
+    });
+    let computer_starts = state.with_game_async(&window, game, move |slot, window| {
+        let human_starts = start_mode.unwrap_or_default()
+            .human_starts(starting_player != slot.computer() as i8, last_human_started, &mut rand::thread_rng());
+        let computer_starts = match (continue_series.unwrap_or(false), best_of) {
+            (true, _) => !slot.series.as_mut().ok_or("no series running")?.next_game()?,
+            (false, Some(best_of)) => {
+                slot.series = Some(Series::new(best_of, human_starts)?);
+                !human_starts
+            },
+            (false, None) => {
+                slot.series = None;
+                !human_starts
+            },
+        };
+        if let Some(series) = slot.series.as_ref() {
+            emit_series(series, &window);
+        }
+        slot.drill = None;
+        slot.puzzle = None;
+        slot.editor = None;
+        slot.game.set_rubber_band(rubber_band);
+        slot.game.set_mistakes(mistakes.unwrap_or(false));
+        // without a personality the computer keeps its weights
+        if let Some(personality) = personality {
+            slot.game.set_personality(personality);
+        }
+        slot.game.set_hotseat(hotseat);
+        slot.game.set_clock(clock)?;
+        slot.game.set_seed(seed);
+        let result = slot.game.set_rules(rules)
+            .and_then(|_| slot.game.reset(level, evaluator, Some(&window)))
+            .and_then(|_| slot.game.set_handicap(&handicap, Some(&window)))
+            .map_err(GameError::from);
+        slot.record(&window, Command::NewGame { level, evaluator, rubber_band, rules, hotseat, handicap }, &result);
+        slot.autosave(&window);
+        result?;
+        Ok(computer_starts)
+    }).await?;
+    let stored = settings.load().and_then(|mut s| {
+        s.last_human_started = Some(!computer_starts);
+        settings.save(&s)
+    });
+    if let Err(e) = stored {
+        warn!("could not store settings: {}", e);
+    }
 
-    if starting_player == state.computer_player as i8 {
-        return playfield.auto_play(state.computer_player, Some(&window))
+    if computer_starts {
+        return state.auto_play(game, &window).await
     }
     Result::Ok(())
 }
 
+#[tauri::command]
+fn create_tournament(
+    state:tauri::State<'_, PlayfieldState>,
+    format:Format,
+    entrants:Vec<Entrant>,
+    round_settings:Vec<RoundSettings>,
+) -> Result<Vec<Pairing>, GameError> {
+    let tournament = Tournament::new(format, entrants, round_settings)?;
+    let pairings = tournament.pairings().to_vec();
+    *state.tournament.lock().unwrap() = Some(tournament);
+    Ok(pairings)
+}
+
+#[tauri::command]
+fn get_pairings(state:tauri::State<'_, PlayfieldState>) -> Result<Vec<Pairing>, GameError> {
+    let tournament = state.tournament.lock().unwrap();
+    tournament.as_ref().map(|t| t.pairings().to_vec()).ok_or("no tournament running".into())
+}
+
+#[tauri::command]
+fn get_standings(state:tauri::State<'_, PlayfieldState>) -> Result<Vec<Standing>, GameError> {
+    let tournament = state.tournament.lock().unwrap();
+    tournament.as_ref().map(|t| t.standings()).ok_or("no tournament running".into())
+}
+
+#[tauri::command]
+fn record_tournament_result(
+    state:tauri::State<'_, PlayfieldState>,
+    pairing_id:usize,
+    outcome:Outcome,
+) -> Result<(), GameError> {
+    let mut tournament = state.tournament.lock().unwrap();
+    Ok(tournament.as_mut().ok_or("no tournament running")?.record_result(pairing_id, outcome)?)
+}
+
+/// Starts the game of a pairing with the settings of its round.
+/// Engine vs engine pairings are played out on a blocking task and their outcome is returned.
+#[tauri::command]
+async fn start_tournament_game(
+    state:tauri::State<'_, PlayfieldState>,
+    window: Window,
+    pairing_id:usize,
+    game:Option<GameId>,
+) -> Result<Option<Outcome>, GameError> {
+    let (p1_kind, settings, engine_match) = {
+        let tournament = state.tournament.lock().unwrap();
+        let tournament = tournament.as_ref().ok_or("no tournament running")?;
+        let pairing = tournament.pairing(pairing_id)?.clone();
+        let p2 = pairing.p2.ok_or("pairing is a bye")?;
+        let settings = tournament.settings_for(pairing_id)?;
+
+        match (tournament.entrants()[pairing.p1].kind, tournament.entrants()[p2].kind) {
+            (EntrantKind::Engine, EntrantKind::Engine) => (EntrantKind::Engine, settings, Some(tournament.engine_match(pairing_id)?)),
+            (EntrantKind::Human, EntrantKind::Human) => return Err("human vs human pairings are not supported on this board".into()),
+            (p1_kind, _) => (p1_kind, settings, None),
+        }
+    };
+    // the tournament is not locked while the engines play
+    if let Some(engine_match) = engine_match {
+        let outcome = async_runtime::spawn_blocking(move || engine_match.play()).await.map_err(|e| e.to_string())??;
+        let mut tournament = state.tournament.lock().unwrap();
+        tournament.as_mut().ok_or("no tournament running")?.record_result(pairing_id, outcome)?;
+        return Ok(Some(outcome));
+    }
+    let engine_starts = state.with_game_async(&window, game, move |slot, window| {
+        slot.leave_modes();
+        // tournament games are always played at full strength and by the standard rules
+        slot.game.set_rubber_band(false);
+        slot.game.set_mistakes(false);
+        slot.game.set_hotseat(false);
+        slot.game.set_clock(None)?;
+        let result = slot.game.set_rules(Rules::default()).and_then(|_| slot.game.reset(settings.level, settings.evaluator, Some(&window))).map_err(GameError::from);
+        let command = Command::NewGame { level: settings.level, evaluator: settings.evaluator, rubber_band: false, rules: Rules::default(), hotseat: false, handicap: Vec::new() };
+        slot.record(&window, command, &result);
+        result?;
+        Ok(p1_kind == EntrantKind::Engine)
+    }).await?;
+    if engine_starts {
+        state.auto_play(game, &window).await?;
+    }
+    Ok(None)
+}
+
+/// Starts a practice game against an engine which never plays one secretly chosen column.
+/// The human always starts.
+#[tauri::command]
+fn start_drill(state:tauri::State<'_, PlayfieldState>, window: Window, level:u8, game:Option<GameId>) -> Result<(), GameError> {
+    validate_level(level)?;
+    let evaluator = EvaluatorKind::default();
+    state.with_game(&window, game, move |slot, window| {
+        slot.human_player = playfield::CellState::P1;
+        slot.game.set_rubber_band(false);
+        slot.game.set_mistakes(false);
+        slot.game.set_hotseat(false);
+        slot.game.set_clock(None)?;
+        let result = slot.game.set_rules(Rules::default()).and_then(|_| slot.game.reset(level, evaluator, Some(&window))).map_err(GameError::from);
+        slot.record(&window, Command::NewGame { level, evaluator, rubber_band: false, rules: Rules::default(), hotseat: false, handicap: Vec::new() }, &result);
+        result?;
+
+        let drill = Drill::generate(&mut rand::thread_rng());
+        slot.game.set_banned_column(Some(drill.column()));
+        slot.drill = Some(drill);
+        slot.series = None;
+        slot.puzzle = None;
+        Ok(())
+    })
+}
+
+/// Number of moves the human has made, who always starts a drill.
+fn human_moves(playfield:&Game) -> u32 {
+    (playfield.moves_played() as u32 + 1) / 2
+}
+
+#[tauri::command]
+fn guess_drill_column(state:tauri::State<'_, PlayfieldState>, window:Window, col:usize, game:Option<GameId>) -> Result<bool, GameError> {
+    state.with_game(&window, game, move |slot, _| {
+        let moves = human_moves(&slot.game);
+        Ok(slot.drill.as_mut().ok_or("no drill running")?.guess(col, moves))
+    })
+}
+
+#[tauri::command]
+fn get_drill_score(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<DrillScore, GameError> {
+    state.with_game(&window, game, |slot, _| {
+        let won = slot.game.winner() == Some(slot.human_player as i8);
+        let finished = slot.game.state().is_over();
+        Ok(slot.drill.as_ref().ok_or("no drill running")?.score(won, finished, human_moves(&slot.game)))
+    })
+}
+
+#[tauri::command]
+fn list_puzzles() -> &'static [Puzzle] {
+    puzzles::PUZZLES
+}
+
+/// Sets up the position of a puzzle, the human plays the side to move against the computer's defence
+/// on the current level.
+#[tauri::command]
+fn load_puzzle(state:tauri::State<'_, PlayfieldState>, window:Window, id:String, game:Option<GameId>) -> Result<(), GameError> {
+    let puzzle = puzzles::find(&id)?;
+    let position = Position::parse(puzzle.position)?;
+    state.with_game(&window, game, move |slot, window| {
+        slot.game.set_rubber_band(false);
+        slot.game.set_mistakes(false);
+        slot.game.set_hotseat(false);
+        slot.game.set_clock(None)?;
+        slot.game.set_rules(Rules::default())?;
+        slot.game.set_position(&position, Some(&window))?;
+        slot.human_player = position.to_move;
+        slot.drill = None;
+        slot.series = None;
+        slot.editor = None;
+        slot.puzzle = Some(PuzzleAttempt::new(puzzle, slot.game.moves_played()));
+        Ok(())
+    })
+}
+
+/// Whether the human has found the forced win of the puzzle within its move limit.
+#[tauri::command]
+fn check_puzzle_solution(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<PuzzleResult, GameError> {
+    state.with_game(&window, game, |slot, _| {
+        let won = slot.game.winner() == Some(slot.human_player as i8);
+        let finished = slot.game.state().is_over();
+        Ok(slot.puzzle.as_ref().ok_or("no puzzle loaded")?.result(won, finished, slot.game.moves_played()))
+    })
+}
+
+fn preset_manager(app:&AppHandle) -> Result<PresetManager, String> {
+    let dir = app.path_resolver().app_data_dir().ok_or("no app data directory available")?;
+    Ok(PresetManager::new(dir.join(PRESETS_DIR)))
+}
+
+#[tauri::command]
+fn list_presets(app:AppHandle) -> Result<Vec<Preset>, GameError> {
+    Ok(preset_manager(&app)?.list())
+}
+
+#[tauri::command]
+fn apply_preset(state:tauri::State<'_, PlayfieldState>, window:Window, app:AppHandle, name:String, game:Option<GameId>) -> Result<(), GameError> {
+    let preset = preset_manager(&app)?.get(&name)?;
+    state.with_game(&window, game, move |slot, _| {
+        slot.game.configure(preset.level, preset.evaluator, preset.weights);
+        Ok(())
+    })
+}
+
+/// Stores the current engine configuration as a preset and writes it to `path` for sharing.
+#[tauri::command]
+fn export_preset(state:tauri::State<'_, PlayfieldState>, window:Window, app:AppHandle, name:String, path:PathBuf, game:Option<GameId>) -> Result<(), GameError> {
+    let preset = state.with_game(&window, game, move |slot, _| {
+        Ok(Preset { name, level: slot.game.level(), evaluator: slot.game.evaluator(), weights: slot.game.weights() })
+    })?;
+    preset_manager(&app)?.store(&preset)?;
+    Ok(preset.save(&path)?)
+}
+
+#[tauri::command]
+fn import_preset(app:AppHandle, path:PathBuf) -> Result<Preset, GameError> {
+    Ok(preset_manager(&app)?.import(&path)?)
+}
+
+fn stats_store(app:&AppHandle) -> Result<StatsStore, String> {
+    let dir = app.path_resolver().app_data_dir().ok_or("no app data directory available")?;
+    Ok(StatsStore::new(dir.join(STATS_FILE)))
+}
+
+fn autosave_store(app:&AppHandle) -> Result<Autosave, String> {
+    let dir = app.path_resolver().app_data_dir().ok_or("no app data directory available")?;
+    Ok(Autosave::new(dir.join(AUTOSAVE_FILE)))
+}
+
+fn settings_store(app:&AppHandle) -> Result<SettingsStore, String> {
+    let dir = app.path_resolver().app_config_dir().ok_or("no app config directory available")?;
+    Ok(SettingsStore::new(dir.join(SETTINGS_FILE)))
+}
+
+/// The finished games against the computer, with the human's record per level.
+#[tauri::command]
+fn get_stats(app:AppHandle) -> Result<Stats, GameError> {
+    Ok(Stats::new(stats_store(&app)?.games()?))
+}
+
+/// Writes every recorded game with its moves, evaluations and times and the rating after it to `path`.
+#[tauri::command]
+fn export_archive(app:AppHandle, format:ArchiveFormat, path:PathBuf) -> Result<(), GameError> {
+    let games = stats_store(&app)?.games()?;
+    Ok(stats::export_archive(&games, format, &path)?)
+}
+
+#[tauri::command]
+fn reset_stats(app:AppHandle) -> Result<(), GameError> {
+    Ok(stats_store(&app)?.reset()?)
+}
+
+/// The preferences of the user, the defaults if none were stored yet.
+#[tauri::command]
+fn get_settings(app:AppHandle) -> Result<Settings, GameError> {
+    Ok(settings_store(&app)?.load()?)
+}
+
+/// Stores the preferences of the user, they apply to the main game from the next start of the app.
+#[tauri::command]
+fn set_settings(app:AppHandle, settings:Settings) -> Result<(), GameError> {
+    let store = settings_store(&app)?;
+    // which side started last is kept by new_game, not by the user
+    let last_human_started = store.load().map(|s| s.last_human_started).unwrap_or(None);
+    store.save(&Settings { last_human_started, ..settings.clone() })?;
+    let state = app.state::<PlayfieldState>();
+    *state.live_eval_pause.lock().unwrap() = settings.live_eval_pause;
+    for window in app.windows().values() {
+        pause_background(&state, window);
+    }
+    Ok(())
+}
+
+/// Suspends or resumes the background evaluation of the games shown in `window`, by whether it is minimized or the
+/// computer runs on battery.
+fn pause_background(state:&PlayfieldState, window:&Window) {
+    let minimized = window.is_minimized().unwrap_or(false);
+    let paused = state.live_eval_pause.lock().unwrap().paused(minimized, power::on_battery());
+    for game in state.games.lock().unwrap().values().filter(|game| game.window == window.label()) {
+        let window = window.clone();
+        game.actor.post(move |slot| slot.game.pause_live_eval(paused, Some(&window)));
+    }
+}
+
+/// Relays a chat message of `window` to every window once it passed the rate limit and the filter, see `ChatRelay`.
+#[tauri::command]
+fn send_chat(app:AppHandle, state:tauri::State<'_, PlayfieldState>, window:Window, text:String) -> Result<(), GameError> {
+    let message = state.chat.lock().unwrap().relay(window.label(), &text, Instant::now())?;
+    Ok(app.emit_all(codes::EVENT_CHAT_MESSAGE, message).map_err(|e| e.to_string())?)
+}
+
+/// Runs on a blocking thread, the benchmark takes seconds.
+#[tauri::command]
+async fn run_benchmark(perft_depth:Option<u8>) -> Result<benchmark::BenchmarkReport, GameError> {
+    let perft_depth = perft_depth.unwrap_or(benchmark::DEFAULT_PERFT_DEPTH);
+    Ok(async_runtime::spawn_blocking(move || benchmark::run(perft_depth)).await.map_err(|e| e.to_string())?)
+}
+
+/// Estimated heap memory of the major components, with one entry per open game.
+#[tauri::command]
+fn get_memory_report(state:tauri::State<'_, PlayfieldState>) -> memory::MemoryReport {
+    let mut report = memory::MemoryReport::default();
+    let mut games: Vec<(GameId, Actor<GameSlot>)> = state.games.lock().unwrap().iter()
+        .map(|(id, handle)| (*id, handle.actor.clone()))
+        .collect();
+    games.sort_by_key(|(id, _)| *id);
+    for (id, actor) in games {
+        let measured = actor.blocking_call(move |slot| {
+            let mut report = memory::MemoryReport::default();
+            report.add("game state", &slot.game);
+            if id == MAIN_GAME {
+                // every search sets up its own board with the precomputed sequences
+                report.add("search board", &engine::ConnectFour::empty(1, slot.game.evaluator().build(&slot.game.weights())));
+            }
+            report
+        });
+        // a game closed in the meantime is left out
+        if let Ok(measured) = measured {
+            report.merge(measured);
+        }
+    }
+    if let Some(tournament) = state.tournament.lock().unwrap().as_ref() {
+        report.add("tournament", tournament);
+    }
+    if let Some(recorder) = state.recorder.lock().unwrap().as_ref() {
+        report.add("session recorder", recorder);
+    }
+    report
+}
+
+/// Measures the steps of the app startup, see `benchmark::profile_startup`. Runs on a blocking thread, as the
+/// steps read files and set up the engine.
+#[tauri::command]
+async fn profile_startup(app:AppHandle) -> Result<benchmark::StartupProfile, GameError> {
+    let weights_file = app.path_resolver().app_config_dir().map(|dir| dir.join(WEIGHTS_FILE));
+    let presets_dir = app.path_resolver().app_data_dir().map(|dir| dir.join(PRESETS_DIR));
+    let profile = async_runtime::spawn_blocking(move || benchmark::profile_startup(weights_file.as_deref(), presets_dir.as_deref()));
+    Ok(profile.await.map_err(|e| e.to_string())?)
+}
+
+#[tauri::command]
+fn get_opening_name(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<Option<String>, GameError> {
+    state.with_game(&window, game, |slot, _| Ok(slot.game.opening().map(|o| o.to_owned())))
+}
+
+/// The landing row of each column, `None` for the columns which cannot be played.
+#[tauri::command]
+fn get_legal_moves(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<[Option<usize>; engine::WIDTH], GameError> {
+    state.with_game(&window, game, |slot, _| Ok(slot.game.legal_moves()))
+}
+
+/// The whole board of the game, so the frontend can resynchronize after missing events.
+#[tauri::command]
+fn get_board(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<BoardSnapshot, GameError> {
+    state.with_game(&window, game, |slot, _| Ok(slot.game.snapshot()))
+}
+
+/// Where a piece dropped into `col` would land and a quick evaluation of the position after it, while the human points
+/// at the column. Fails with `GameError::Cancelled` once another column is previewed or a move is played.
+#[tauri::command]
+async fn preview_col(state:tauri::State<'_, PlayfieldState>, window:Window, col:usize, game:Option<GameId>) -> Result<ColumnPreview, GameError> {
+    let pending = state.with_game_async(&window, game, move |slot, _| slot.game.start_preview(col)).await?;
+    // waited for on a blocking thread, the engine thread is kept free for the computer's moves
+    async_runtime::spawn_blocking(move || pending.wait()).await.map_err(|e| e.to_string())?
+}
+
+/// Whose turn it is, the settings of the game and whether the computer is thinking.
+#[tauri::command]
+fn get_status(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<GameStatus, GameError> {
+    state.with_game(&window, game, |slot, _| Ok(slot.game.status()))
+}
+
+/// Writes the game to `path`, so it can be continued after restarting the app.
+#[tauri::command]
+fn save_game(state:tauri::State<'_, PlayfieldState>, window:Window, path:PathBuf, game:Option<GameId>) -> Result<(), GameError> {
+    let saved = state.with_game(&window, game, |slot, _| slot.game.to_saved())?;
+    Ok(saved.save(&path)?)
+}
+
+/// Continues a game saved with `save_game`. An invalid file is rejected and leaves the current game alone.
+#[tauri::command]
+fn load_game(state:tauri::State<'_, PlayfieldState>, window:Window, path:PathBuf, game:Option<GameId>) -> Result<(), GameError> {
+    let saved = SavedGame::load(&path)?;
+    state.with_game(&window, game, move |slot, window| {
+        slot.leave_modes();
+        slot.editor = None;
+        Ok(slot.game.load(&saved, Some(&window))?)
+    })
+}
+
+/// The moves of the game as column digits counting from 1, to analyze the game with other tools.
+#[tauri::command]
+fn export_notation(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<String, GameError> {
+    state.with_game(&window, game, |slot, _| Ok(slot.game.notation()))
+}
+
+/// Whether the main game was left unfinished when the app was closed or crashed, see `resume_autosave`.
+#[tauri::command]
+fn has_autosave(app:AppHandle) -> Result<bool, GameError> {
+    Ok(autosave_store(&app)?.exists())
+}
+
+/// Continues the main game where it was left by the last run of the app. The computer replies if it is to move.
+#[tauri::command]
+async fn resume_autosave(state:tauri::State<'_, PlayfieldState>, window:Window, app:AppHandle) -> Result<(), GameError> {
+    let saved = autosave_store(&app)?.load()?;
+    state.with_game_async(&window, Some(MAIN_GAME), move |slot, window| {
+        slot.leave_modes();
+        slot.editor = None;
+        Ok(slot.game.load(&saved, Some(&window))?)
+    }).await?;
+    computer_reply(&state, &window, Some(MAIN_GAME)).await
+}
+
+/// Lets the computer reply to a position which was just set up, if it is to move.
+async fn computer_reply(state:&PlayfieldState, window:&Window, game:Option<GameId>) -> Result<(), GameError> {
+    let computer_to_move = state.with_game_async(window, game, |slot, _| {
+        Ok(slot.game.state() == GameState::Running && slot.game.position().to_move == slot.computer())
+    }).await?;
+    match computer_to_move {
+        true => state.auto_play(game, window).await,
+        false => Ok(()),
+    }
+}
+
+/// Starts over with the position of a column digit string. The computer replies if it is to move.
+#[tauri::command]
+async fn import_notation(state:tauri::State<'_, PlayfieldState>, window:Window, moves:String, game:Option<GameId>) -> Result<(), GameError> {
+    state.with_game_async(&window, game, move |slot, window| {
+        slot.leave_modes();
+        Ok(slot.game.import_notation(&moves, Some(&window))?)
+    }).await?;
+    computer_reply(&state, &window, game).await
+}
+
+/// Copies the position of the game to the clipboard and returns it, see `Position`.
+#[tauri::command]
+fn copy_position(state:tauri::State<'_, PlayfieldState>, window:Window, app:AppHandle, game:Option<GameId>) -> Result<String, GameError> {
+    let position = state.with_game(&window, game, |slot, _| Ok(slot.game.position().to_string()))?;
+    app.clipboard_manager().write_text(position.clone()).map_err(|e| e.to_string())?;
+    Ok(position)
+}
+
+/// Starts over with the position on the clipboard. The computer replies if it is to move.
+#[tauri::command]
+async fn paste_position(state:tauri::State<'_, PlayfieldState>, app:AppHandle, window:Window, game:Option<GameId>) -> Result<(), GameError> {
+    let text = app.clipboard_manager().read_text().map_err(|e| e.to_string())?.ok_or("the clipboard holds no text")?;
+    let position = Position::parse(&text)?;
+    state.with_game_async(&window, game, move |slot, window| {
+        slot.leave_modes();
+        Ok(slot.game.set_position(&position, Some(&window))?)
+    }).await?;
+    computer_reply(&state, &window, game).await
+}
+
+/// Starts setting up a position, beginning with the board of the game. Moves cannot be played until the
+/// editing is finished.
+#[tauri::command]
+fn enter_edit_mode(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<(), GameError> {
+    state.with_game(&window, game, |slot, _| {
+        slot.editor = Some(Editor::new(slot.game.position().board));
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn set_cell(state:tauri::State<'_, PlayfieldState>, window:Window, row:usize, col:usize, cell_state:playfield::CellState, game:Option<GameId>) -> Result<(), GameError> {
+    state.with_game(&window, game, move |slot, window| {
+        Ok(slot.editor.as_mut().ok_or("not editing a position")?.set_cell(row, col, cell_state, Some(&window))?)
+    })
+}
+
+/// Starts over with the edited position, if it can occur in a game. The computer replies if it is to move.
+#[tauri::command]
+async fn finish_edit(state:tauri::State<'_, PlayfieldState>, window:Window, side_to_move:playfield::CellState, game:Option<GameId>) -> Result<(), GameError> {
+    state.with_game_async(&window, game, move |slot, window| {
+        let position = slot.editor.as_ref().ok_or("not editing a position")?.finish(side_to_move)?;
+        slot.game.set_position(&position, Some(&window))?;
+        slot.editor = None;
+        slot.leave_modes();
+        Ok(())
+    }).await?;
+    computer_reply(&state, &window, game).await
+}
+
+/// Shows the game, which has to be finished, from its start. Returns the number of moves to step through.
+#[tauri::command]
+fn start_replay(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<usize, GameError> {
+    state.with_game(&window, game, move |slot, window| {
+        let replay = Replay::new(&slot.game, Some(&window))?;
+        let moves = replay.len();
+        slot.replay = Some(replay);
+        Ok(moves)
+    })
+}
+
+fn with_replay(
+    state:&PlayfieldState,
+    window:&Window,
+    game:Option<GameId>,
+    step:impl FnOnce(&mut Replay, Option<&Window>) -> Result<usize, String> + Send + 'static,
+) -> Result<usize, GameError> {
+    state.with_game(window, game, move |slot, window| Ok(step(slot.replay.as_mut().ok_or("no replay started")?, Some(&window))?))
+}
+
+/// Shows one more move of the replay, returns the number of moves shown.
+#[tauri::command]
+fn replay_step_forward(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<usize, GameError> {
+    with_replay(&state, &window, game, |replay, window| replay.step_forward(window))
+}
+
+/// Takes back one move of the replay, returns the number of moves shown.
+#[tauri::command]
+fn replay_step_back(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<usize, GameError> {
+    with_replay(&state, &window, game, |replay, window| replay.step_back(window))
+}
+
+/// Shows the replayed game after `n` moves.
+#[tauri::command]
+fn replay_jump(state:tauri::State<'_, PlayfieldState>, window:Window, n:usize, game:Option<GameId>) -> Result<usize, GameError> {
+    with_replay(&state, &window, game, move |replay, window| replay.jump(n, window))
+}
+
+/// The moves of the game in the order they were played.
+#[tauri::command]
+fn get_history(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<Vec<MoveRecord>, GameError> {
+    state.with_game(&window, game, |slot, _| Ok(slot.game.history()))
+}
+
+/// What the engine did in its last search of the game.
+#[tauri::command]
+fn get_last_search_stats(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<minimax::SearchStats, GameError> {
+    state.with_game(&window, game, |slot, _| slot.game.last_stats().cloned().ok_or("no search in this game yet".into()))
+}
+
+/// Settings of the engine's last search in the game, which reproduce it if it was deterministic.
+#[tauri::command]
+fn get_last_search_config(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<minimax::ConfigSnapshot, GameError> {
+    state.with_game(&window, game, |slot, _| slot.game.last_config().cloned().ok_or("no search in this game yet".into()))
+}
+
+/// Stops the computer's search. Depending on `set_cancel_mode` the computer then plays the best move found so far
+/// or the human's last move is taken back, the interrupted command then fails with `GameError::Cancelled`.
+#[tauri::command]
+fn cancel_ai(state:tauri::State<'_, PlayfieldState>, window:Window, game:Option<GameId>) -> Result<(), GameError> {
+    state.with_game(&window, game, |slot, _| {
+        slot.game.cancel_token().store(true, Ordering::Relaxed);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn set_cancel_mode(state:tauri::State<'_, PlayfieldState>, window:Window, mode:playfield::CancelMode, game:Option<GameId>) -> Result<(), GameError> {
+    state.with_game(&window, game, move |slot, _| {
+        slot.game.set_cancel_mode(mode);
+        Ok(())
+    })
+}
+
+/// Keeps the evaluation bar current while the human thinks, by evaluating the position in the background.
+#[tauri::command]
+fn set_live_eval(state:tauri::State<'_, PlayfieldState>, window:Window, enabled:bool, game:Option<GameId>) -> Result<(), GameError> {
+    state.with_game(&window, game, move |slot, _| {
+        slot.game.set_live_eval(enabled);
+        Ok(())
+    })
+}
+
+/// Judges each move of the human against the evaluation before it, see `Update::Annotation`.
+#[tauri::command]
+fn set_annotations(state:tauri::State<'_, PlayfieldState>, window:Window, enabled:bool, game:Option<GameId>) -> Result<(), GameError> {
+    state.with_game(&window, game, move |slot, _| {
+        slot.game.set_annotations(enabled);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn set_tree_recording(state:tauri::State<'_, PlayfieldState>, window:Window, enabled:bool, game:Option<GameId>) -> Result<(), GameError> {
+    state.with_game(&window, game, move |slot, _| {
+        slot.game.set_tree_recording(enabled);
+        Ok(())
+    })
+}
+
+/// Writes the tree of the engine's last search to `path`, as Graphviz DOT for a `.dot` file and as JSON otherwise.
+#[tauri::command]
+fn export_search_tree(state:tauri::State<'_, PlayfieldState>, window:Window, path:PathBuf, game:Option<GameId>) -> Result<(), GameError> {
+    let dot = path.extension().and_then(|e| e.to_str()) == Some("dot");
+    let content = state.with_game(&window, game, move |slot, _| {
+        let tree = slot.game.last_tree().ok_or("no recorded search in this game yet")?;
+        match dot {
+            true => Ok(tree.to_dot()),
+            false => serde_json::to_string(tree).map_err(|e| e.to_string().into()),
+        }
+    })?;
+    Ok(std::fs::write(&path, content).map_err(|e| e.to_string())?)
+}
+
+/// The board sizes, variants, levels and engine features this backend supports.
+#[tauri::command]
+fn get_capabilities() -> capabilities::Capabilities {
+    capabilities::capabilities()
+}
+
+/// Every event name, error code and search stop reason the backend uses.
+#[tauri::command]
+fn list_codes() -> Vec<codes::Code> {
+    codes::CODES.to_vec()
+}
+
+/// Sets the level of the search log, "off" disables it. Returns the path of the log file.
+#[tauri::command]
+fn set_search_log(state:tauri::State<'_, PlayfieldState>, level:String) -> Result<PathBuf, GameError> {
+    let search_log = state.search_log.lock().unwrap();
+    let search_log = search_log.as_ref().ok_or("search log is not available")?;
+    search_log.set_level(&level)?;
+    Ok(search_log.path().to_owned())
+}
+
+/// Developer mode: records every command on the main game and its outcome to `path`, or stops recording if no path
+/// is given.
+#[tauri::command]
+fn record_session(state:tauri::State<'_, PlayfieldState>, path:Option<PathBuf>) -> Result<(), GameError> {
+    let recorder = path.map(|p| SessionRecorder::create(&p)).transpose()?;
+    *state.recorder.lock().unwrap() = recorder;
+    Ok(())
+}
+
+/// Replays a recorded session against a fresh game and reports where it diverges from the recording.
+#[tauri::command]
+fn replay_session(path:PathBuf) -> Result<ReplayReport, GameError> {
+    Ok(session::replay(&session::load_session(&path)?))
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let subcommand = match args.get(1).map(|a| a.as_str()) {
+        Some("selfplay") => Some(selfplay::run_cli as fn(&[String]) -> Result<(), String>),
+        Some("tune") => Some(tuner::run_cli as fn(&[String]) -> Result<(), String>),
+        Some("bench") => Some(benchmark::run_cli as fn(&[String]) -> Result<(), String>),
+        #[cfg(feature = "ts-bindings")]
+        Some("bindings") => Some(bindings::run_cli as fn(&[String]) -> Result<(), String>),
+        _ => None
+    };
+    if let Some(run) = subcommand {
+        if let Err(e) = run(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let main_game = GameHandle {
+        window: MAIN_WINDOW.to_owned(),
+        actor: Actor::spawn(async { GameSlot::new(MAIN_GAME, Game::new(DEFAULT_LEVEL)) }),
+    };
+    let engine = EngineWorker::start().expect("could not start the engine thread");
     tauri::Builder::default()
         .manage(PlayfieldState {
-            playfield: Mutex::new(Game::new(8)),
-            human_player: playfield::CellState::P1,
-            computer_player: playfield::CellState::P2,
+            games: Mutex::new(HashMap::from([(MAIN_GAME, main_game)])),
+            window_games: Mutex::new(HashMap::from([(MAIN_WINDOW.to_owned(), MAIN_GAME)])),
+            next_game_id: AtomicU32::new(MAIN_GAME + 1),
+            tournament: Mutex::new(None),
+            recorder: Mutex::new(None),
+            search_log: Mutex::new(None),
+            engine,
+            live_eval_pause: Mutex::new(PausePolicy::default()),
+            chat: Mutex::new(ChatRelay::default()),
+        })
+        .setup(|app| {
+            let search_log = app.path_resolver().app_log_dir()
+                .ok_or("no log directory available".to_owned())
+                .and_then(|dir| std::fs::create_dir_all(&dir).map_err(|e| e.to_string()).map(|_| dir))
+                .and_then(|dir| SearchLog::init(&dir));
+            match search_log {
+                Ok(log) => *app.state::<PlayfieldState>().search_log.lock().unwrap() = Some(log),
+                Err(e) => eprintln!("search log disabled: {}", e),
+            }
+
+            match settings_store(&app.handle()).and_then(|store| store.load()) {
+                Ok(settings) => {
+                    *app.state::<PlayfieldState>().live_eval_pause.lock().unwrap() = settings.live_eval_pause;
+                    app.state::<PlayfieldState>().with_game_id(MAIN_GAME, move |slot| {
+                        slot.game.set_rules(settings.rules)?;
+                        Ok(slot.game.reset(settings.level, slot.game.evaluator(), None)?)
+                    })?
+                },
+                Err(e) => warn!("could not load settings: {}", e),
+            }
+
+            let path = app.path_resolver().app_config_dir().map(|dir| dir.join(WEIGHTS_FILE));
+            if let Some(path) = path.filter(|p| p.exists()) {
+                match EvalWeights::load(&path) {
+                    Ok(weights) => app.state::<PlayfieldState>().with_game_id(MAIN_GAME, move |slot| {
+                        slot.game.set_weights(weights);
+                        Ok(())
+                    })?,
+                    Err(e) => eprintln!("could not load {}: {}", path.display(), e),
+                }
+            }
+
+            let path = app.path_resolver().app_config_dir().map(|dir| dir.join(CHAT_FILTER_FILE));
+            if let Some(path) = path.filter(|p| p.exists()) {
+                match std::fs::read_to_string(&path) {
+                    Ok(list) => app.state::<PlayfieldState>().chat.lock().unwrap().set_filter(Box::new(WordFilter::parse(&list))),
+                    Err(e) => eprintln!("could not load {}: {}", path.display(), e),
+                }
+            }
+
+            let handle = app.handle();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(CLOCK_TICK);
+                let state = handle.state::<PlayfieldState>();
+                for game in state.games.lock().unwrap().values() {
+                    let Some(window) = handle.get_window(&game.window) else {
+                        continue;
+                    };
+                    // skips a tick of a game whose actor is still busy with the earlier ones
+                    game.actor.post(move |slot| {
+                        if slot.game.tick(Some(&window)).is_some() {
+                            slot.game_over(&window);
+                        }
+                    });
+                }
+            });
+
+            let handle = app.handle();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(POWER_POLL);
+                for window in handle.windows().values() {
+                    pause_background(&handle.state::<PlayfieldState>(), window);
+                }
+            });
+            Ok(())
+        })
+        .on_window_event(|event| {
+            // minimizing and restoring resize the window
+            if let WindowEvent::Resized(_) = event.event() {
+                pause_background(&event.window().state::<PlayfieldState>(), event.window());
+            }
+            if let WindowEvent::Destroyed = event.event() {
+                let label = event.window().label();
+                let state = event.window().state::<PlayfieldState>();
+                // locked before the games like in `with_game`
+                let mut window_games = state.window_games.lock().unwrap();
+                if label != MAIN_WINDOW {
+                    window_games.remove(label);
+                    state.chat.lock().unwrap().remove_sender(label);
+                }
+                let mut games = state.games.lock().unwrap();
+                games.retain(|id, handle| {
+                    if handle.window != label {
+                        return true;
+                    }
+                    // do not keep the process alive for an engine move nobody will see
+                    handle.actor.post(|slot| slot.game.cancel_token().store(true, Ordering::Relaxed));
+                    // the games opened in a window are closed with it
+                    *id == MAIN_GAME
+                });
+            }
         })
-        .invoke_handler(tauri::generate_handler![play_col, new_game])
+        .invoke_handler(tauri::generate_handler![
+            create_game,
+            close_game,
+            play_col,
+            undo,
+            takeback,
+            resign,
+            swap_sides,
+            new_game,
+            create_tournament,
+            get_pairings,
+            get_standings,
+            record_tournament_result,
+            start_tournament_game,
+            list_presets,
+            apply_preset,
+            export_preset,
+            import_preset,
+            get_stats,
+            export_archive,
+            reset_stats,
+            get_settings,
+            set_settings,
+            run_benchmark,
+            record_session,
+            replay_session,
+            get_opening_name,
+            profile_startup,
+            get_memory_report,
+            start_drill,
+            guess_drill_column,
+            get_drill_score,
+            list_puzzles,
+            load_puzzle,
+            check_puzzle_solution,
+            set_search_log,
+            get_capabilities,
+            list_codes,
+            get_board,
+            get_status,
+            preview_col,
+            get_legal_moves,
+            get_history,
+            save_game,
+            load_game,
+            has_autosave,
+            resume_autosave,
+            export_notation,
+            import_notation,
+            copy_position,
+            paste_position,
+            enter_edit_mode,
+            set_cell,
+            finish_edit,
+            start_replay,
+            replay_step_forward,
+            replay_step_back,
+            replay_jump,
+            get_last_search_stats,
+            get_last_search_config,
+            set_tree_recording,
+            set_annotations,
+            set_live_eval,
+            send_chat,
+            cancel_ai,
+            set_cancel_mode,
+            export_search_tree,
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,62 @@
+use std::mem::size_of;
+
+use serde::Serialize;
+
+/// Lightweight accounting of the heap memory held by a component. Estimates are based on
+/// capacities, allocator overhead is not included.
+pub trait MemoryUsage {
+    fn heap_bytes(&self) -> usize;
+}
+
+/// Bytes allocated for the elements of `v`, excluding what they own themselves.
+pub fn vec_bytes<T>(v:&Vec<T>) -> usize {
+    v.capacity() * size_of::<T>()
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MemoryEntry {
+    pub name: &'static str,
+    pub bytes: usize,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct MemoryReport {
+    pub entries: Vec<MemoryEntry>,
+    pub total_bytes: usize,
+}
+
+impl MemoryReport {
+    pub fn add(&mut self, name:&'static str, component:&impl MemoryUsage) {
+        let bytes = component.heap_bytes();
+        self.entries.push(MemoryEntry { name, bytes });
+        self.total_bytes += bytes;
+    }
+
+    /// Appends the entries of a report measured elsewhere, e.g. on the actor owning a game.
+    pub fn merge(&mut self, other:MemoryReport) {
+        self.entries.extend(other.entries);
+        self.total_bytes += other.total_bytes;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Buffer(Vec<u32>);
+
+    impl MemoryUsage for Buffer {
+        fn heap_bytes(&self) -> usize {
+            vec_bytes(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let mut report = MemoryReport::default();
+        report.add("empty", &Buffer(Vec::new()));
+        report.add("buffer", &Buffer(Vec::with_capacity(10)));
+        assert_eq!(40, report.entries[1].bytes);
+        assert_eq!(40, report.total_bytes);
+    }
+}
@@ -1,141 +1,959 @@
-use rand::{seq::*, Rng};
-use tauri::utils::config;
-use std::{cmp::max, iter::Iterator, time::{self, Instant}};
-use ordered_float::NotNan;
+use rand::{rngs::StdRng, seq::*, SeedableRng};
+use std::{cell::{Cell, RefCell}, cmp::max, collections::HashMap, fmt, hash::Hash, iter::Iterator, sync::{atomic::{AtomicBool, Ordering}, mpsc, Arc}, thread, time::{self, Duration, Instant}};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, debug_span, trace, Span};
+
+/// Scores are fixed point integers, so the search never compares floats. Environments choose the scale.
+pub type Score = i32;
+
+/// Shared flag to abort a running search from another thread.
+pub type CancelToken = Arc<AtomicBool>;
+
+/// Late move reduction only applies from this remaining depth on...
+const LMR_MIN_DEPTH:u8 = 3;
+/// ...and to the moves after the first ones of the ordered list.
+const LMR_FULL_MOVES:usize = 3;
+/// Score of a subtree, whether it was searched until its end, the visited nodes and whether the score is a proven win or loss.
+type Subtree = (Score, bool, u128, bool);
+
+/// Fixed point scale of `epsilon` inside the search, so discounting a score is an integer operation.
+const EPSILON_SCALE:Score = 1000;
+/// Maximum number of memoized states, further results are not stored.
+const TABLE_CAPACITY:usize = 1 << 20;
+/// Parallel searches only split nodes from this remaining depth on, shallower subtrees are not worth a thread.
+const SPLIT_MIN_DEPTH:u8 = 4;
+/// Maximum number of recorded tree nodes, deeper searches are recorded only partially.
+const TREE_CAPACITY:usize = 200_000;
+/// In units of the score: randomized searches pick a move scored 1 lower than the best about a third as often as
+/// the best one. Searches on scaled scores, like the engine's by `SCORE_SCALE` per point, scale it alike.
+pub const DEFAULT_TEMPERATURE:f32 = 1.;
 
 /// Implemented methods should in general not call each other.
 /// State should be persisted and invalidated if necessary
-pub trait Environment {
-    /// Evaluate the current environment state by a score ranging from -127. to +127.
+pub trait Environment<A:Copy + Eq + Hash = usize> {
+    /// Evaluate the current environment state by a score within the bounds of the config, e.g. -127 to +127.
+    /// Won and lost final states have to score exactly the bounds, see `ConfigBuilder::new`.
     /// Note that this function is called for each state which is to be evaluated. 
-    /// It is advisable to highly optimize it for fast execution times.
-    fn evaluate(&mut self) -> f32;
+    /// It is advisable to highly optimize it for fast execution times, ideally to constant time
+    /// by accumulating the evaluation in `apply` and `revert` instead of recomputing it here.
+    fn evaluate(&mut self) -> Score;
     
     /// Returns all valid moves an agent can take in the current environment state.
     /// If an empty list is returned, `self.finished()` must yield true.
-    /// An action is identified by a usize.  
-    fn actions(&self) -> Vec<usize>;
+    /// An action is identified by a small value of type `A`, a column index for most board games.
+    fn actions(&self) -> Vec<A>;
 
-    /// Changes the environment state
-    fn apply(&mut self, action:&usize);
+    /// The same actions as `actions`, most promising first, see `Config::set_move_ordering`. A good order
+    /// leads to more cutoffs. The root is ordered by the scores of the previous depth instead.
+    fn ordered_actions(&self) -> Vec<A> {
+        self.actions()
+    }
+
+    /// Changes the environment state, including any state `evaluate` accumulates
+    fn apply(&mut self, action:&A);
 
     /// Reverts the action taken. May panic if action was not taken
-    fn revert(&mut self, action:&usize);
+    fn revert(&mut self, action:&A);
 
     /// Determines if the Environment is in a final state. If that is the case, no more actions can be performed.
     fn is_finished(&mut self) -> bool;
 
     /// Toggles the current player between minimizer and maximizer
-    fn swap_players(&mut self);    
+    fn swap_players(&mut self);
+
+    /// Determines if the action just applied created a threat which has to be answered with the next move.
+    /// Used to extend the search of forcing sequences, environments without threat detection can keep the default.
+    fn is_forcing(&mut self) -> bool {
+        false
+    }
+
+    /// Identifies the current state including the player to move, so equal states reached by different
+    /// move orders are searched only once if memoization is enabled. Environments without hashing return `None`.
+    fn state_hash(&self) -> Option<u64> {
+        None
+    }    
+
+    /// Determines if the environment is finished without a winner. Drawn states are scored by `Config::set_draw_score`
+    /// instead of `evaluate`, environments without draws can keep the default.
+    fn is_draw(&mut self) -> bool {
+        false
+    }
+
+    /// An independent copy of the current state for a helper thread of a parallel search, see `Config::set_threads`.
+    /// Environments which cannot be copied keep the default and are searched on a single thread.
+    fn split(&self) -> Option<Box<dyn Environment<A> + Send>> {
+        None
+    }
+}
+
+/// An environment which can be copied, so searches can work on their own instance, e.g. on another thread,
+/// instead of applying and reverting actions on a single shared one.
+pub trait ForkableEnvironment<A:Copy + Eq + Hash = usize>: Environment<A> + Send + Sized {
+    /// An independent copy of the current state. Actions applied to the copy must not affect `self`.
+    fn fork(&self) -> Self;
+}
+
+pub struct StateEvaluation<A = usize> {
+    pub best_action:Option<A>,
+    pub ops_count:u128,
+    pub score:Score,
+    /// The best root moves ordered by score, as many as `Config::set_multi_pv` requested.
+    pub top_moves:Vec<RootMove<A>>,
+    pub termination:Termination,
+    pub stats:SearchStats<A>,
+    /// Settings of the search, to reproduce it with `ConfigSnapshot::build`.
+    pub config:ConfigSnapshot,
+    /// The explored tree of the last completed depth, if enabled by `Config::set_tree_recording`.
+    pub tree:Option<SearchTree<A>>,
+}
+
+/// What the search did, for diagnostics.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SearchStats<A = usize> {
+    /// Number of completed depths.
+    pub depth:u8,
+    pub cutoffs:u128,
+    /// States taken from memoization, see `Config::set_memoization`.
+    pub tt_hits:u128,
+    pub elapsed_micros:u128,
+    /// Nodes visited below each root move in the completed depths.
+    pub root_nodes:Vec<(A, u128)>,
+}
+
+/// The nodes explored by one depth of a search, in the order they were entered.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SearchTree<A = usize> {
+    pub nodes:Vec<TreeNode<A>>,
+}
+
+/// State reached by an action. Window and score are from the perspective of player 1.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct TreeNode<A = usize> {
+    /// Index of the parent node, `None` for root actions.
+    pub parent:Option<usize>,
+    pub action:A,
+    /// Number of actions from the root, 1 for root actions.
+    pub depth:u8,
+    pub alpha:Score,
+    pub beta:Score,
+    pub score:Score,
+    /// Whether the search of the node stopped early since the window was closed.
+    pub cutoff:bool,
+}
+
+impl<A:fmt::Debug> SearchTree<A> {
+    /// Graphviz representation, nodes cut off are drawn red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph search {\n    root;\n");
+        for (i, node) in self.nodes.iter().enumerate() {
+            let color = if node.cutoff { ", color=red" } else { "" };
+            dot += &format!(
+                "    n{} [label=\"{:?}\\n[{}, {}]\\n{}\"{}];\n",
+                i, node.action, node.alpha, node.beta, node.score, color
+            );
+            match node.parent {
+                Some(parent) => dot += &format!("    n{} -> n{};\n", parent, i),
+                None => dot += &format!("    root -> n{};\n", i),
+            }
+        }
+        dot + "}\n"
+    }
+}
+
+/// Records the tree while searching, see `Config::set_tree_recording`.
+struct TreeRecorder<A> {
+    tree:SearchTree<A>,
+    /// Nodes entered but not left yet, `None` if the node was not recorded.
+    open:Vec<Option<usize>>,
+}
+
+impl<A> TreeRecorder<A> {
+    fn new() -> TreeRecorder<A> {
+        TreeRecorder { tree:SearchTree { nodes:Vec::new() }, open:Vec::new() }
+    }
+}
+
+/// How to choose among root moves sharing the best score, unless the search is randomized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreak {
+    /// The move listed first by `Environment::actions`, which should list preferred moves first, e.g. central columns.
+    #[default]
+    CenterFirst,
+    Random,
+    /// The move whose score was settled at the lowest depth, e.g. the fastest win.
+    ShallowestWin,
+    /// The move which scored best in the depth before the last one.
+    MostRobust,
 }
 
-pub struct StateEvaluation {
-    pub best_action:Option<usize>,
+/// Why the iterative deepening stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Termination {
+    /// Every line was searched until the end of the game.
+    Exhausted,
+    DepthReached,
+    TimeOut,
+    NodeBudget,
+    Cancelled,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RootMove<A = usize> {
+    pub action:A,
+    pub score:Score,
+}
+
+/// When a `Progress` is reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressPhase {
+    /// The search starts another depth.
+    Started,
+    /// The depth has been searched completely.
+    Completed,
+}
+
+/// Snapshot of the search whenever a depth starts or completes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Progress<A = usize> {
+    pub phase:ProgressPhase,
+    pub depth:u8,
+    /// Best move and its score of the last completed depth, `None` before the first one completed.
+    pub best:Option<RootMove<A>>,
+    /// Nodes visited so far, including the previous depths.
     pub ops_count:u128,
-    pub score:f32
 }
 
-pub struct Config {
+/// How the memoized value of a state relates to its true value, as the search window may have cut it short.
+#[derive(Clone, Copy)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy)]
+struct TableEntry {
+    level:u8,
+    /// Value from the perspective of the side choosing, before the discount of `epsilon`.
+    value:Score,
+    bound:Bound,
+    exploited:bool,
+    proven:bool,
+}
+
+pub struct Config<A = usize> {
     time_limit_millis:Option<u128>,
     max_depth:Option<u8>,
     randomized:bool,
-    min_score:f32,
-    max_score:f32,
-    epsilon:f32,
+    temperature:f32,
+    min_score:Score,
+    max_score:Score,
+    /// Discount of every ply in units of `EPSILON_SCALE`.
+    epsilon:Score,
+    on_progress:Option<Box<dyn Fn(Progress<A>)>>,
+    multi_pv:usize,
+    cancel:Option<CancelToken>,
+    max_nodes:Option<u128>,
+    threads:usize,
+    /// End of the time limit of the running search.
+    deadline:Cell<Option<Instant>>,
+    visited:Cell<u128>,
+    cutoffs:Cell<u128>,
+    tt_hits:Cell<u128>,
+    futility_margin:Option<Score>,
+    late_move_reduction:bool,
+    forced_move_extensions:bool,
+    move_ordering:bool,
+    opponent_accuracy:Option<f32>,
+    tie_break:TieBreak,
+    memoize:bool,
+    draw_score:Score,
+    /// Seed the generator is reset to by every search, see `ConfigBuilder::deterministic`.
+    deterministic:Option<u64>,
+    recorder:RefCell<Option<TreeRecorder<A>>>,
+    table:RefCell<HashMap<u64, TableEntry>>,
+    /// Side choosing at the root of the running search.
+    root_player:Cell<Score>,
+    rng:RefCell<StdRng>,
 }
 
-impl Default for Config {
-    fn default() -> Config {
+impl<A> Default for Config<A> {
+    fn default() -> Config<A> {
         Config {
             time_limit_millis:None,
             max_depth:Some(5),
             randomized:false,
-            min_score:-127.,
-            max_score:127.,
-            epsilon:0.95,
+            temperature:DEFAULT_TEMPERATURE,
+            min_score:-127,
+            max_score:127,
+            epsilon:950,
+            on_progress:None,
+            multi_pv:0,
+            cancel:None,
+            max_nodes:None,
+            threads:1,
+            deadline:Cell::new(None),
+            visited:Cell::new(0),
+            cutoffs:Cell::new(0),
+            tt_hits:Cell::new(0),
+            futility_margin:None,
+            late_move_reduction:false,
+            forced_move_extensions:false,
+            move_ordering:false,
+            opponent_accuracy:None,
+            tie_break:TieBreak::default(),
+            memoize:false,
+            draw_score:0,
+            deterministic:None,
+            recorder:RefCell::new(None),
+            table:RefCell::new(HashMap::new()),
+            root_player:Cell::new(1),
+            rng:RefCell::new(StdRng::from_entropy()),
         }
     }
 }
 
-impl Config {
-    pub fn new(
-        time_limit_millis:Option<u128>,
-        max_depth:Option<u8>,
-        randomized:bool,
-        min_score:f32,
-        epsilon:f32,
-    ) -> Config {
-        assert!(
-            time_limit_millis.is_some() != max_depth.is_some(),
-           "exactly one of time_limit_millis or max_depth has to be specified"
-        );
-        
-        Config {
-            time_limit_millis,
-            max_depth,
-            randomized,
+/// Invalid settings of a `ConfigBuilder`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigError {
+    /// Neither a time limit nor a maximum depth was set.
+    NoLimit,
+    ZeroLimit,
+    MinScore(Score),
+    Epsilon(f32),
+    Temperature(f32),
+    /// The accuracy of the opponent of an expectimax search is no probability.
+    Accuracy(f32),
+    /// A deterministic search was requested without a maximum depth.
+    NoDepth,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::NoLimit => write!(f, "either a time limit or a maximum depth is required"),
+            ConfigError::ZeroLimit => write!(f, "the search limit must be positive"),
+            ConfigError::MinScore(score) => write!(f, "minimum score {} must be negative", score),
+            ConfigError::Epsilon(epsilon) => write!(f, "epsilon {} must be within (0, 1]", epsilon),
+            ConfigError::Temperature(temperature) => write!(f, "temperature {} must be positive and finite", temperature),
+            ConfigError::Accuracy(accuracy) => write!(f, "opponent accuracy {} must be within [0, 1]", accuracy),
+            ConfigError::NoDepth => write!(f, "a deterministic search requires a maximum depth"),
+        }
+    }
+}
+
+impl From<ConfigError> for String {
+    fn from(e:ConfigError) -> String {
+        e.to_string()
+    }
+}
+
+/// Validated construction of a `Config`. The search is limited by time, depth or both, whichever is reached first.
+pub struct ConfigBuilder {
+    time_limit_millis:Option<u128>,
+    max_depth:Option<u8>,
+    randomized:bool,
+    temperature:f32,
+    min_score:Score,
+    epsilon:f32,
+    deterministic:Option<u64>,
+}
+
+impl ConfigBuilder {
+    /// Scores range from `min_score` to `-min_score`. `epsilon` is the factor applied to heuristic scores for every ply
+    /// they are away from the root, so nearer gains are preferred, 1 turns the discount off. Won and lost final states,
+    /// which score the bounds, are not discounted.
+    pub fn new(min_score:Score, epsilon:f32) -> ConfigBuilder {
+        ConfigBuilder {
+            time_limit_millis:None,
+            max_depth:None,
+            randomized:false,
+            temperature:DEFAULT_TEMPERATURE,
             min_score,
-            max_score:-min_score,
             epsilon,
+            deterministic:None,
+        }
+    }
+
+    /// Stops the search once `millis` have passed, also in the middle of a depth. Root moves fully searched
+    /// in the interrupted depth are kept if the best move of the depth before is among them.
+    pub fn time_limit(mut self, millis:u128) -> ConfigBuilder {
+        self.time_limit_millis = Some(millis);
+        self
+    }
+
+    pub fn max_depth(mut self, depth:u8) -> ConfigBuilder {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Picks among the best moves at random instead of strictly by score.
+    pub fn randomized(mut self, randomized:bool) -> ConfigBuilder {
+        self.randomized = randomized;
+        self
+    }
+
+    /// Spread of the randomized selection, which picks root moves with probabilities by the softmax of
+    /// their scores divided by `temperature`. Higher temperatures give weaker moves more chances.
+    pub fn temperature(mut self, temperature:f32) -> ConfigBuilder {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Makes the result depend on nothing but the environment and the settings, so a search can be reproduced
+    /// exactly: the time limit is ignored and every search resets the generator to `seed` before it starts.
+    pub fn deterministic(mut self, seed:u64) -> ConfigBuilder {
+        self.deterministic = Some(seed);
+        self
+    }
+
+    pub fn build<A>(self) -> Result<Config<A>, ConfigError> {
+        match (self.time_limit_millis, self.max_depth) {
+            (None, None) => return Err(ConfigError::NoLimit),
+            (Some(0), _) | (_, Some(0)) => return Err(ConfigError::ZeroLimit),
+            _ => {}
+        }
+        if self.deterministic.is_some() && self.max_depth.is_none() {
+            return Err(ConfigError::NoDepth);
+        }
+        if self.min_score >= 0 {
+            return Err(ConfigError::MinScore(self.min_score));
+        }
+        let epsilon = (self.epsilon * EPSILON_SCALE as f32).round() as Score;
+        if !(self.epsilon <= 1. && epsilon > 0) {
+            return Err(ConfigError::Epsilon(self.epsilon));
+        }
+        if !(self.temperature.is_finite() && self.temperature > 0.) {
+            return Err(ConfigError::Temperature(self.temperature));
+        }
+        Ok(Config {
+            time_limit_millis:self.time_limit_millis.filter(|_| self.deterministic.is_none()),
+            max_depth:self.max_depth,
+            randomized:self.randomized,
+            temperature:self.temperature,
+            min_score:self.min_score,
+            max_score:-self.min_score,
+            epsilon,
+            deterministic:self.deterministic,
+            ..Default::default()
+        })
+    }
+}
+
+/// The settings of a `Config` affecting the result of a search, without callbacks and search state.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSnapshot {
+    pub time_limit_millis:Option<u128>,
+    pub max_depth:Option<u8>,
+    pub randomized:bool,
+    pub temperature:f32,
+    pub min_score:Score,
+    pub epsilon:f32,
+    pub multi_pv:usize,
+    pub max_nodes:Option<u128>,
+    pub threads:usize,
+    pub futility_margin:Option<Score>,
+    pub late_move_reduction:bool,
+    pub forced_move_extensions:bool,
+    pub move_ordering:bool,
+    pub opponent_accuracy:Option<f32>,
+    pub tie_break:TieBreak,
+    pub memoize:bool,
+    pub draw_score:Score,
+    pub deterministic:Option<u64>,
+}
+
+impl ConfigSnapshot {
+    /// A config with these settings. A deterministic search run with it returns the same result as the original one.
+    pub fn build<A>(&self) -> Result<Config<A>, ConfigError> {
+        let mut builder = ConfigBuilder::new(self.min_score, self.epsilon)
+            .randomized(self.randomized)
+            .temperature(self.temperature);
+        if let Some(millis) = self.time_limit_millis {
+            builder = builder.time_limit(millis);
+        }
+        if let Some(depth) = self.max_depth {
+            builder = builder.max_depth(depth);
+        }
+        if let Some(seed) = self.deterministic {
+            builder = builder.deterministic(seed);
+        }
+        let mut config = builder.build()?;
+        config.set_multi_pv(self.multi_pv);
+        if let Some(max_nodes) = self.max_nodes {
+            config.set_node_budget(max_nodes);
+        }
+        config.set_threads(self.threads);
+        config.set_futility_pruning(self.futility_margin);
+        config.set_late_move_reduction(self.late_move_reduction);
+        config.set_forced_move_extensions(self.forced_move_extensions);
+        config.set_move_ordering(self.move_ordering);
+        config.set_expectimax(self.opponent_accuracy)?;
+        config.set_tie_break(self.tie_break);
+        config.set_memoization(self.memoize);
+        config.set_draw_score(self.draw_score);
+        Ok(config)
+    }
+}
+
+impl<A> Config<A> {
+    /// Registers a callback which is invoked whenever the search starts a new depth and after each completed one,
+    /// with the best move so far.
+    pub fn set_on_progress(&mut self, on_progress:Box<dyn Fn(Progress<A>)>) {
+        self.on_progress = Some(on_progress);
+    }
+
+    /// Enables futility pruning: one ply above the leaves, a node is not expanded if its static evaluation
+    /// misses the window by more than `margin`. Disabled with `None`.
+    pub fn set_futility_pruning(&mut self, margin:Option<Score>) {
+        self.futility_margin = margin;
+    }
+
+    /// Enables searching late moves of the ordered action list one ply shallower. If such a move turns out
+    /// to improve the window, it is searched again at full depth.
+    pub fn set_late_move_reduction(&mut self, enabled:bool) {
+        self.late_move_reduction = enabled;
+    }
+
+    /// Enables searching one ply deeper whenever there is only one legal reply or a threat has to be answered,
+    /// so forcing sequences are not cut off at the horizon.
+    pub fn set_forced_move_extensions(&mut self, enabled:bool) {
+        self.forced_move_extensions = enabled;
+    }
+
+    /// Enables searching the actions below the root in the order of `Environment::ordered_actions`.
+    /// Nodes just above the leaves keep the plain order, as their children are cheap to evaluate anyway.
+    pub fn set_move_ordering(&mut self, enabled:bool) {
+        self.move_ordering = enabled;
+    }
+
+    /// Switches to expectimax: the opponent is modeled to play its best reply only with probability `accuracy`
+    /// and a random one otherwise, so its nodes are scored by the expected value instead of the best reply.
+    /// Nodes of the opponent are not pruned. `None` searches for a perfect opponent.
+    pub fn set_expectimax(&mut self, accuracy:Option<f32>) -> Result<(), ConfigError> {
+        if let Some(accuracy) = accuracy.filter(|a| !(0. ..=1.).contains(a)) {
+            return Err(ConfigError::Accuracy(accuracy));
+        }
+        self.opponent_accuracy = accuracy;
+        Ok(())
+    }
+
+    pub fn set_tie_break(&mut self, tie_break:TieBreak) {
+        self.tie_break = tie_break;
+    }
+
+    /// Enables memoizing searched states by `Environment::state_hash` during a search.
+    pub fn set_memoization(&mut self, enabled:bool) {
+        self.memoize = enabled;
+    }
+
+    /// Value of a drawn final state for the side choosing at the root, 0 by default. A negative value (contempt)
+    /// makes the search prefer risky positions over a dead draw, a positive one settles for the draw.
+    pub fn set_draw_score(&mut self, score:Score) {
+        self.draw_score = score;
+    }
+
+    pub fn snapshot(&self) -> ConfigSnapshot {
+        ConfigSnapshot {
+            time_limit_millis:self.time_limit_millis,
+            max_depth:self.max_depth,
+            randomized:self.randomized,
+            temperature:self.temperature,
+            min_score:self.min_score,
+            epsilon:self.epsilon as f32 / EPSILON_SCALE as f32,
+            multi_pv:self.multi_pv,
+            max_nodes:self.max_nodes,
+            threads:self.threads,
+            futility_margin:self.futility_margin,
+            late_move_reduction:self.late_move_reduction,
+            forced_move_extensions:self.forced_move_extensions,
+            move_ordering:self.move_ordering,
+            opponent_accuracy:self.opponent_accuracy,
+            tie_break:self.tie_break,
+            memoize:self.memoize,
+            draw_score:self.draw_score,
+            deterministic:self.deterministic,
+        }
+    }
+
+    /// Records the explored tree of every depth, which is returned in `StateEvaluation::tree`.
+    /// Meant for debugging, as it slows the search down and takes a lot of memory.
+    pub fn set_tree_recording(&mut self, enabled:bool) {
+        self.recorder = RefCell::new(enabled.then(TreeRecorder::new));
+    }
+
+    /// Starts recording the tree of another depth, returns the tree of the previous one.
+    fn restart_tree(&self) -> Option<SearchTree<A>> {
+        self.recorder.borrow_mut().as_mut().map(|r| {
+            r.open.clear();
+            std::mem::replace(&mut r.tree, SearchTree { nodes:Vec::new() })
+        })
+    }
+
+    fn restore_tree(&self, tree:Option<SearchTree<A>>) {
+        if let (Some(r), Some(tree)) = (self.recorder.borrow_mut().as_mut(), tree) {
+            r.tree = tree;
+        }
+    }
+
+    fn recorded_tree(&self) -> Option<SearchTree<A>> where A:Clone {
+        self.recorder.borrow().as_ref().map(|r| r.tree.clone())
+    }
+
+    /// Records the state reached by `action`, which is searched with the window `alpha`..`beta`.
+    fn enter(&self, action:A, alpha:Score, beta:Score) {
+        if let Some(r) = self.recorder.borrow_mut().as_mut() {
+            let index = (r.tree.nodes.len() < TREE_CAPACITY).then(|| {
+                r.tree.nodes.push(TreeNode {
+                    parent:r.open.last().copied().flatten(),
+                    action,
+                    depth:r.open.len() as u8 + 1,
+                    alpha,
+                    beta,
+                    score:0,
+                    cutoff:false,
+                });
+                r.tree.nodes.len() - 1
+            });
+            r.open.push(index);
         }
     }
 
-    fn keep_going(&self, now:Instant, level:u8) -> bool {
-        match self.time_limit_millis {
-            Some(tlm) => now.elapsed().as_millis() < tlm,
-            None => level < self.max_depth.unwrap()
+    /// Completes the node entered last with its `score`.
+    fn leave(&self, score:Score) {
+        if let Some(r) = self.recorder.borrow_mut().as_mut() {
+            if let Some(index) = r.open.pop().flatten() {
+                r.tree.nodes[index].score = score;
+            }
+        }
+    }
+
+    /// Flags the node entered last as cut off.
+    fn record_cutoff(&self) {
+        if let Some(r) = self.recorder.borrow_mut().as_mut() {
+            if let Some(index) = r.open.last().copied().flatten() {
+                r.tree.nodes[index].cutoff = true;
+            }
+        }
+    }
+
+    /// Value of a leaf from the perspective of player 1.
+    fn leaf_value<E:Environment<A> + ?Sized>(&self, env:&mut E) -> Score where A:Copy + Eq + Hash {
+        match env.is_finished() && env.is_draw() {
+            true => self.root_player.get() * self.draw_score,
+            false => env.evaluate(),
+        }
+    }
+
+    /// Looks up a state searched to the same depth whose value settles the node for the window `alpha`..`beta`.
+    fn memoized(&self, hash:u64, level:u8, alpha:Score, beta:Score) -> Option<TableEntry> {
+        let entry = self.table.borrow().get(&hash).copied().filter(|entry| entry.level == level && match entry.bound {
+            Bound::Exact => true,
+            Bound::Lower => entry.value >= beta,
+            Bound::Upper => entry.value <= alpha,
+        });
+        if entry.is_some() {
+            self.tt_hits.set(self.tt_hits.get() + 1);
+        }
+        entry
+    }
+
+    /// Discounts a score for another ply. Proven wins and losses lose a point instead, so they stay apart from
+    /// heuristic scores and faster wins are still preferred.
+    fn discount(&self, score:Score, proven:bool) -> Score {
+        match proven {
+            true => score - score.signum(),
+            false => score * self.epsilon / EPSILON_SCALE,
+        }
+    }
+
+    fn memoize(&self, hash:u64, entry:TableEntry) {
+        // results of an interrupted search are incomplete
+        if self.interrupted().is_some() {
+            return;
+        }
+        let mut table = self.table.borrow_mut();
+        if table.len() < TABLE_CAPACITY || table.contains_key(&hash) {
+            table.insert(hash, entry);
+        }
+    }
+
+    /// Makes the randomized move selection reproducible. The generator is kept between searches
+    /// with the same config, so a sequence of searches is reproduced as a whole.
+    pub fn set_seed(&mut self, seed:u64) {
+        self.rng = RefCell::new(StdRng::seed_from_u64(seed));
+    }
+
+    /// Requests the `k` best root moves with their scores in `StateEvaluation::top_moves`.
+    pub fn set_multi_pv(&mut self, k:usize) {
+        self.multi_pv = k;
+    }
+
+    /// Stops the search once `max_nodes` leaves have been evaluated. The depth which exceeds the budget
+    /// is discarded, so for a non-randomized search the result does not depend on the speed of the machine.
+    pub fn set_node_budget(&mut self, max_nodes:u128) {
+        self.max_nodes = Some(max_nodes);
+    }
+
+    /// Searches the siblings of a node in parallel on up to `threads` threads, once its first child has been
+    /// searched and the window is known (young brothers wait). Requires `Environment::split`, other environments
+    /// are searched on one thread. Helper threads have their own memoized states and are not recorded in the tree,
+    /// each of them may use the remaining node budget. Results do not depend on the number of threads.
+    pub fn set_threads(&mut self, threads:usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Config of a helper thread of a parallel search, which does not split its subtree any further.
+    fn helper(settings:&ConfigSnapshot, cancel:Option<CancelToken>, deadline:Option<Instant>, root_player:Score, max_nodes:Option<u128>) -> Config<A> {
+        Config {
+            min_score:settings.min_score,
+            max_score:-settings.min_score,
+            epsilon:(settings.epsilon * EPSILON_SCALE as f32).round() as Score,
+            cancel,
+            max_nodes,
+            futility_margin:settings.futility_margin,
+            late_move_reduction:settings.late_move_reduction,
+            forced_move_extensions:settings.forced_move_extensions,
+            move_ordering:settings.move_ordering,
+            opponent_accuracy:settings.opponent_accuracy,
+            memoize:settings.memoize,
+            draw_score:settings.draw_score,
+            root_player:Cell::new(root_player),
+            deadline:Cell::new(deadline),
+            ..Default::default()
+        }
+    }
+
+    /// Visited nodes, cutoffs and memoization hits of the running search.
+    fn counters(&self) -> (u128, u128, u128) {
+        (self.visited.get(), self.cutoffs.get(), self.tt_hits.get())
+    }
+
+    /// Adds the counters of a finished helper thread to this search.
+    fn merge(&self, (visited, cutoffs, tt_hits):(u128, u128, u128)) {
+        self.visited.set(self.visited.get() + visited);
+        self.cutoffs.set(self.cutoffs.get() + cutoffs);
+        self.tt_hits.set(self.tt_hits.get() + tt_hits);
+    }
+
+    fn count_node(&self) {
+        self.visited.set(self.visited.get() + 1);
+    }
+
+    /// Reason to abandon the depth in progress.
+    fn interrupted(&self) -> Option<Termination> {
+        if self.is_cancelled() {
+            return Some(Termination::Cancelled);
         }
+        match (self.max_nodes, self.deadline.get()) {
+            (Some(max_nodes), _) if self.visited.get() >= max_nodes => Some(Termination::NodeBudget),
+            (_, Some(deadline)) if Instant::now() >= deadline => Some(Termination::TimeOut),
+            _ => None
+        }
+    }
+
+    /// Once the token is set, the search returns as soon as possible with the result of the last completed depth.
+    pub fn set_cancel_token(&mut self, cancel:CancelToken) {
+        self.cancel = Some(cancel);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed))
+    }
+
+    /// Reason not to start another depth, if any.
+    fn stop_reason(&self, now:Instant, level:u8) -> Option<Termination> {
+        self.interrupted()
+            .or(self.max_depth.filter(|depth| level >= *depth).map(|_| Termination::DepthReached))
+            .or(self.time_limit_millis.filter(|tlm| now.elapsed().as_millis() >= *tlm).map(|_| Termination::TimeOut))
     }
 }
 
-pub fn minimize(env:&mut impl Environment, config:&Config) -> Option<StateEvaluation> {
-    return eval(env, config, -1.0);
+pub fn minimize<A:Copy + Eq + Hash>(env:&mut impl Environment<A>, config:&Config<A>) -> Option<StateEvaluation<A>> {
+    return eval(env, config, -1);
 } 
 
-pub fn maximize(env:&mut impl Environment, config:&Config) -> Option<StateEvaluation> {
-    return eval(env, config, 1.0);
+pub fn maximize<A:Copy + Eq + Hash>(env:&mut impl Environment<A>, config:&Config<A>) -> Option<StateEvaluation<A>> {
+    return eval(env, config, 1);
+}
+
+/// Runs a search for `player`, 1 to maximize and -1 to minimize, on a dedicated thread working on a fork of `env`.
+/// The config is created by `setup` on that thread, so it does not have to be `Send`. Its cancel token is replaced
+/// by `cancel`, which can be shared to stop the search from elsewhere as well. Fails if the thread cannot be started.
+pub fn eval_async<A, E, F>(env:&E, setup:F, player:Score, cancel:CancelToken) -> Result<SearchHandle<A>, String>
+where
+    A:Copy + Eq + Hash + Send + 'static,
+    E:ForkableEnvironment<A> + 'static,
+    F:FnOnce() -> Result<Config<A>, String> + Send + 'static,
+{
+    let token = cancel.clone();
+    let mut env = env.fork();
+    let (sender, receiver) = mpsc::channel();
+    let thread = thread::Builder::new().name("search".into()).spawn(move || {
+        let result = setup().map(|mut config| {
+            config.set_cancel_token(token);
+            eval(&mut env, &config, player)
+        });
+        // the receiver may be gone already, nobody is interested in the result then
+        let _ = sender.send(result);
+    }).map_err(|e| format!("could not start the search: {}", e))?;
+    Ok(SearchHandle { receiver, cancel, thread:Some(thread) })
+}
+
+/// A search running on its own thread, see `eval_async`. Dropping the handle before the result was received cancels the search.
+pub struct SearchHandle<A=usize> {
+    receiver:mpsc::Receiver<Result<Option<StateEvaluation<A>>, String>>,
+    cancel:CancelToken,
+    /// Taken once the result was received.
+    thread:Option<thread::JoinHandle<()>>,
+}
+
+impl<A> SearchHandle<A> {
+    /// Stops the search as soon as possible, `wait` then returns the result of the last completed depth.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Blocks until the search has ended. The result is `None` if the environment was already finished.
+    pub fn wait(mut self) -> Result<Option<StateEvaluation<A>>, String> {
+        let result = self.receiver.recv();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        result.map_err(|_| "search thread panicked".to_owned())?
+    }
+}
+
+impl<A> Drop for SearchHandle<A> {
+    fn drop(&mut self) {
+        if self.thread.is_some() {
+            self.cancel();
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
-struct ActionEvaluation {
-    action:usize,
-    score:f32,
+struct ActionEvaluation<A> {
+    action:A,
+    score:Score,
     exploited:bool,
+    nodes:u128,
+    /// Position in the list of `Environment::actions`.
+    index:usize,
+    /// Score of the depth before.
+    prior:Score,
+    /// Depth at which the score was settled.
+    resolved_at:Option<u8>,
 }
 
-struct Action {
-    action:usize,
-    score:f32,
+fn eval<A:Copy + Eq + Hash>(env:&mut impl Environment<A>, config:&Config<A>, player:Score) -> Option<StateEvaluation<A>> {
+    let mut search = search_incremental(env, config, player)?;
+    while search.step() {}
+    search.span.in_scope(|| debug!(termination = ?search.termination(), ops = search.ops_count, "search stopped"));
+    Some(search.result())
 }
 
-fn eval(env:&mut impl Environment, config:&Config, player:f32) -> Option<StateEvaluation> {
+/// Starts an anytime search for `player`, 1 to maximize and -1 to minimize. Each item of the returned iterator
+/// is the evaluation after another completed depth, so the caller can stop at any moment and still play a valid move.
+/// Items report `Termination::DepthReached` until the search ends, the reason it ended is `IncrementalSearch::termination`.
+/// Returns `None` if the environment is already finished.
+pub fn search_incremental<'a, A:Copy + Eq + Hash, E:Environment<A>>(
+    env:&'a mut E,
+    config:&'a Config<A>,
+    player:Score
+) -> Option<IncrementalSearch<'a, A, E>> {
     if env.is_finished() {
         return None;
     }
-    let mut level:u8 = 0;
-
-    let mut actions:Vec<ActionEvaluation> = env.actions().iter().map(|action| ActionEvaluation{
+    let actions = env.actions().iter().enumerate().map(|(index, action)| ActionEvaluation{
         action:*action, 
         score:config.min_score, 
-        exploited:false
+        exploited:false,
+        nodes:0,
+        index,
+        prior:config.min_score,
+        resolved_at:None,
     }).collect();
 
-    let now = Instant::now();
-    let mut unexploited = true;
-    let mut ops_count: u128 = 0;
-    while unexploited && config.keep_going(now, level) {
+    config.visited.set(0);
+    config.cutoffs.set(0);
+    config.tt_hits.set(0);
+    config.root_player.set(player);
+    config.deadline.set(config.time_limit_millis.map(|millis| Instant::now() + Duration::from_millis(millis as u64)));
+    config.table.borrow_mut().clear();
+    if let Some(seed) = config.deterministic {
+        *config.rng.borrow_mut() = StdRng::seed_from_u64(seed);
+    }
+    Some(IncrementalSearch {
+        env,
+        config,
+        player,
+        actions,
+        level:0,
+        ops_count:0,
+        now:Instant::now(),
+        termination:None,
+        span:debug_span!("search", player),
+    })
+}
+
+/// Iterative deepening in progress, see `search_incremental`.
+pub struct IncrementalSearch<'a, A:Copy + Eq + Hash, E:Environment<A>> {
+    env:&'a mut E,
+    config:&'a Config<A>,
+    player:Score,
+    actions:Vec<ActionEvaluation<A>>,
+    level:u8,
+    ops_count:u128,
+    now:Instant,
+    termination:Option<Termination>,
+    span:Span,
+}
+
+impl<'a, A:Copy + Eq + Hash, E:Environment<A>> IncrementalSearch<'a, A, E> {
+    /// Why the search ended, `None` while another depth can be searched.
+    pub fn termination(&self) -> Option<Termination> {
+        self.termination
+    }
+
+    /// Searches the next depth. Returns false if the search has ended instead.
+    fn step(&mut self) -> bool {
+        if self.termination.is_some() {
+            return false;
+        }
+        let _span = self.span.enter();
+        let (env, config, player, level) = (&mut *self.env, self.config, self.player, self.level);
+        if let Some(reason) = config.stop_reason(self.now, level) {
+            self.termination = Some(reason);
+            return false;
+        }
         let mut all_exploited = true;
         let mut max_value = config.min_score;
-        let mut alpha = config.min_score.clone();
-        let mut beta = config.max_score.clone();
-
-        print!("search until level {:?}. ", level);
+        let alpha = config.min_score;
+        let beta = config.max_score;
+        let previous = self.actions.clone();
+        let previous_tree = config.restart_tree();
+
+        if let Some(on_progress) = &config.on_progress {
+            // the moves are ranked once the first depth completed
+            let best = self.actions.first().filter(|_| level > 0);
+            on_progress(Progress {
+                phase:ProgressPhase::Started,
+                depth:level,
+                best:best.map(|best| RootMove { action:best.action, score:player*best.score }),
+                ops_count:self.ops_count,
+            });
+        }
         
-        actions.iter_mut()
-        .for_each(|action_eval| {
+        let mut ops_count = self.ops_count;
+        // moves searched before an interruption, resolved moves are not searched again
+        let mut completed = vec![true; self.actions.len()];
+        self.actions.iter_mut().zip(completed.iter_mut())
+        .for_each(|(action_eval, completed)| {
             if !action_eval.exploited {
                 env.apply(&action_eval.action);
-                let (score, exploited, cnt) = deepen(
+                config.enter(action_eval.action, alpha, beta);
+                let (score, exploited, cnt, _) = deepen(
                     env, 
                     alpha, 
                     beta, 
@@ -143,10 +961,17 @@ fn eval(env:&mut impl Environment, config:&Config, player:f32) -> Option<StateEv
                     player, 
                     config
                 );
-                print!("ops {:?}. ", cnt);
+                config.leave(score);
+                *completed = config.interrupted().is_none();
+                trace!(depth = level, ops = cnt, exploited, "root move searched");
                 ops_count += cnt;
+                action_eval.nodes += cnt;
+                action_eval.prior = action_eval.score;
                 action_eval.score = player * score;
                 action_eval.exploited = exploited;
+                if exploited {
+                    action_eval.resolved_at = Some(level);
+                }
                 all_exploited &= exploited;
                 
                 if action_eval.score > max_value {
@@ -155,213 +980,352 @@ fn eval(env:&mut impl Environment, config:&Config, player:f32) -> Option<StateEv
                 env.revert(&action_eval.action);
             }
         });
-        println!("");
-        actions.sort_by_key(|v| NotNan::new(-v.score).unwrap());
-        level += 1;
+        self.ops_count = ops_count;
+        if let Some(reason) = config.interrupted() {
+            config.restore_tree(previous_tree);
+            self.termination = Some(reason);
+            // the previous best move is searched first, unless it completed the new scores are not comparable
+            if reason != Termination::TimeOut || !completed.first().copied().unwrap_or(false) {
+                self.actions = previous;
+                return false;
+            }
+            // moves interrupted keep the score of the last completed depth and are ranked after the completed ones
+            let mut ranked:Vec<_> = self.actions.iter().zip(previous).zip(completed).map(|((current, previous), completed)| {
+                (completed, if completed { *current } else { previous })
+            }).collect();
+            ranked.sort_by_key(|(completed, a)| (!completed, -a.score));
+            self.actions = ranked.into_iter().map(|(_, a)| a).collect();
+            debug!(depth = level, best = self.actions.first().map(|a| player*a.score), "partial depth kept");
+            return false;
+        }
+        self.actions.sort_by_key(|v| -v.score);
+        debug!(depth = level, ops = ops_count, best = self.actions.first().map(|a| player*a.score), "depth completed");
+        if let (Some(on_progress), Some(best)) = (&config.on_progress, self.actions.first()) {
+            on_progress(Progress {
+                phase:ProgressPhase::Completed,
+                depth:level,
+                best:Some(RootMove { action:best.action, score:player*best.score }),
+                ops_count
+            });
+        }
+        self.level += 1;
         
-        unexploited = !all_exploited;
+        if all_exploited {
+            self.termination = Some(Termination::Exhausted);
+        }
+        true
     }
 
-    // println!("scores: {:?}", actions.clone().into_iter().map(|a| a.score).collect::<Vec<f32>>());
-    let best_move: Option<ActionEvaluation> = match config.randomized {
-        true => {
-            let mut rng = rand::thread_rng();
-            actions.into_iter().max_by_key(|i| {
-                NotNan::new(i.score * rng.gen_range(0.8..1.2)).unwrap()
-            })
-        },
-        false => actions.into_iter().max_by_key(|i| NotNan::new(i.score).unwrap())
-    };
+    /// Evaluation based on the completed depths.
+    fn result(&self) -> StateEvaluation<A> {
+        let (config, player) = (self.config, self.player);
+        let actions = self.actions.clone();
+        let stats = SearchStats {
+            depth:self.level,
+            cutoffs:config.cutoffs.get(),
+            tt_hits:config.tt_hits.get(),
+            elapsed_micros:self.now.elapsed().as_micros(),
+            root_nodes:actions.iter().map(|a| (a.action, a.nodes)).collect(),
+        };
+        let top_moves = actions.iter().take(config.multi_pv).map(|a| RootMove {
+            action:a.action,
+            score:player*a.score
+        }).collect();
+
+        let best_move: Option<ActionEvaluation<A>> = match config.randomized {
+            true => {
+                // scores are from the perspective of the side choosing, so the best move has the highest weight
+                let best_score = actions.first().map_or(0, |a| a.score);
+                actions.choose_weighted(&mut *config.rng.borrow_mut(), |a| {
+                    ((a.score - best_score).min(0) as f32 / config.temperature).exp()
+                }).ok().copied()
+            },
+            false => {
+                let best_score = actions.first().map(|a| a.score);
+                let tied = actions.into_iter().take_while(|a| Some(a.score) == best_score);
+                match config.tie_break {
+                    TieBreak::CenterFirst => tied.min_by_key(|a| a.index),
+                    TieBreak::Random => tied.collect::<Vec<_>>().choose(&mut *config.rng.borrow_mut()).copied(),
+                    TieBreak::ShallowestWin => tied.min_by_key(|a| a.resolved_at.unwrap_or(u8::MAX)),
+                    TieBreak::MostRobust => tied.min_by_key(|a| -a.prior),
+                }
+            }
+        };
 
-    Option::Some(StateEvaluation {
-        best_action:best_move.map(|i| i.action),
-        ops_count:ops_count,
-        score:player*best_move.map_or(config.min_score, |i| i.score)
-    })
+        StateEvaluation {
+            best_action:best_move.map(|i| i.action),
+            ops_count:self.ops_count,
+            score:player*best_move.map_or(config.min_score, |i| i.score),
+            top_moves,
+            termination:self.termination.unwrap_or(Termination::DepthReached),
+            stats,
+            config:config.snapshot(),
+            tree:config.recorded_tree(),
+        }
+    }
+}
+
+impl<'a, A:Copy + Eq + Hash, E:Environment<A>> Iterator for IncrementalSearch<'a, A, E> {
+    type Item = StateEvaluation<A>;
+
+    fn next(&mut self) -> Option<StateEvaluation<A>> {
+        match self.step() {
+            true => Some(self.result()),
+            false => None
+        }
+    }
 }
 
-fn deepen_head(
-    env:&mut impl Environment, 
-    alpha:f32,
-    beta:f32,
+/// Negamax search below a root action. Scores and the window are passed and returned from the perspective
+/// of player 1, but inside a node they are flipped to the side choosing, which maximizes if `player` is positive.
+fn deepen<A:Copy + Eq + Hash, E:Environment<A> + ?Sized>(
+    env:&mut E, 
+    alpha:Score,
+    beta:Score,
     level:u8,
-    player:f32,
-    config:&Config
-) -> (f32, bool, u128) {
+    player:Score,
+    config:&Config<A>
+) -> Subtree {
+    if config.interrupted().is_some() {
+        return (0, false, 0, false);
+    }
+
     if level == 0 {
-        return (env.evaluate(), env.is_finished(), 1);
+        config.count_node();
+        let value = config.leaf_value(env);
+        return (value, env.is_finished(), 1, value.abs() >= config.max_score);
     }
 
     if env.is_finished() {
-        return (env.evaluate(), true, 1);
+        config.count_node();
+        let value = config.leaf_value(env);
+        return (value, true, 1, value.abs() >= config.max_score);
     }
 
-    env.swap_players();
-
-    let mut all_exploited = true;
-    let mut ops_count = 0;
-    let mut alpha_ = alpha;
-    let mut beta_ = beta;
-    let actions = env.actions();
-    let best_eval = match player.is_sign_positive() {
-        true => {
-            let mut best_eval = config.min_score;
-            for action in actions {
-                env.apply(&action);
-                let (eval, exploited, cnt) = deepen(env, alpha_.clone(), beta_.clone(), level - 1, -player, config);
-                all_exploited &= exploited;
-                ops_count += cnt;
+    let (mut alpha_, beta_) = match player > 0 {
+        true => (alpha, beta),
+        false => (-beta, -alpha),
+    };
 
-                env.revert(&action);
+    if let (1, Some(margin)) = (level, config.futility_margin) {
+        let static_eval = env.evaluate();
+        if player * static_eval + margin <= alpha_ {
+            config.count_node();
+            return (static_eval, false, 1, false);
+        }
+    }
 
-                if eval > best_eval {
-                    best_eval = eval;
-                }
+    if let Some(accuracy) = config.opponent_accuracy.filter(|_| player != config.root_player.get()) {
+        return expect(env, level, player, accuracy, config);
+    }
 
-                if eval > alpha_ {
-                    alpha_ = eval;
-                }
+    let hash = config.memoize.then(|| env.state_hash()).flatten();
+    if let Some(entry) = hash.and_then(|h| config.memoized(h, level, alpha_, beta_)) {
+        return (config.discount(player * entry.value, entry.proven), entry.exploited, 0, entry.proven);
+    }
+    let floor = alpha_;
 
-                if beta_ <= alpha_ {
-                    println!("player 1 breaks at {:?}", eval);
-                    break;
-                }
-            }
-            best_eval
-        },
-        false => {
-            let mut best_eval = config.max_score;
-            for action in actions {
-                env.apply(&action);
-                let (eval, exploited, cnt) = deepen(env, alpha_, beta_, level - 1, -player, config);
-                all_exploited &= exploited;
-                ops_count += cnt;
+    env.swap_players();
 
-                env.revert(&action);
+    let mut all_exploited = true;
+    let mut ops_count = 0;
+    let mut best_eval = match player > 0 {
+        true => config.min_score,
+        false => -config.max_score,
+    };
+    let mut best_proven = false;
 
-                if eval < best_eval {
-                    best_eval = eval;
-                }
+    let actions = match config.move_ordering && level > 1 {
+        true => env.ordered_actions(),
+        false => env.actions(),
+    };
+    let split = config.threads > 1 && level >= SPLIT_MIN_DEPTH;
+    let mut index = 0;
+    'search: while index < actions.len() {
+        let (lower, upper) = match player > 0 {
+            true => (alpha_, beta_),
+            false => (-beta_, -alpha_),
+        };
+        // the first child is searched alone, its siblings profit from the window it establishes
+        let batch = match split && index > 0 {
+            true => &actions[index..actions.len().min(index + config.threads)],
+            false => &actions[index..=index],
+        };
+        let results = search_batch(env, batch, lower, upper, level, index, -player, config);
+        index += batch.len();
+
+        for (eval, exploited, cnt, proven) in results {
+            all_exploited &= exploited;
+            ops_count += cnt;
+
+            let eval = player * eval;
+            if eval > best_eval {
+                best_eval = eval;
+                best_proven = proven;
+            } else if eval == best_eval {
+                // a loss equals the initial value
+                best_proven |= proven;
+            }
 
-                if eval < beta_ {
-                    beta_ = eval;
-                }
+            if eval > alpha_ {
+                alpha_ = eval;
+            }
 
-                if beta_ <= alpha_ {
-                    println!("player -1 breaks at {:?}", eval);
-                    break;
-                }
+            if beta_ <= alpha_ {
+                trace!(depth = level, player, eval = player * eval, "cutoff");
+                config.cutoffs.set(config.cutoffs.get() + 1);
+                config.record_cutoff();
+                break 'search;
             }
-            best_eval
         }
-    };
+    }
 
     env.swap_players();
-    (config.epsilon*best_eval, all_exploited, ops_count)
+    if let Some(hash) = hash {
+        let bound = match best_eval {
+            v if v >= beta_ => Bound::Lower,
+            v if v <= floor => Bound::Upper,
+            _ => Bound::Exact,
+        };
+        config.memoize(hash, TableEntry { level, value: best_eval, bound, exploited: all_exploited, proven: best_proven });
+    }
+    (config.discount(player * best_eval, best_proven), all_exploited, ops_count, best_proven)
 }
 
-fn deepen(
-    env:&mut impl Environment, 
-    alpha:f32,
-    beta:f32,
+/// Chance node of expectimax, see `Config::set_expectimax`. Mixes the best reply of the side choosing with
+/// the average of all replies. Every reply needs its exact value, so children are searched with the full window.
+fn expect<A:Copy + Eq + Hash, E:Environment<A> + ?Sized>(
+    env:&mut E,
     level:u8,
-    player:f32,
-    config:&Config
-) -> (f32, bool, u128) {
-    if level == 0 {
-        return (env.evaluate(), env.is_finished(), 1);
-    }
-
-    if env.is_finished() {
-        return (env.evaluate(), true, 1);
-    }
-
+    player:Score,
+    accuracy:f32,
+    config:&Config<A>
+) -> Subtree {
     env.swap_players();
 
     let mut all_exploited = true;
     let mut ops_count = 0;
-    let mut alpha_ = alpha;
-    let mut beta_ = beta;
-    let actions = env.actions();
-
-    let mut best_eval = match player.is_sign_positive() {
-        true => config.min_score,
-        false => config.max_score,
+    let mut evals = Vec::new();
+    for (index, action) in env.actions().into_iter().enumerate() {
+        env.apply(&action);
+        config.enter(action, config.min_score, config.max_score);
+        let (eval, exploited, cnt, proven) = deepen_child(env, config.min_score, config.max_score, level, index, -player, config);
+        config.leave(eval);
+        all_exploited &= exploited;
+        ops_count += cnt;
+        env.revert(&action);
+        evals.push((player * eval, proven));
     }
 
-    let best_eval = match player.is_sign_positive() {
-        true => {
-            let mut best_eval = config.min_score;
-            for action in actions {
-                env.apply(&action);
-                let (eval, exploited, cnt) = deepen(env, alpha_.clone(), beta_.clone(), level - 1, -player, config);
-                all_exploited &= exploited;
-                ops_count += cnt;
-
-                env.revert(&action);
-
-                if eval > best_eval {
-                    best_eval = eval;
-                }
-
-                if eval > alpha_ {
-                    alpha_ = eval;
-                }
-
-                if beta_ <= alpha_ {
-                    println!("player 1 breaks at {:?}", eval);
-                    break;
-                }
-            }
-            best_eval
-        },
-        false => {
-            let mut best_eval = config.max_score;
-            for action in actions {
-                env.apply(&action);
-                let (eval, exploited, cnt) = deepen(env, alpha_, beta_, level - 1, -player, config);
-                all_exploited &= exploited;
-                ops_count += cnt;
-
-                env.revert(&action);
+    env.swap_players();
+    let best = evals.iter().map(|(eval, _)| *eval).max().unwrap_or(config.min_score);
+    let mean = evals.iter().map(|(eval, _)| *eval).sum::<Score>() as f32 / evals.len() as f32;
+    let expected = (accuracy * best as f32 + (1. - accuracy) * mean).round() as Score;
+    // only a result every reply leads to is proven
+    let proven = evals.iter().all(|(eval, proven)| *proven && *eval == expected);
+    (config.discount(player * expected, proven), all_exploited, ops_count, proven)
+}
 
-                if eval < best_eval {
-                    best_eval = eval;
-                }
+/// Searches the positions after `actions`, the first of which is the `index`-th action of a node at `level`.
+/// Several actions are searched in parallel on split environments, all of them with the same window.
+#[allow(clippy::too_many_arguments)]
+fn search_batch<A:Copy + Eq + Hash, E:Environment<A> + ?Sized>(
+    env:&mut E,
+    actions:&[A],
+    alpha:Score,
+    beta:Score,
+    level:u8,
+    index:usize,
+    player:Score,
+    config:&Config<A>
+) -> Vec<Subtree> {
+    let children = match actions.len() {
+        1 => None,
+        _ => actions.iter().map(|action| {
+            env.apply(action);
+            let child = env.split();
+            env.revert(action);
+            child
+        }).collect::<Option<Vec<_>>>(),
+    };
+    let Some(children) = children else {
+        return actions.iter().enumerate().map(|(i, action)| {
+            env.apply(action);
+            config.enter(*action, alpha, beta);
+            let result = deepen_child(env, alpha, beta, level, index + i, player, config);
+            config.leave(result.0);
+            env.revert(action);
+            result
+        }).collect();
+    };
 
-                if eval < beta_ {
-                    beta_ = eval;
-                }
+    let settings = config.snapshot();
+    let max_nodes = config.max_nodes.map(|max_nodes| max_nodes.saturating_sub(config.visited.get()));
+    let (root_player, deadline) = (config.root_player.get(), config.deadline.get());
+    let helpers = thread::scope(|scope| {
+        let handles:Vec<_> = children.into_iter().enumerate().map(|(i, mut child)| {
+            let (settings, cancel) = (&settings, config.cancel.clone());
+            scope.spawn(move || {
+                let helper = Config::<A>::helper(settings, cancel, deadline, root_player, max_nodes);
+                let result = deepen_child(&mut *child, alpha, beta, level, index + i, player, &helper);
+                (result, helper.counters())
+            })
+        }).collect();
+        handles.into_iter().map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e))).collect::<Vec<_>>()
+    });
+    actions.iter().zip(helpers).map(|(action, (result, counters))| {
+        config.merge(counters);
+        config.enter(*action, alpha, beta);
+        config.leave(result.0);
+        result
+    }).collect()
+}
 
-                if beta_ <= alpha_ {
-                    println!("player -1 breaks at {:?}", eval);
-                    break;
-                }
-            }
-            best_eval
+/// Searches the position after the `index`-th action of a node at `level`. Forced positions are searched one ply
+/// deeper if extensions are enabled. With late move reduction, late actions are searched one ply shallower first
+/// and only searched again at full depth if they improve the window of the parent, which maximizes if `player` is negative.
+fn deepen_child<A:Copy + Eq + Hash, E:Environment<A> + ?Sized>(
+    env:&mut E,
+    alpha:Score,
+    beta:Score,
+    level:u8,
+    index:usize,
+    player:Score,
+    config:&Config<A>
+) -> Subtree {
+    if config.forced_move_extensions && (env.actions().len() == 1 || env.is_forcing()) {
+        return deepen(env, alpha, beta, level, player, config);
+    }
+    if config.late_move_reduction && level >= LMR_MIN_DEPTH && index >= LMR_FULL_MOVES {
+        let (eval, exploited, cnt, proven) = deepen(env, alpha, beta, level - 2, player, config);
+        let improves = match player > 0 {
+            true => eval < beta,
+            false => eval > alpha,
+        };
+        if !improves {
+            return (eval, exploited, cnt, proven);
         }
-    };
-
-    env.swap_players();
-    (config.epsilon*best_eval, all_exploited, ops_count)
+        let (eval, exploited, full, proven) = deepen(env, alpha, beta, level - 1, player, config);
+        return (eval, exploited, cnt + full, proven);
+    }
+    deepen(env, alpha, beta, level - 1, player, config)
 }
 
 #[cfg(test)]
 mod tests {
     use std::default;
 
-    use float_cmp::assert_approx_eq;
     use indextree::{Arena, NodeId};
     use rand::prelude::*;
     use super::*;
 
     struct Game {
-        arena:Arena<f32>,
+        arena:Arena<Score>,
         state:NodeId,
     }
     
     impl Environment for Game {
-        fn evaluate(&mut self) -> f32 {
+        fn evaluate(&mut self) -> Score {
             *self.arena.get(self.state).unwrap().get()
         }
      
@@ -384,13 +1348,336 @@ mod tests {
         fn swap_players(&mut self) { }
     }
 
+    /// Picks one of a few named options, each finishing the game with a fixed score.
+    #[derive(Clone)]
+    struct Menu {
+        options:Vec<(char, Score)>,
+        picked:Option<char>,
+    }
+
+    impl Environment<char> for Menu {
+        fn evaluate(&mut self) -> Score {
+            self.options.iter().find(|(c, _)| Some(*c) == self.picked).map_or(0, |(_, score)| *score)
+        }
+
+        fn apply(&mut self, action:&char) {
+            self.picked = Some(*action);
+        }
+
+        fn revert(&mut self, _action:&char) {
+            self.picked = None;
+        }
+
+        fn is_finished(&mut self) -> bool {
+            self.picked.is_some()
+        }
+
+        fn actions(&self) -> Vec<char> {
+            self.options.iter().map(|(c, _)| *c).collect()
+        }
+
+        fn swap_players(&mut self) { }
+    }
+
+    impl ForkableEnvironment<char> for Menu {
+        fn fork(&self) -> Self {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn generic_actions() {
+        let mut menu = Menu { options:vec![('a', 1), ('b', 3), ('c', -2)], picked:None };
+        let mut config = Config {..Default::default() };
+        config.set_multi_pv(1);
+
+        let result = maximize(&mut menu, &config).unwrap();
+        assert_eq!(Some('b'), result.best_action);
+        assert_eq!(vec![RootMove {action:'b', score:3}], result.top_moves);
+        assert_eq!(Some('c'), minimize(&mut menu, &config).unwrap().best_action);
+    }
+
+    #[test]
+    fn builder() {
+        let builder = || ConfigBuilder::new(-127, 0.95);
+        assert!(builder().max_depth(3).build::<usize>().is_ok());
+        assert!(builder().time_limit(100).randomized(true).build::<usize>().is_ok());
+        assert_eq!(Some(ConfigError::NoLimit), builder().build::<usize>().err());
+        assert!(builder().max_depth(3).time_limit(100).build::<usize>().is_ok());
+        assert_eq!(Some(ConfigError::ZeroLimit), builder().max_depth(3).time_limit(0).build::<usize>().err());
+        assert_eq!(Some(ConfigError::ZeroLimit), builder().time_limit(0).build::<usize>().err());
+        assert_eq!(Some(ConfigError::MinScore(1)), ConfigBuilder::new(1, 0.95).max_depth(3).build::<usize>().err());
+        assert_eq!(Some(ConfigError::Epsilon(0.)), ConfigBuilder::new(-127, 0.).max_depth(3).build::<usize>().err());
+    }
+
+    #[test]
+    fn combined_limits() {
+        let mut arena = Arena::new();
+        let root = arena.new_node(0);
+        root.append_value(5, &mut arena).append_value(-1, &mut arena).append_value(2, &mut arena);
+        root.append_value(1, &mut arena).append_value(4, &mut arena).append_value(3, &mut arena);
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let config = ConfigBuilder::new(-127, 1.).max_depth(1).time_limit(60_000).build().unwrap();
+        let result = maximize(&mut game, &config).unwrap();
+        assert_eq!(Termination::DepthReached, result.termination);
+        assert_eq!(1, result.stats.depth);
+
+        let config = ConfigBuilder::new(-127, 1.).max_depth(20).time_limit(60_000).build().unwrap();
+        assert_eq!(Termination::Exhausted, maximize(&mut game, &config).unwrap().termination);
+    }
+
+    /// Two moves in every state and never finished.
+    struct Endless {
+        depth:usize,
+    }
+
+    impl Environment for Endless {
+        fn evaluate(&mut self) -> Score {
+            0
+        }
+
+        fn apply(&mut self, _action:&usize) {
+            self.depth += 1;
+        }
+
+        fn revert(&mut self, _action:&usize) {
+            self.depth -= 1;
+        }
+
+        fn is_finished(&mut self) -> bool {
+            false
+        }
+
+        fn actions(&self) -> Vec<usize> {
+            vec![0, 1]
+        }
+
+        fn swap_players(&mut self) { }
+    }
+
+    impl ForkableEnvironment for Endless {
+        fn fork(&self) -> Self {
+            Endless { depth:self.depth }
+        }
+    }
+
+    /// Three moves in every state, scored by a pseudo random function of the moves played.
+    #[derive(Clone)]
+    struct Scrambled {
+        moves:Vec<usize>,
+    }
+
+    impl Environment for Scrambled {
+        fn evaluate(&mut self) -> Score {
+            let hash = self.moves.iter().fold(7, |h, m| (h * 31 + m + 1) % 1009);
+            (hash % 21) as Score - 10
+        }
+
+        fn apply(&mut self, action:&usize) {
+            self.moves.push(*action);
+        }
+
+        fn revert(&mut self, _action:&usize) {
+            self.moves.pop();
+        }
+
+        fn is_finished(&mut self) -> bool {
+            false
+        }
+
+        fn actions(&self) -> Vec<usize> {
+            vec![0, 1, 2]
+        }
+
+        fn swap_players(&mut self) { }
+
+        fn split(&self) -> Option<Box<dyn Environment + Send>> {
+            Some(Box::new(self.clone()))
+        }
+    }
+
+    #[test]
+    fn parallel_search() {
+        let mut game = Scrambled { moves:Vec::new() };
+        let search = |threads, game:&mut Scrambled| {
+            let mut config = ConfigBuilder::new(-127, 0.95).max_depth(7).build().unwrap();
+            config.set_multi_pv(3);
+            config.set_threads(threads);
+            let result = maximize(game, &config).unwrap();
+            (result.best_action, result.top_moves, result.stats.depth)
+        };
+        let sequential = search(1, &mut game);
+        assert_eq!(sequential, search(4, &mut game));
+        assert_eq!(sequential, search(2, &mut game));
+        assert!(game.moves.is_empty());
+    }
+
+    /// Either agrees to a draw or takes a slightly favourable finished state.
+    struct Truce {
+        drawn:Option<bool>,
+    }
+
+    impl Environment for Truce {
+        fn evaluate(&mut self) -> Score {
+            match self.drawn {
+                Some(false) => 1,
+                _ => 0,
+            }
+        }
+
+        fn apply(&mut self, action:&usize) {
+            self.drawn = Some(*action == 0);
+        }
+
+        fn revert(&mut self, _action:&usize) {
+            self.drawn = None;
+        }
+
+        fn is_finished(&mut self) -> bool {
+            self.drawn.is_some()
+        }
+
+        fn actions(&self) -> Vec<usize> {
+            vec![0, 1]
+        }
+
+        fn swap_players(&mut self) { }
+
+        fn is_draw(&mut self) -> bool {
+            self.drawn == Some(true)
+        }
+    }
+
+    /// Two moves finishing the game, the second one takes long to evaluate.
+    struct Sluggish {
+        picked:Option<usize>,
+    }
+
+    impl Environment for Sluggish {
+        fn evaluate(&mut self) -> Score {
+            if self.picked == Some(1) {
+                thread::sleep(Duration::from_millis(200));
+                return 5;
+            }
+            1
+        }
+
+        fn apply(&mut self, action:&usize) {
+            self.picked = Some(*action);
+        }
+
+        fn revert(&mut self, _action:&usize) {
+            self.picked = None;
+        }
+
+        fn is_finished(&mut self) -> bool {
+            self.picked.is_some()
+        }
+
+        fn actions(&self) -> Vec<usize> {
+            vec![0, 1]
+        }
+
+        fn swap_players(&mut self) { }
+    }
+
+    #[test]
+    fn proven_scores() {
+        //        root
+        //      +--+--+
+        //      a     b
+        //      |     |
+        //     100    c
+        //            |
+        //           127
+        let mut arena = Arena::new();
+        let root = arena.new_node(0);
+        root.append_value(0, &mut arena).append_value(100, &mut arena);
+        root.append_value(0, &mut arena).append_value(0, &mut arena).append_value(127, &mut arena);
+        let mut game = Game { arena, state:root };
+
+        // the win loses a point per ply instead of being halved
+        let mut config = ConfigBuilder::new(-127, 0.5).max_depth(3).build().unwrap();
+        config.set_multi_pv(2);
+        let result = maximize(&mut game, &config).unwrap();
+        assert_eq!(vec![RootMove {action:1, score:125}, RootMove {action:0, score:50}], result.top_moves);
+
+        let mut config = ConfigBuilder::new(-127, 1.).max_depth(3).build().unwrap();
+        config.set_multi_pv(2);
+        let result = maximize(&mut game, &config).unwrap();
+        assert_eq!(vec![RootMove {action:1, score:125}, RootMove {action:0, score:100}], result.top_moves);
+        // scores are from the perspective of player 1, the minimizer avoids its loss
+        let result = minimize(&mut game, &config).unwrap();
+        assert_eq!(vec![RootMove {action:0, score:100}, RootMove {action:1, score:125}], result.top_moves);
+    }
+
+    #[test]
+    fn hard_time_limit() {
+        let config = ConfigBuilder::new(-127, 0.95).time_limit(50).max_depth(60).build().unwrap();
+        let now = Instant::now();
+        let result = maximize(&mut Endless { depth:0 }, &config).unwrap();
+        assert!(now.elapsed().as_millis() < 1000);
+        assert_eq!(Termination::TimeOut, result.termination);
+
+        // the first move was searched completely before the time ran out, the second one is not trusted
+        let config = ConfigBuilder::new(-127, 1.).time_limit(100).max_depth(3).build().unwrap();
+        let result = maximize(&mut Sluggish { picked:None }, &config).unwrap();
+        assert_eq!((Some(0), 1), (result.best_action, result.score));
+        assert_eq!((Termination::TimeOut, 0), (result.termination, result.stats.depth));
+    }
+
+    #[test]
+    fn draw_score() {
+        let mut truce = Truce { drawn:None };
+        let mut config = Config {epsilon:EPSILON_SCALE, ..Default::default() };
+        assert_eq!(Some(1), maximize(&mut truce, &config).unwrap().best_action);
+        assert_eq!(Some(0), minimize(&mut truce, &config).unwrap().best_action);
+
+        config.set_draw_score(2);
+        let result = maximize(&mut truce, &config).unwrap();
+        assert_eq!((Some(0), 2), (result.best_action, result.score));
+
+        config.set_draw_score(-2);
+        let result = minimize(&mut truce, &config).unwrap();
+        assert_eq!((Some(1), 1), (result.best_action, result.score));
+        assert_eq!(Some(1), maximize(&mut truce, &config).unwrap().best_action);
+    }
+
+    #[test]
+    fn eval_async() {
+        let cancel = CancelToken::default();
+        let menu = Menu { options:vec![('a', 1), ('b', 3)], picked:None };
+        let handle = super::eval_async(&menu, || Ok(Config {..Default::default() }), 1, cancel.clone()).unwrap();
+        assert_eq!(Some('b'), handle.wait().unwrap().unwrap().best_action);
+        assert!(!cancel.load(Ordering::Relaxed));
+        assert_eq!(None, menu.picked);
+
+        let endless = Endless { depth:0 };
+        let handle = super::eval_async(&endless, || {
+            Ok(ConfigBuilder::new(-127, 0.95).max_depth(60).build()?)
+        }, -1, cancel.clone()).unwrap();
+        handle.cancel();
+        let result = handle.wait().unwrap().unwrap();
+        assert_eq!(Termination::Cancelled, result.termination);
+        assert!(result.best_action.is_some());
+
+        let handle = super::eval_async(&endless, || {
+            Ok(ConfigBuilder::new(-127, 0.95).build()?)
+        }, 1, CancelToken::default()).unwrap();
+        assert_eq!(Some(ConfigError::NoLimit.to_string()), handle.wait().err());
+    }
+
     #[test]
     fn simple_case() {      
         let mut arena = Arena::new();
 
-        let root = arena.new_node(0.0);
-        root.append_value(10.0, &mut arena);
-        root.append_value(-5.0, &mut arena);
+        let root = arena.new_node(0);
+        root.append_value(10, &mut arena);
+        root.append_value(-5, &mut arena);
 
         let mut game = Game {
             arena:arena,
@@ -399,9 +1686,330 @@ mod tests {
         let config = Config {..Default::default() };
 
         let result = maximize(&mut game, &config).unwrap();
-        assert_approx_eq!(f32, 10., result.score, ulps=2);
+        assert_eq!(10, result.score);
         assert_eq!(2, result.ops_count);
-        assert_approx_eq!(f32, -5., minimize(&mut game, &config).unwrap().score, ulps=2);
+        assert_eq!(-5, minimize(&mut game, &config).unwrap().score);
+    }
+
+    #[test]
+    fn progress() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let mut arena = Arena::new();
+
+        let root = arena.new_node(0);
+        root.append_value(-5, &mut arena);
+        root.append_value(10, &mut arena);
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        let mut config = Config {..Default::default() };
+        let sink = reported.clone();
+        config.set_on_progress(Box::new(move |p| sink.borrow_mut().push(p)));
+
+        let result = minimize(&mut game, &config).unwrap();
+        assert_eq!(vec![
+            Progress {phase:ProgressPhase::Started, depth:0, best:None, ops_count:0},
+            Progress {phase:ProgressPhase::Completed, depth:0, best:Some(RootMove {action:0, score:-5}), ops_count:result.ops_count},
+        ], *reported.borrow());
+        assert_eq!(Termination::Exhausted, result.termination);
+    }
+
+    #[test]
+    fn tie_break() {
+        //           r
+        //     +-----+-----+
+        //     |     |     |
+        //     3     5     1
+        //           |     |
+        //           3     3
+
+        let mut arena = Arena::new();
+        let root = arena.new_node(0);
+        root.append_value(3, &mut arena);
+        root.append_value(5, &mut arena).append_value(3, &mut arena);
+        root.append_value(1, &mut arena).append_value(3, &mut arena);
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let mut config = Config {epsilon:EPSILON_SCALE, ..Default::default() };
+        assert_eq!(Some(0), maximize(&mut game, &config).unwrap().best_action);
+
+        config.set_tie_break(TieBreak::ShallowestWin);
+        assert_eq!(Some(0), maximize(&mut game, &config).unwrap().best_action);
+
+        config.set_tie_break(TieBreak::MostRobust);
+        assert_eq!(Some(1), maximize(&mut game, &config).unwrap().best_action);
+
+        config.set_tie_break(TieBreak::Random);
+        let mut picks: Vec<usize> = (0..20).map(|_| maximize(&mut game, &config).unwrap().best_action.unwrap()).collect();
+        picks.sort();
+        picks.dedup();
+        assert!(picks.len() > 1);
+    }
+
+    #[test]
+    fn incremental() {
+        let mut arena = Arena::new();
+        let root = arena.new_node(0);
+        root.append_value(3, &mut arena);
+        root.append_value(5, &mut arena).append_value(-1, &mut arena);
+        root.append_value(1, &mut arena).append_value(4, &mut arena);
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let config = Config {epsilon:EPSILON_SCALE, ..Default::default() };
+        let mut search = search_incremental(&mut game, &config, 1).unwrap();
+
+        let first = search.next().unwrap();
+        assert_eq!((Some(1), 5), (first.best_action, first.score));
+        assert_eq!(Termination::DepthReached, first.termination);
+        assert_eq!(None, search.termination());
+
+        let second = search.next().unwrap();
+        assert_eq!((Some(2), 4), (second.best_action, second.score));
+        assert_eq!(Termination::Exhausted, second.termination);
+        assert!(search.next().is_none());
+        assert_eq!(Some(Termination::Exhausted), search.termination());
+
+        let full = maximize(&mut game, &config).unwrap();
+        assert_eq!((second.best_action, second.ops_count), (full.best_action, full.ops_count));
+    }
+
+    #[test]
+    fn deterministic() {
+        let mut arena = Arena::new();
+
+        let root = arena.new_node(0);
+        for _ in 0..5 {
+            root.append_value(1, &mut arena);
+        }
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let mut picks = Vec::new();
+        for seed in 0..20 {
+            let config = ConfigBuilder::new(-127, 0.95).time_limit(1).max_depth(3).randomized(true).deterministic(seed).build().unwrap();
+            let result = maximize(&mut game, &config).unwrap();
+            assert_eq!(result.best_action, maximize(&mut game, &config).unwrap().best_action);
+            assert_eq!(None, result.config.time_limit_millis);
+
+            let snapshot:ConfigSnapshot = serde_json::from_str(&serde_json::to_string(&result.config).unwrap()).unwrap();
+            let replayed = maximize(&mut game, &snapshot.build().unwrap()).unwrap();
+            assert_eq!((result.best_action, result.ops_count), (replayed.best_action, replayed.ops_count));
+            picks.push(result.best_action.unwrap());
+        }
+        picks.sort();
+        picks.dedup();
+        assert!(picks.len() > 1);
+        assert_eq!(Some(ConfigError::NoDepth), ConfigBuilder::new(-127, 0.95).time_limit(100).deterministic(1).build::<usize>().err());
+    }
+
+    #[test]
+    fn seeded() {
+        let mut arena = Arena::new();
+
+        let root = arena.new_node(0);
+        for _ in 0..5 {
+            root.append_value(1, &mut arena);
+        }
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let mut picks = Vec::new();
+        for seed in 0..20 {
+            let mut config = Config {randomized:true, ..Default::default() };
+            config.set_seed(seed);
+            let first = maximize(&mut game, &config).unwrap().best_action;
+            let second = maximize(&mut game, &config).unwrap().best_action;
+
+            config.set_seed(seed);
+            assert_eq!(first, maximize(&mut game, &config).unwrap().best_action);
+            assert_eq!(second, maximize(&mut game, &config).unwrap().best_action);
+            picks.push(first.unwrap());
+        }
+        picks.sort();
+        picks.dedup();
+        assert!(picks.len() > 1);
+    }
+
+    #[test]
+    fn temperature() {
+        let mut arena = Arena::new();
+
+        let root = arena.new_node(0);
+        root.append_value(-10, &mut arena);
+        root.append_value(-9, &mut arena);
+        root.append_value(-2, &mut arena);
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let picks = |temperature:f32, player:Score, game:&mut Game| {
+            let mut config = ConfigBuilder::new(-127, 1.).max_depth(1).randomized(true).temperature(temperature).build().unwrap();
+            config.set_seed(7);
+            let mut picks = (0..50).map(|_| eval(game, &config, player).unwrap().best_action.unwrap()).collect::<Vec<_>>();
+            picks.sort();
+            picks.dedup();
+            picks
+        };
+        assert_eq!(vec![2], picks(0.01, 1, &mut game));
+        assert_eq!(vec![0], picks(0.01, -1, &mut game));
+        assert_eq!(vec![0, 1], picks(1., -1, &mut game));
+        assert_eq!(vec![0, 1, 2], picks(100., -1, &mut game));
+        assert_eq!(Some(ConfigError::Temperature(0.)), ConfigBuilder::new(-127, 1.).max_depth(1).temperature(0.).build::<usize>().err());
+    }
+
+    #[test]
+    fn stats() {
+        let mut arena = Arena::new();
+
+        let root = arena.new_node(0);
+        root.append_value(10, &mut arena);
+        root.append_value(-5, &mut arena);
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let config = Config {..Default::default() };
+
+        let stats = maximize(&mut game, &config).unwrap().stats;
+        assert_eq!(1, stats.depth);
+        assert_eq!(0, stats.cutoffs);
+        assert_eq!(0, stats.tt_hits);
+        assert_eq!(vec![(0, 1), (1, 1)], stats.root_nodes);
+    }
+
+    #[test]
+    fn tree_recording() {
+        let mut arena = Arena::new();
+        let root = arena.new_node(0);
+        root.append_value(5, &mut arena).append_value(-1, &mut arena);
+        root.append_value(1, &mut arena).append_value(4, &mut arena);
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let mut config = Config {epsilon:EPSILON_SCALE, ..Default::default() };
+        assert_eq!(None, maximize(&mut game, &config).unwrap().tree);
+
+        config.set_tree_recording(true);
+        let tree = maximize(&mut game, &config).unwrap().tree.unwrap();
+        let nodes = tree.nodes.iter().map(|n| (n.parent, n.action, n.depth, n.score)).collect::<Vec<_>>();
+        assert_eq!(vec![(None, 0, 1, -1), (Some(0), 0, 2, -1), (None, 1, 1, 4), (Some(2), 0, 2, 4)], nodes);
+        assert!(tree.to_dot().contains("root -> n2;\n    n3 [label=\"0\\n[-127, 127]\\n4\"];\n    n2 -> n3;"));
+    }
+
+    #[test]
+    fn multi_pv() {
+        let mut arena = Arena::new();
+
+        let root = arena.new_node(0);
+        root.append_value(10, &mut arena);
+        root.append_value(-5, &mut arena);
+        root.append_value(3, &mut arena);
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let mut config = Config {..Default::default() };
+        config.set_multi_pv(2);
+
+        let result = maximize(&mut game, &config).unwrap();
+        assert_eq!(vec![RootMove {action:0, score:10}, RootMove {action:2, score:3}], result.top_moves);
+
+        let result = minimize(&mut game, &config).unwrap();
+        assert_eq!(vec![RootMove {action:1, score:-5}, RootMove {action:2, score:3}], result.top_moves);
+
+        config.set_multi_pv(0);
+        assert!(maximize(&mut game, &config).unwrap().top_moves.is_empty());
+    }
+
+    #[test]
+    fn cancelled() {
+        let mut arena = Arena::new();
+
+        let root = arena.new_node(0);
+        root.append_value(-5, &mut arena);
+        root.append_value(10, &mut arena);
+
+        let mut game = Game {
+            arena:arena,
+            state:root,
+        };
+        let mut config = Config {..Default::default() };
+        let cancel = CancelToken::default();
+        config.set_cancel_token(cancel.clone());
+        cancel.store(true, Ordering::Relaxed);
+
+        // nothing was searched, but a legal action is still returned
+        let result = maximize(&mut game, &config).unwrap();
+        assert_eq!(0, result.ops_count);
+        assert!(result.best_action.is_some());
+        assert_eq!(Termination::Cancelled, result.termination);
+    }
+
+    #[test]
+    fn expectimax() {
+        //           r
+        //     +-----+-----+
+        //     |           |
+        //     y           x
+        //   +---+       +---+
+        //   |   |       |   |
+        //   4   8     -20   40
+
+        let mut arena = Arena::new();
+        let y = arena.new_node(0);
+        y.append_value(4, &mut arena);
+        y.append_value(8, &mut arena);
+        let x = arena.new_node(0);
+        x.append_value(-20, &mut arena);
+        x.append_value(40, &mut arena);
+        let r = arena.new_node(0);
+        r.append(y, &mut arena);
+        r.append(x, &mut arena);
+
+        let mut game = Game {
+            arena:arena,
+            state:r,
+        };
+        let mut config = Config {epsilon:EPSILON_SCALE, ..Default::default() };
+        config.root_player.set(1);
+        let (score, _, ops_count, _) = deepen(&mut game, config.min_score, config.max_score, 2, 1, &config);
+        assert_eq!(4, score);
+        assert_eq!(3, ops_count);
+
+        // the opponent is expected to miss the refutation half of the time
+        config.set_expectimax(Some(0.5)).unwrap();
+        let (score, _, ops_count, _) = deepen(&mut game, config.min_score, config.max_score, 2, 1, &config);
+        assert_eq!(5, score);
+        assert_eq!(4, ops_count);
+
+        config.set_expectimax(Some(1.)).unwrap();
+        let (score, _, _, _) = deepen(&mut game, config.min_score, config.max_score, 2, 1, &config);
+        assert_eq!(4, score);
+
+        // an accuracy which is no probability is rejected, also when it comes from stored settings
+        assert_eq!(Err(ConfigError::Accuracy(1.5)), config.set_expectimax(Some(1.5)));
+        assert_eq!(Some(1.), config.opponent_accuracy);
+        let snapshot = ConfigSnapshot { opponent_accuracy: Some(f32::NAN), ..config.snapshot() };
+        assert!(matches!(snapshot.build::<usize>(), Err(ConfigError::Accuracy(a)) if a.is_nan()));
     }
 
     #[test]
@@ -417,17 +2025,17 @@ mod tests {
 
         let mut arena = Arena::new();
         
-        let aa = arena.new_node(0.0);
-        aa.append_value(10., &mut arena);
-        aa.append_value(-5., &mut arena);
-        aa.append_value(3., &mut arena);
+        let aa = arena.new_node(0);
+        aa.append_value(10, &mut arena);
+        aa.append_value(-5, &mut arena);
+        aa.append_value(3, &mut arena);
 
-        let ab = arena.new_node(0.);
-        ab.append_value(-6., &mut arena);
+        let ab = arena.new_node(0);
+        ab.append_value(-6, &mut arena);
         ab.append_value(random(), &mut arena);
         ab.append_value(random(), &mut arena);
 
-        let a = arena.new_node(0.);
+        let a = arena.new_node(0);
         a.append(aa, &mut arena);
         a.append(ab, &mut arena);
 
@@ -436,19 +2044,19 @@ mod tests {
             state:a,
         };
 
-        let config = Config {epsilon:1., ..Default::default() };
+        let config = Config {epsilon:EPSILON_SCALE, ..Default::default() };
         
-        let (score, all_exploited, ops_count) = deepen(&mut game, config.min_score.clone(), 
-        config.max_score.clone(), 2, 1., &config);
-        assert_approx_eq!(f32, -5., score);
+        let (score, all_exploited, ops_count, _) = deepen(&mut game, config.min_score.clone(), 
+        config.max_score.clone(), 2, 1, &config);
+        assert_eq!(-5, score);
         assert_eq!(4, ops_count);
         assert!(all_exploited);
 
         // let result = maximize(&mut game, &config).unwrap();
-        // assert_approx_eq!(f32, -5., result.score);
+        // assert_eq!(-5, result.score);
         // assert_eq!(7, result.ops_count);
 
-        // assert_approx_eq!(f32, 10., minimize(&mut game, &config).unwrap().score);
+        // assert_eq!(10, minimize(&mut game, &config).unwrap().score);
     }
 
     #[test]
@@ -463,35 +2071,35 @@ mod tests {
         // |   |   |   |   |   |   |   |   |   |   |   |
         // 1  -5   3  -6   15  ?   10  12  3   13  ?   ? 
         
-        let aa = arena.new_node(0.);
-        aa.append_value(10., &mut arena);
-        aa.append_value(-5., &mut arena);
-        aa.append_value(3., &mut arena);
-
-        let ab = arena.new_node(0.);
-        ab.append_value(-6., &mut arena);
-        ab.append_value(15., &mut arena);
+        let aa = arena.new_node(0);
+        aa.append_value(10, &mut arena);
+        aa.append_value(-5, &mut arena);
+        aa.append_value(3, &mut arena);
+
+        let ab = arena.new_node(0);
+        ab.append_value(-6, &mut arena);
+        ab.append_value(15, &mut arena);
         ab.append_value(random(), &mut arena);
 
-        let a = arena.new_node(0.);
+        let a = arena.new_node(0);
         a.append(aa, &mut arena);
         a.append(ab, &mut arena);
 
-        let ba = arena.new_node(0.);
-        ba.append_value(10., &mut arena);
-        ba.append_value(12., &mut arena);
-        ba.append_value(3., &mut arena);
+        let ba = arena.new_node(0);
+        ba.append_value(10, &mut arena);
+        ba.append_value(12, &mut arena);
+        ba.append_value(3, &mut arena);
 
-        let bb = arena.new_node(0.);
-        bb.append_value(13., &mut arena);
+        let bb = arena.new_node(0);
+        bb.append_value(13, &mut arena);
         bb.append_value(random(), &mut arena);
         bb.append_value(random(), &mut arena);
 
-        let b = arena.new_node(0.);
+        let b = arena.new_node(0);
         b.append(ba, &mut arena);
         b.append(bb, &mut arena);
 
-        let root = arena.new_node(0.);
+        let root = arena.new_node(0);
         root.append(a, &mut arena);
         root.append(b, &mut arena);
 
@@ -500,16 +2108,16 @@ mod tests {
             state:root,
         };
 
-        let config = Config {epsilon:1.0, ..Default::default() };
+        let config = Config {epsilon:EPSILON_SCALE, ..Default::default() };
 
-        let (score, all_exploited, ops_count) = deepen(&mut game, config.min_score.clone(), 
-        config.max_score.clone(), 3, 1., &config);
-        assert_approx_eq!(f32, 12., score);
+        let (score, all_exploited, ops_count, _) = deepen(&mut game, config.min_score.clone(), 
+        config.max_score.clone(), 3, 1, &config);
+        assert_eq!(12, score);
         assert_eq!(9, ops_count);
         assert!(all_exploited);
 
         // let res = maximize(&mut game, &config).unwrap();
-        // assert_approx_eq!(f32, 12.0, res.score);
+        // assert_eq!(12, res.score);
         // assert_eq!(14, res.ops_count);
     }
 
@@ -517,16 +2125,16 @@ mod tests {
     fn case_4() {      
         let mut arena = Arena::new();
 
-        let a = arena.new_node(0.);
-        a.append_value(-100., &mut arena);
+        let a = arena.new_node(0);
+        a.append_value(-100, &mut arena);
 
 
-        let c = arena.new_node(0.);
-        c.append_value(-100., &mut arena);
+        let c = arena.new_node(0);
+        c.append_value(-100, &mut arena);
 
-        let root = arena.new_node(0.0);
+        let root = arena.new_node(0);
         root.append(a, &mut arena);
-        root.append_value(-50.0, &mut arena);
+        root.append_value(-50, &mut arena);
         root.append(c, &mut arena);
 
         let mut game = Game {
@@ -535,7 +2143,7 @@ mod tests {
         };
         let config = Config {..Default::default() };
         let result = maximize(&mut game, &config).unwrap();
-        assert_approx_eq!(f32, -50., result.score, ulps=2);
+        assert_eq!(-50, result.score);
         assert_eq!(4, result.ops_count);
         assert_eq!(2, result.best_action.unwrap());
     }
@@ -0,0 +1,62 @@
+use rand::Rng;
+
+use crate::engine::MIN_LEVEL;
+
+/// From this level on the computer never picks a worse move on purpose.
+pub const MISTAKE_FREE_LEVEL:u8 = 10;
+/// Chance of each alternative move on the lowest level, before it is scaled down by its score gap.
+const MAX_MISTAKE_RATE:f32 = 0.4;
+/// Score gap in points which halves the chance of an alternative move, so blunders stay rarer than slips.
+const GAP_HALVING_POINTS:f32 = 8.;
+
+/// Chance of picking an alternative which is `gap` points worse than the best move on `level`.
+pub fn mistake_chance(level:u8, gap:f32) -> f32 {
+    let span = (MISTAKE_FREE_LEVEL - MIN_LEVEL) as f32;
+    let rate = MAX_MISTAKE_RATE * MISTAKE_FREE_LEVEL.saturating_sub(level) as f32 / span;
+    rate * 0.5f32.powf(gap.max(0.) / GAP_HALVING_POINTS)
+}
+
+/// Picks the index of the move to play among `scores` of the best root moves in points, ordered best first, the way
+/// a weaker player would: mostly the best one, sometimes one of the runners-up.
+pub fn choose(level:u8, scores:&[f32], rng:&mut impl Rng) -> usize {
+    let Some(best) = scores.first() else {
+        return 0;
+    };
+    let roll = rng.gen::<f32>();
+    let mut chance = 0.;
+    for (i, score) in scores.iter().enumerate().skip(1) {
+        chance += mistake_chance(level, (best - score).abs());
+        if roll < chance {
+            return i;
+        }
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn test_mistake_chance() {
+        assert!((mistake_chance(MIN_LEVEL, 0.) - MAX_MISTAKE_RATE).abs() < 1e-6);
+        assert!((mistake_chance(MIN_LEVEL, GAP_HALVING_POINTS) - MAX_MISTAKE_RATE / 2.).abs() < 1e-6);
+        assert!(mistake_chance(5, 1.) < mistake_chance(3, 1.));
+        assert_eq!(0., mistake_chance(MISTAKE_FREE_LEVEL, 0.));
+        assert_eq!(0., mistake_chance(20, 0.));
+    }
+
+    #[test]
+    fn test_choose() {
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(0, choose(1, &[], &mut rng));
+        assert!((0..100).all(|_| choose(MISTAKE_FREE_LEVEL, &[10., 9., 8.], &mut rng) == 0));
+
+        let picks: Vec<usize> = (0..1000).map(|_| choose(MIN_LEVEL, &[10., 9., -500.], &mut rng)).collect();
+        let count = |i| picks.iter().filter(|p| **p == i).count();
+        assert!(count(0) > count(1) && count(1) > 200);
+        assert_eq!(0, count(2));
+    }
+}
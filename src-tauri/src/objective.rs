@@ -0,0 +1,41 @@
+use crate::{corpus::LabeledPosition, engine::{ConnectFour, EvalWeights, EvaluatorKind}};
+
+/// Scores are mapped to winning probabilities by `sigmoid(score / SCALE)`.
+const SCALE:f32 = 8.;
+
+fn sigmoid(score:f32) -> f32 {
+    1. / (1. + (-score / SCALE).exp())
+}
+
+/// Mean squared error between the winning probability of player 1 predicted by the static evaluation
+/// and the labeled outcome (1 for a win, 0.5 for a draw, 0 for a loss). Lower is better.
+pub fn objective(corpus:&[LabeledPosition], evaluator:EvaluatorKind, weights:&EvalWeights) -> f32 {
+    if corpus.is_empty() {
+        return 0.;
+    }
+
+    let total: f32 = corpus.iter().map(|position| {
+        let mut game = ConnectFour::empty(1, evaluator.build(weights));
+        for col in position.moves.iter() {
+            game.play(*col);
+        }
+        let target = (position.value as f32 + 1.) / 2.;
+        (sigmoid(game.score()) - target).powi(2)
+    }).sum();
+    total / corpus.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_objective() {
+        let weights = EvalWeights::default();
+        let won = LabeledPosition { moves: vec![0, 6, 0, 6, 0, 6, 0], value: 1 };
+        let lost = LabeledPosition { moves: vec![0, 6, 0, 6, 0, 6, 0], value: -1 };
+        assert!(objective(&[won], EvaluatorKind::Heuristic, &weights) < 1e-3);
+        assert!(objective(&[lost], EvaluatorKind::Heuristic, &weights) > 0.99);
+        assert_eq!(objective(&[], EvaluatorKind::Heuristic, &weights), 0.);
+    }
+}
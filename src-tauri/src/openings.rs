@@ -0,0 +1,73 @@
+use crate::engine::WIDTH;
+
+/// A named pattern of the first moves of a game. Columns are 0-based and only one of the two
+/// mirrored variants is listed, the other one is recognized as well.
+pub struct Opening {
+    pub name: &'static str,
+    pub moves: &'static [usize],
+}
+
+pub const OPENINGS: &[Opening] = &[
+    Opening { name: "Center Opening", moves: &[3] },
+    Opening { name: "Off-Center Opening", moves: &[2] },
+    Opening { name: "Flank Opening", moves: &[1] },
+    Opening { name: "Edge Opening", moves: &[0] },
+    Opening { name: "Center Opening, Stacked Reply", moves: &[3, 3] },
+    Opening { name: "Center Opening, Adjacent Reply", moves: &[3, 2] },
+    Opening { name: "Center Opening, Wide Reply", moves: &[3, 1] },
+    Opening { name: "Center Opening, Edge Reply", moves: &[3, 0] },
+    Opening { name: "Off-Center Opening, Center Reply", moves: &[2, 3] },
+    Opening { name: "Off-Center Opening, Stacked Reply", moves: &[2, 2] },
+    Opening { name: "Flank Opening, Center Reply", moves: &[1, 3] },
+    Opening { name: "Center Tower", moves: &[3, 3, 3] },
+    Opening { name: "Center Tower, Capped", moves: &[3, 3, 3, 3] },
+    Opening { name: "Stacked Reply, Side Step", moves: &[3, 3, 2] },
+    Opening { name: "Adjacent Reply, Counter", moves: &[3, 2, 4] },
+    Opening { name: "Adjacent Reply, Stack", moves: &[3, 2, 3] },
+    Opening { name: "Adjacent Reply, Cover", moves: &[3, 2, 2] },
+    Opening { name: "Edge Reply, Stack", moves: &[3, 0, 3] },
+];
+
+fn mirror(col:usize) -> usize {
+    WIDTH - 1 - col
+}
+
+fn matches(opening:&Opening, history:&[usize]) -> bool {
+    opening.moves.len() <= history.len() && (
+        opening.moves.iter().zip(history).all(|(a, b)| a == b) ||
+        opening.moves.iter().zip(history).all(|(a, b)| mirror(*a) == *b)
+    )
+}
+
+/// The most specific opening the move history starts with.
+pub fn recognize(history:&[usize]) -> Option<&'static Opening> {
+    OPENINGS.iter()
+        .filter(|opening| matches(opening, history))
+        .max_by_key(|opening| opening.moves.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recognize() {
+        assert!(recognize(&[]).is_none());
+        assert_eq!("Center Opening", recognize(&[3]).unwrap().name);
+        assert_eq!("Center Opening, Adjacent Reply", recognize(&[3, 2]).unwrap().name);
+        assert_eq!("Center Opening, Adjacent Reply", recognize(&[3, 4, 0, 6]).unwrap().name);
+        assert_eq!("Adjacent Reply, Counter", recognize(&[3, 4, 2]).unwrap().name);
+        assert_eq!("Off-Center Opening, Center Reply", recognize(&[4, 3, 1]).unwrap().name);
+        assert_eq!("Edge Opening", recognize(&[6, 5]).unwrap().name);
+    }
+
+    #[test]
+    fn test_unique() {
+        for (i, a) in OPENINGS.iter().enumerate() {
+            for b in OPENINGS[i + 1..].iter() {
+                let mirrored: Vec<usize> = b.moves.iter().map(|c| mirror(*c)).collect();
+                assert!(a.moves != b.moves && a.moves != mirrored.as_slice(), "{} and {}", a.name, b.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Serialize, Deserialize};
+
+use crate::engine::{EvalWeights, WIDTH};
+use crate::minimax::DEFAULT_TEMPERATURE;
+
+/// A playing style of the computer, made of evaluation weights and how freely it strays from the best move.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub enum Personality {
+    #[default]
+    Balanced,
+    /// Builds own threats and cares less about the opponent's.
+    Aggressive,
+    /// Blocks the opponent's threats first and keeps to the center.
+    Defensive,
+    /// Spreads open twos for double threats later and varies its moves more.
+    Trappy,
+}
+
+impl Personality {
+    pub fn weights(self) -> EvalWeights {
+        let balanced = EvalWeights::default();
+        match self {
+            Personality::Balanced => balanced,
+            Personality::Aggressive => EvalWeights { open_two: 1.5, open_three: 6., blocked_three: 2., ..balanced },
+            Personality::Defensive => EvalWeights {
+                col_bonus: [0., 0.75, 1.5, 2., 1.5, 0.75, 0.],
+                open_two: 0.5,
+                open_three: 3.,
+                blocked_three: 5.,
+            },
+            Personality::Trappy => EvalWeights {
+                col_bonus: [0.5; WIDTH],
+                open_two: 2.,
+                open_three: 5.,
+                blocked_three: 2.,
+            },
+        }
+    }
+
+    /// Temperature of the computer's randomized move choice, see `ConfigBuilder::temperature`.
+    pub fn temperature(self) -> f32 {
+        match self {
+            Personality::Balanced | Personality::Defensive => DEFAULT_TEMPERATURE,
+            Personality::Aggressive => 1.5 * DEFAULT_TEMPERATURE,
+            Personality::Trappy => 2. * DEFAULT_TEMPERATURE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_personalities() {
+        let all = [Personality::Balanced, Personality::Aggressive, Personality::Defensive, Personality::Trappy];
+        for personality in all {
+            assert!(personality.weights().validate().is_ok(), "{:?}", personality);
+            assert!(personality.temperature() > 0.);
+        }
+        assert_eq!(EvalWeights::default(), Personality::default().weights());
+        assert!(Personality::Aggressive.weights().open_three > Personality::Defensive.weights().open_three);
+        assert!(Personality::Defensive.weights().blocked_three > Personality::Aggressive.weights().blocked_three);
+    }
+}
@@ -1,11 +1,35 @@
-use std::{borrow::BorrowMut, collections::VecDeque};
+use std::{borrow::BorrowMut, collections::VecDeque, fmt, iter, mem::{self, size_of}, sync::{atomic::Ordering, Arc, Mutex}, thread, time::Instant};
 
 use array2d::Array2D;
-use serde::{Serialize, Deserialize};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Serialize, Serializer, Deserialize};
+use serde_json::{json, Value};
 use tauri::Window;
-use crate::engine::{self, ActionEvaluation, Eval, HEIGHT, TOTAL_FIELDS, WIDTH};
+use tracing::{debug, info, trace};
+use crate::clock::{Clock, ClockSettings};
+use crate::engine::{self, ActionEvaluation, BoardError, Eval, EvalWeights, EvaluatorKind, MoveKind, MoveQuality, PreparedSearch, Rules, SearchOptions, Variant, HEIGHT, TOTAL_FIELDS, WIDTH};
+use crate::minimax::{CancelToken, ConfigSnapshot, Progress, ProgressPhase, SearchHandle, SearchStats, SearchTree, StateEvaluation, Termination};
+use crate::memory::{vec_bytes, MemoryUsage};
+use crate::codes;
+use crate::mistakes;
+use crate::openings;
+use crate::personality::Personality;
+use crate::rubber_band::Policy;
+use crate::savegame::{self, LoadError, SavedGame};
+
+/// Number of alternative moves reported in the analysis after each computer move.
+const ANALYSIS_MOVES:usize = 3;
+/// Shortest think time the computer is given on a clock, so it always finds a move.
+const MIN_CLOCK_THINK_MILLIS:u128 = 10;
+/// Level of the searches judging the moves of the human.
+const ANNOTATION_LEVEL:u8 = 3;
+/// Level of the background evaluation while the human thinks.
+const LIVE_EVAL_LEVEL:u8 = 5;
+/// Level of the quick evaluation of a column the human points at, see `Game::start_preview`.
+const PREVIEW_LEVEL:u8 = 3;
 
 #[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
 #[repr(i8)]
 pub enum CellState {
     Blank=0,
@@ -13,13 +37,27 @@ pub enum CellState {
     P2=-1,
 }
 
+impl CellState {
+    /// The other player, `Blank` stays `Blank`.
+    pub fn opponent(self) -> CellState {
+        match self {
+            CellState::P1 => CellState::P2,
+            CellState::P2 => CellState::P1,
+            CellState::Blank => CellState::Blank,
+        }
+    }
+}
+
 #[derive(serde::Serialize, Clone)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
 pub enum Update {
+    /// `drop` is set when a piece was dropped into the cell, as opposed to a cell being cleared or marked as winning.
     Cell {
         row: u8,
         col: u8,
         state: i8,
         winning: bool,
+        drop: Option<PieceDrop>,
     },
     State {
         state: i8,
@@ -27,10 +65,134 @@ pub enum Update {
     },
     Balance {
         value: f32,
-    }
+    },
+    Thinking {
+        phase: ThinkingPhase,
+    },
+    Analysis {
+        moves: Vec<AnalysisMove>,
+    },
+    Progress {
+        depth: u8,
+        col: u8,
+        score: f32,
+        #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+        nodes: u64,
+    },
+    Move {
+        row: u8,
+        col: u8,
+        player: i8,
+        kind: MoveKind,
+    },
+    /// The computer's search ended, `reason` is one of the `codes::REASON_*` codes.
+    Searched {
+        depth: u8,
+        #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+        elapsed_millis: u64,
+        #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+        nodes: u64,
+        reason: &'static str,
+    },
+    /// Time left of both players, `running` is the player whose time runs.
+    Clock {
+        #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+        p1_millis: u64,
+        #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+        p2_millis: u64,
+        running: Option<i8>,
+    },
+    /// Emitted periodically while the computer thinks. `depth` is the one being searched, `col` the best move of the
+    /// depth before and `nodes` counts the nodes of the completed depths.
+    SearchProgress {
+        depth: u8,
+        #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+        nodes: u64,
+        col: Option<u8>,
+        #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+        elapsed_millis: u64,
+    },
+    /// The cells which changed at once, e.g. on a reset or when a game was loaded, instead of an `Update::Cell` each.
+    Board {
+        cells: Vec<CellChange>,
+    },
+    /// The cells of the latest move of each player, to mark them on the board.
+    LastMove {
+        p1: Option<MovedCell>,
+        p2: Option<MovedCell>,
+    },
+    /// Judgement of a move of the human, `swing` are the points it lost from the player's perspective.
+    Annotation {
+        col: u8,
+        player: i8,
+        quality: MoveQuality,
+        swing: f32,
+    },
 } 
 
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct MovedCell {
+    pub row: u8,
+    pub col: u8,
+}
+
+/// How a piece fell into its cell, so the frontend can animate it and time its sound.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct PieceDrop {
+    /// The column the piece was dropped into.
+    pub col: u8,
+    /// The row it landed in, row 0 is the bottom row.
+    pub row: u8,
+    /// Number of rows it fell through, counted from above the top row.
+    pub distance: u8,
+}
+
+impl PieceDrop {
+    fn new(row:usize, col:usize) -> PieceDrop {
+        PieceDrop { col: col as u8, row: row as u8, distance: (HEIGHT - row) as u8 }
+    }
+}
+
+/// A cell of `Update::Board`, with the fields of `Update::Cell` but the drop.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct CellChange {
+    pub row: u8,
+    pub col: u8,
+    pub state: i8,
+    pub winning: bool,
+}
+
+/// The cells of `after` which differ from `before`.
+fn board_diff(before:&Array2D<Cell>, after:&Array2D<Cell>) -> Vec<CellChange> {
+    before.elements_row_major_iter().zip(after.elements_row_major_iter())
+        .filter(|(old, new)| old.state != new.state || old.winning != new.winning)
+        .map(|(_, new)| CellChange { row: new.row as u8, col: new.col as u8, state: new.state as i8, winning: new.winning })
+        .collect()
+}
+
+#[derive(serde::Serialize, Clone)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct AnalysisMove {
+    col: u8,
+    score: f32,
+}
+
+/// Sub-states reported while the game is `Calculating`. There is no phase for a book lookup, as the engine plays
+/// no opening book: `openings` only names the first moves of a game, every move of the computer is searched.
+#[derive(serde::Serialize, Clone)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub enum ThinkingPhase {
+    Searching {
+        depth: u8,
+    },
+    Finalizing,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
 pub struct Cell {
     row:usize,
     col:usize,
@@ -38,24 +200,39 @@ pub struct Cell {
     winning:bool,
 }
 
-fn emit_update(event:Update, window:&Window) -> Result<(), String> {
+pub fn emit_update(event:Update, window:&Window) -> Result<(), String> {
     let s = match event {
-        Update::Balance { value: _ } => "updateBalance".to_owned(),
-        Update::Cell { row, col, state: _, winning: _ } => format!("updateCell-{}-{}", row, col),
-        Update::State { state: _, winner:_ } => "updateState".to_owned()
+        Update::Balance { value: _ } => codes::EVENT_BALANCE.to_owned(),
+        Update::Cell { row, col, state: _, winning: _, drop: _ } => format!("{}-{}-{}", codes::EVENT_CELL, row, col),
+        Update::State { state: _, winner:_ } => codes::EVENT_STATE.to_owned(),
+        Update::Thinking { phase: _ } => codes::EVENT_THINKING.to_owned(),
+        Update::Analysis { moves: _ } => codes::EVENT_ANALYSIS.to_owned(),
+        Update::Progress { depth: _, col: _, score: _, nodes: _ } => codes::EVENT_PROGRESS.to_owned(),
+        Update::Move { row: _, col: _, player: _, kind: _ } => codes::EVENT_MOVE.to_owned(),
+        Update::Searched { depth: _, elapsed_millis: _, nodes: _, reason: _ } => codes::EVENT_SEARCHED.to_owned(),
+        Update::Clock { p1_millis: _, p2_millis: _, running: _ } => codes::EVENT_CLOCK.to_owned(),
+        Update::Annotation { col: _, player: _, quality: _, swing: _ } => codes::EVENT_ANNOTATION.to_owned(),
+        Update::LastMove { p1: _, p2: _ } => codes::EVENT_LAST_MOVE.to_owned(),
+        Update::Board { cells: _ } => codes::EVENT_BOARD.to_owned(),
+        Update::SearchProgress { depth: _, nodes: _, col: _, elapsed_millis: _ } => codes::EVENT_SEARCH_PROGRESS.to_owned(),
     };
     window.emit(&s, event).map_err(|e| e.to_string())
 }
 
 impl Cell {
     fn emit_update(&self, window:Option<&Window>) {
-        println!("update cell");
+        self.emit_drop(None, window);
+    }
+
+    fn emit_drop(&self, drop:Option<PieceDrop>, window:Option<&Window>) {
+        trace!(row = self.row, col = self.col, "update cell");
         window.map(|w| emit_update( 
             Update::Cell { 
                 row: self.row as u8,
                 col: self.col as u8,
                 state: self.state as i8,
-                winning: self.winning 
+                winning: self.winning,
+                drop,
             },
             w
         ));
@@ -67,6 +244,7 @@ impl Cell {
         self.emit_update(window);
     }
 
+    /// Changes the state of the cell, a piece put into it is reported as dropped into its column.
     fn set_state(&mut self, state:CellState, window:Option<&Window>) -> Result<bool, String> {
         if state == self.state {
             return Ok(false);
@@ -98,17 +276,516 @@ impl Cell {
             }
         }?;
         
-        self.emit_update(window);
+        let drop = match state {
+            CellState::Blank => None,
+            CellState::P1 | CellState::P2 => Some(PieceDrop::new(self.row, self.col)),
+        };
+        self.emit_drop(drop, window);
         Ok(result)
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
 pub enum GameState {
     Blank,
     Running,
+    /// Won by a player, on the board, by resignation or on time.
     Finished,
     Calculating,
+    /// The board filled up without a winner.
+    Draw,
+}
+
+impl GameState {
+    /// Whether the game ended, with a winner or as a draw.
+    pub fn is_over(self) -> bool {
+        matches!(self, GameState::Finished | GameState::Draw)
+    }
+}
+
+/// Why a command on a game was rejected. Sent to the frontend as its `kind`, its code from `codes`, a `message` in
+/// English and the `params` of the message, so it can be translated.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameError {
+    ColumnFull(usize),
+    /// The column does not exist on the board.
+    InvalidColumn(usize),
+    /// The other player is to move.
+    OutOfTurn,
+    GameFinished,
+    /// No move was played yet.
+    NotStarted,
+    /// The computer is searching its move.
+    EngineBusy,
+    /// The player's time ran out with this move.
+    TimeUp,
+    /// The computer is asked to move in a game of two humans.
+    Hotseat,
+    /// A takeback was asked for, but the last move is not a reply of the computer.
+    NoComputerReply,
+    /// The game was reset or ended while the computer searched, its move is discarded.
+    SearchDiscarded,
+    /// The search was cancelled and the human's last move taken back, see `CancelMode::TakeBack`.
+    Cancelled,
+    /// Moves cannot be played while the position is edited.
+    Editing,
+    UnknownGame(u32),
+    /// The engine rejected the board or the move.
+    Board(BoardError),
+    /// Any other reason, e.g. invalid settings. The message is passed as `detail` parameter.
+    Other(String),
+}
+
+impl GameError {
+    /// Name of the variant in camel case, which the frontend can branch on.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GameError::ColumnFull(_) => "columnFull",
+            GameError::InvalidColumn(_) => "invalidColumn",
+            GameError::OutOfTurn => "outOfTurn",
+            GameError::GameFinished => "gameFinished",
+            GameError::NotStarted => "notStarted",
+            GameError::EngineBusy => "engineBusy",
+            GameError::TimeUp => "timeUp",
+            GameError::Hotseat => "hotseat",
+            GameError::NoComputerReply => "noComputerReply",
+            GameError::SearchDiscarded => "searchDiscarded",
+            GameError::Cancelled => "cancelled",
+            GameError::Editing => "editing",
+            GameError::UnknownGame(_) => "unknownGame",
+            GameError::Board(_) => "board",
+            GameError::Other(_) => "other",
+        }
+    }
+
+    /// The values inserted into the message, by name.
+    pub fn params(&self) -> Value {
+        match self {
+            GameError::ColumnFull(col) | GameError::InvalidColumn(col) => json!({ "col": col }),
+            GameError::UnknownGame(game) => json!({ "game": game }),
+            GameError::Board(e) => match *e {
+                BoardError::Dimensions { rows, cols } => json!({ "rows": rows, "cols": cols, "height": HEIGHT, "width": WIDTH }),
+                BoardError::CellValue { row, col, value } => json!({ "row": row, "col": col, "value": value }),
+                BoardError::FloatingPiece { row, col } => json!({ "row": row, "col": col }),
+                BoardError::PieceCount { p1, p2 } => json!({ "p1": p1, "p2": p2 }),
+                BoardError::Player(player) | BoardError::NotPlayersTurn(player) => json!({ "player": player }),
+                BoardError::AlreadyDecided(winner) => json!({ "winner": winner }),
+                BoardError::ColumnFull(col) | BoardError::InvalidColumn(col) => json!({ "col": col }),
+            },
+            GameError::Other(detail) => json!({ "detail": detail }),
+            _ => json!({}),
+        }
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::ColumnFull(col) => write!(f, "column {} is full", col),
+            GameError::InvalidColumn(col) => write!(f, "column {} does not exist", col),
+            GameError::OutOfTurn => write!(f, "not your turn"),
+            GameError::GameFinished => write!(f, "the game is already finished"),
+            GameError::NotStarted => write!(f, "not started"),
+            GameError::EngineBusy => write!(f, "calculating"),
+            GameError::TimeUp => write!(f, "time is up"),
+            GameError::Hotseat => write!(f, "the computer does not play in hotseat games"),
+            GameError::NoComputerReply => write!(f, "the last move is not a reply of the computer"),
+            GameError::SearchDiscarded => write!(f, "the game changed during the search"),
+            GameError::Cancelled => write!(f, "the search was cancelled"),
+            GameError::Editing => write!(f, "the position is being edited"),
+            GameError::UnknownGame(id) => write!(f, "unknown game {}", id),
+            GameError::Board(e) => write!(f, "{}", e),
+            GameError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// How a `GameError` is sent to the frontend.
+#[derive(Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS), ts(rename = "GameError"))]
+pub struct ErrorPayload {
+    kind: &'static str,
+    code: &'static str,
+    message: String,
+    #[cfg_attr(feature = "ts-bindings", ts(type = "Record<string, unknown>"))]
+    params: Value,
+}
+
+impl Serialize for GameError {
+    fn serialize<S:Serializer>(&self, serializer:S) -> Result<S::Ok, S::Error> {
+        ErrorPayload {
+            kind: self.kind(),
+            code: codes::game_error(self),
+            message: self.to_string(),
+            params: self.params(),
+        }.serialize(serializer)
+    }
+}
+
+impl std::error::Error for GameError {}
+
+impl From<BoardError> for GameError {
+    fn from(e:BoardError) -> GameError {
+        match e {
+            BoardError::ColumnFull(col) if col >= WIDTH => GameError::InvalidColumn(col),
+            BoardError::ColumnFull(col) => GameError::ColumnFull(col),
+            BoardError::NotPlayersTurn(_) => GameError::OutOfTurn,
+            BoardError::AlreadyDecided(_) => GameError::GameFinished,
+            e => GameError::Board(e),
+        }
+    }
+}
+
+impl From<String> for GameError {
+    fn from(e:String) -> GameError {
+        GameError::Other(e)
+    }
+}
+
+impl From<&str> for GameError {
+    fn from(e:&str) -> GameError {
+        GameError::Other(e.to_owned())
+    }
+}
+
+impl From<LoadError> for GameError {
+    fn from(e:LoadError) -> GameError {
+        GameError::Other(e.to_string())
+    }
+}
+
+impl From<GameError> for String {
+    fn from(e:GameError) -> String {
+        e.to_string()
+    }
+}
+
+/// What the computer does when its search is cancelled.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub enum CancelMode {
+    /// Plays the best move of the depths searched so far.
+    #[default]
+    PlayBest,
+    /// Takes back the human's last move, so it is the human's turn again.
+    TakeBack,
+}
+
+/// A computer move to be searched on another thread, so the game need not wait while the computer thinks.
+/// See `Game::start_auto_play`.
+pub struct PendingMove {
+    player: CellState,
+    prepared: PreparedSearch,
+    /// Number of the search in its game.
+    search: u64,
+}
+
+/// What the computer's running search got to, updated by its callbacks and reported by `Game::tick`.
+#[derive(Clone, Copy, Default)]
+struct SearchStatus {
+    depth: u8,
+    nodes: u64,
+    col: Option<u8>,
+}
+
+/// The result of a `PendingMove`, to be applied with `Game::finish_auto_play`.
+pub struct SearchedMove {
+    player: CellState,
+    search: u64,
+    result: Result<Option<StateEvaluation>, String>,
+}
+
+impl PendingMove {
+    /// Searches the move on the calling thread, which it blocks until the search has ended.
+    pub fn run(self) -> SearchedMove {
+        SearchedMove { player: self.player, search: self.search, result: self.prepared.run() }
+    }
+}
+
+/// Where a piece dropped into a column would land and how the position after it looks.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct ColumnPreview {
+    pub row: u8,
+    pub col: u8,
+    /// Evaluation after the move in points, from the perspective of the player who would make it.
+    pub score: f32,
+    /// Whether the move would end the game.
+    pub finished: bool,
+}
+
+/// A search of `Game::judge`, which can be stopped if its move is rejected.
+struct JudgeSearch {
+    handle: SearchHandle,
+    cancel: CancelToken,
+}
+
+impl JudgeSearch {
+    /// Score for `player` to move, from the perspective of `player`.
+    fn wait(self, player:CellState) -> Result<f32, String> {
+        let res = self.handle.wait()?.ok_or("no evaluation of the position")?;
+        Ok(engine::to_points(res.score) * player as i8 as f32)
+    }
+}
+
+/// The evaluation after a judged move.
+enum After {
+    Search(JudgeSearch),
+    Won,
+    Lost,
+    Draw,
+}
+
+/// A move being judged on other threads, see `Game::play_annotated`.
+pub struct PendingJudgement {
+    player: CellState,
+    before: JudgeSearch,
+    after: After,
+}
+
+impl PendingJudgement {
+    /// Blocks until the evaluations before and after the move are known, returns the quality of the move and
+    /// by how many points it changed the evaluation.
+    pub fn wait(self) -> Result<(MoveQuality, f32), String> {
+        let before = self.before.wait(self.player)?;
+        let after = match self.after {
+            After::Search(search) => -search.wait(self.player.opponent())?,
+            After::Won => before.max(0.),
+            After::Lost => -engine::WIN_POINTS,
+            After::Draw => 0.,
+        };
+        let swing = before - after;
+        Ok((MoveQuality::from_swing(swing), swing))
+    }
+}
+
+/// A preview being evaluated on another thread, see `Game::start_preview`.
+pub struct PendingPreview {
+    player: CellState,
+    preview: ColumnPreview,
+    /// The search for the opponent's reply, `None` if the move ends the game.
+    handle: Option<SearchHandle>,
+    cancel: CancelToken,
+}
+
+impl PendingPreview {
+    /// Blocks until the evaluation has ended, fails if it was cancelled.
+    pub fn wait(self) -> Result<ColumnPreview, GameError> {
+        let Some(handle) = self.handle else {
+            return Ok(self.preview);
+        };
+        let res = handle.wait()?;
+        if self.cancel.load(Ordering::Relaxed) {
+            return Err(GameError::Cancelled);
+        }
+        let res = res.ok_or("no reply to the move")?;
+        Ok(ColumnPreview { score: engine::to_points(res.score) * self.player as i8 as f32, ..self.preview })
+    }
+}
+
+/// Everything the frontend shows of a game, to resynchronize it in one go.
+#[derive(Serialize, Clone)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct BoardSnapshot {
+    /// Cells in row major order, row 0 is the bottom row.
+    pub cells: Vec<Cell>,
+    pub col_heights: [usize; WIDTH],
+    pub state: GameState,
+    /// The player who made the last move.
+    pub current_player: CellState,
+    pub move_history: Vec<usize>,
+    pub winner: Option<i8>,
+    /// The latest move of player 1 and 2.
+    pub last_moves: [Option<MovedCell>; 2],
+}
+
+/// The state of a game apart from its board, see `Game::status`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct GameStatus {
+    pub state: GameState,
+    /// The player whose turn it is, `None` once the game is over.
+    pub to_move: Option<CellState>,
+    pub level: u8,
+    pub rules: Rules,
+    pub hotseat: bool,
+    pub moves: usize,
+    /// Whether the computer is searching its move.
+    pub calculating: bool,
+}
+
+/// A move of the game, as listed in the move history.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct MoveRecord {
+    /// Counting from 1.
+    pub number: usize,
+    pub col: usize,
+    pub player: CellState,
+    /// Score of the computer's search for its moves from the perspective of player 1, `None` for other moves.
+    pub score: Option<f32>,
+    /// Time taken for the move, from the previous move or the start of the game.
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub millis: u64,
+}
+
+/// A move in the history of a `Game`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct PlayedMove {
+    col: usize,
+    /// Wall-clock time from the previous move, or the start of the game, to this one.
+    millis: u64,
+}
+
+/// A piece placed before the first move of a game, see `Game::set_handicap`.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct HandicapCell {
+    pub row: usize,
+    pub col: usize,
+    pub player: CellState,
+}
+
+/// A position in a compact text form: the rows from top to bottom with `x` for player 1, `o` for player 2 and `.` for
+/// an empty cell, separated by `/`, then the side to move and the number of moves played, e.g.
+/// `......./......./......./......./...o.../...x... x 2`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Position {
+    /// Cell states in row major order, row 0 is the bottom row.
+    pub board: Vec<i8>,
+    pub to_move: CellState,
+    pub moves: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = |value:i8| match value {
+            1 => 'x',
+            -1 => 'o',
+            _ => '.',
+        };
+        let rows: Vec<String> = self.board.chunks(WIDTH).rev()
+            .map(|row| row.iter().map(|v| symbol(*v)).collect())
+            .collect();
+        write!(f, "{} {} {}", rows.join("/"), symbol(self.to_move as i8), self.moves)
+    }
+}
+
+impl Position {
+    pub fn parse(s:&str) -> Result<Position, String> {
+        let [rows, to_move, moves] = s.split_whitespace().collect::<Vec<&str>>()[..] else {
+            return Err("expected the rows, the side to move and the number of moves".into());
+        };
+        let rows: Vec<&str> = rows.split('/').collect();
+        if rows.len() != HEIGHT || rows.iter().any(|r| r.chars().count() != WIDTH) {
+            return Err(format!("expected {} rows of {} cells", HEIGHT, WIDTH));
+        }
+        let board = rows.iter().rev().flat_map(|r| r.chars()).map(|c| match c {
+            'x' => Ok(1),
+            'o' => Ok(-1),
+            '.' => Ok(0),
+            c => Err(format!("unknown cell '{}'", c)),
+        }).collect::<Result<Vec<i8>, String>>()?;
+        let to_move = match to_move {
+            "x" => CellState::P1,
+            "o" => CellState::P2,
+            s => return Err(format!("unknown side to move '{}'", s)),
+        };
+        let moves = moves.parse().map_err(|_| format!("invalid number of moves '{}'", moves))?;
+
+        let position = Position { board, to_move, moves };
+        position.validate()?;
+        Ok(position)
+    }
+
+    /// Checks that the number of moves and the side to move fit the pieces on the board.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.to_move == CellState::Blank {
+            return Err("no side to move".into());
+        }
+        let pieces = |player:i8| self.board.iter().filter(|v| **v == player).count();
+        let (p1, p2) = (pieces(1), pieces(-1));
+        if p1 + p2 != self.moves {
+            return Err(format!("{} moves were played, but there are {} pieces", self.moves, p1 + p2));
+        }
+        let last_mover = match p1 as i64 - p2 as i64 {
+            0 => -(self.to_move as i8),
+            1 => 1,
+            -1 => -1,
+            _ => return Err(format!("impossible piece count: {} for player 1, {} for player 2", p1, p2)),
+        };
+        if self.moves > 0 && last_mover == self.to_move as i8 {
+            return Err("the side to move made the last move".into());
+        }
+        Ok(())
+    }
+
+    /// An order of moves leading to the position in a game played by `rules`. It is searched backwards
+    /// by taking back the top pieces, as only the last move may have completed a row.
+    pub fn move_order(&self, rules:Rules) -> Result<Vec<usize>, String> {
+        let mut heights = col_heights(&self.board)?;
+        let mut board = self.board.clone();
+        let mut order = Vec::with_capacity(self.moves);
+        match take_back(&mut board, &mut heights, -(self.to_move as i8), rules, &mut order) {
+            true => Ok(order),
+            false => Err("position cannot occur in a game".into()),
+        }
+    }
+}
+
+/// The number of pieces in each column of a row major board, which fails for pieces resting on an empty cell.
+fn col_heights(board:&[i8]) -> Result<[usize; WIDTH], String> {
+    let mut heights = [0; WIDTH];
+    for (col, height) in heights.iter_mut().enumerate() {
+        *height = (0..HEIGHT).take_while(|row| board[row * WIDTH + col] != 0).count();
+        if (*height..HEIGHT).any(|row| board[row * WIDTH + col] != 0) {
+            return Err(format!("piece in column {} is not supported", col + 1));
+        }
+    }
+    Ok(heights)
+}
+
+/// Takes back a move of `player` and the ones before, pushing the columns in the order they were played.
+fn take_back(board:&mut [i8], heights:&mut [usize; WIDTH], player:i8, rules:Rules, order:&mut Vec<usize>) -> bool {
+    if heights.iter().all(|h| *h == 0) {
+        return true;
+    }
+    for col in 0..WIDTH {
+        let Some(row) = heights[col].checked_sub(1) else {
+            continue;
+        };
+        if board[row * WIDTH + col] != player {
+            continue;
+        }
+        board[row * WIDTH + col] = 0;
+        heights[col] = row;
+        if !has_row(board, rules) && take_back(board, heights, -player, rules, order) {
+            order.push(col);
+            return true;
+        }
+        board[row * WIDTH + col] = player;
+        heights[col] = row + 1;
+    }
+    false
+}
+
+fn has_row(board:&[i8], rules:Rules) -> bool {
+    let len = rules.win_length;
+    let at = |row:isize, col:isize| {
+        let col = match rules.variant {
+            Variant::Cylinder => col.rem_euclid(WIDTH as isize),
+            Variant::Standard | Variant::Misere => col,
+        };
+        match (0..HEIGHT as isize).contains(&row) && (0..WIDTH as isize).contains(&col) {
+            true => board[row as usize * WIDTH + col as usize],
+            false => 0,
+        }
+    };
+    (0..HEIGHT as isize).flat_map(|r| (0..WIDTH as isize).map(move |c| (r, c)))
+        .filter(|(r, c)| at(*r, *c) != 0)
+        .any(|(r, c)| [(0, 1), (1, 0), (1, 1), (1, -1)].iter()
+            .any(|(dr, dc)| (1..len as isize).all(|i| at(r + i * dr, c + i * dc) == at(r, c))))
 }
 
 pub struct Game {
@@ -117,7 +794,56 @@ pub struct Game {
     col_heights: [usize; engine::WIDTH],
     current_player: CellState,
     level:u8,
-    move_history: VecDeque<usize>,
+    evaluator: EvaluatorKind,
+    weights: EvalWeights,
+    move_history: VecDeque<PlayedMove>,
+    /// When the last move was played, or the game started.
+    last_move_at: Instant,
+    cancel: CancelToken,
+    rubber_band: bool,
+    /// Whether the computer picks a weaker move now and then on low levels, see `mistakes::choose`.
+    mistakes: bool,
+    /// The playing style the weights come from, `None` for weights set directly.
+    personality: Option<Personality>,
+    seed: Option<u64>,
+    last_stats: Option<SearchStats>,
+    /// Settings of the computer's last search, to reproduce it.
+    last_config: Option<ConfigSnapshot>,
+    /// Whether the computer's searches record their tree for debugging.
+    record_tree: bool,
+    last_tree: Option<SearchTree>,
+    /// Column the computer must not play, used for drills.
+    banned_column: Option<usize>,
+    /// Score of each computer move's search from the perspective of player 1.
+    eval_history: Vec<f32>,
+    /// Search score of each move in `move_history`, `None` if the computer did not search it.
+    move_scores: Vec<Option<f32>>,
+    /// The player who gave up the game or ran out of time, if it did not end on the board.
+    resigned: Option<CellState>,
+    rules: Rules,
+    /// Both players are human, the computer does not move.
+    hotseat: bool,
+    /// Time control of the next games, `None` for untimed ones.
+    clock_settings: Option<ClockSettings>,
+    clock: Option<Clock>,
+    /// Whether the moves played with `play_annotated` are judged.
+    annotate: bool,
+    /// Whether `start_live_eval` evaluates the position in the background.
+    live_eval: bool,
+    /// Suspends the background evaluation while the window is minimized or on battery, see `PausePolicy`.
+    live_eval_paused: bool,
+    /// Aborts the running background evaluation.
+    live_eval_cancel: Option<CancelToken>,
+    /// Aborts the evaluation of the last preview.
+    preview_cancel: Option<CancelToken>,
+    cancel_mode: CancelMode,
+    /// Counts the computer's searches, a result is only applied if no other search started and the game was not
+    /// reset in the meantime.
+    searches: u64,
+    /// Pieces placed before the first move, they stay on the board when every move is taken back.
+    handicap: Vec<HandicapCell>,
+    /// Start and status of the computer's latest search.
+    search_status: Option<(Instant, Arc<Mutex<SearchStatus>>)>,
 }
 
 impl Game {
@@ -135,7 +861,36 @@ impl Game {
             col_heights: [0; engine::WIDTH],
             current_player: CellState::P1,
             level: level,
+            evaluator: EvaluatorKind::default(),
+            weights: EvalWeights::default(),
             move_history: VecDeque::with_capacity(TOTAL_FIELDS),
+            last_move_at: Instant::now(),
+            cancel: CancelToken::default(),
+            rubber_band: false,
+            mistakes: false,
+            personality: None,
+            seed: None,
+            last_stats: None,
+            last_config: None,
+            record_tree: false,
+            last_tree: None,
+            banned_column: None,
+            eval_history: Vec::new(),
+            move_scores: Vec::with_capacity(TOTAL_FIELDS),
+            resigned: None,
+            rules: Rules::default(),
+            hotseat: false,
+            clock_settings: None,
+            clock: None,
+            annotate: false,
+            live_eval: false,
+            live_eval_paused: false,
+            live_eval_cancel: None,
+            preview_cancel: None,
+            cancel_mode: CancelMode::default(),
+            searches: 0,
+            handicap: Vec::new(),
+            search_status: None,
         }
     }
 
@@ -154,46 +909,79 @@ impl Game {
         )
     }
 
-    fn evaluate(&self) -> ActionEvaluation {
-        self.move_history.back()
-                .map(|col| engine::evaluate_action(Some(self.map_values()), self.current_player as i8,*col))
-                .unwrap_or(ActionEvaluation {
-                    eval: Eval {
-                        score: 0.,
-                        finished: false,
-                        winner: None
-                    },
-                    winning_cells: Option::None
-                })
+    /// Evaluates the last move. `current_player` made that move, so the opponent is the one to move next.
+    fn evaluate(&self) -> Result<ActionEvaluation, String> {
+        match self.move_history.back() {
+            Some(played) => engine::evaluate_action(
+                Some(self.map_values()),
+                -(self.current_player as i8),
+                played.col,
+                self.evaluator.build(&self.weights),
+                self.rules,
+                self.handicap_balance()
+            ).map_err(|e| e.into()),
+            None => Ok(ActionEvaluation {
+                eval: Eval {
+                    score: 0,
+                    finished: false,
+                    winner: None
+                },
+                winning_cells: Option::None
+            })
+        }
     }
 
-    pub fn play_col(&mut self, col:usize, player:CellState, window:Option<&Window>) -> Result<GameState, String> {
+    pub fn play_col(&mut self, col:usize, player:CellState, window:Option<&Window>) -> Result<GameState, GameError> {
         // println!("{:?}", col);
         match self.state {
             GameState::Blank => {
                 self.state = GameState::Running;
-                Ok::<(),String>(())
+                Ok(())
             },
-            GameState::Finished => Err("Already finished".into()),
-            GameState::Calculating => Err("calculating".into()),
+            GameState::Finished | GameState::Draw => Err(GameError::GameFinished),
+            GameState::Calculating => Err(GameError::EngineBusy),
             GameState::Running => Ok(())
         }?;
+        self.stop_live_eval();
+        if !self.move_history.is_empty() && player == self.current_player {
+            return Err(GameError::OutOfTurn);
+        }
+        let kind = engine::classify_move(self.map_values(), player as i8, col, self.rules, self.handicap_balance()).inspect_err(|e| {
+            debug!(code = codes::board_error(e), col, "move rejected");
+        })?;
         self.current_player = player;        
         let row = self.col_heights[col];
 
         if row >= HEIGHT {
-            return Err("column already full".into());
+            return Err(GameError::ColumnFull(col));
+        }
+        if let Some(clock) = self.clock.as_mut() {
+            if !clock.complete_move(player) {
+                self.lose_on_time(player, window);
+                return Err(GameError::TimeUp);
+            }
         }
 
         self.col_heights[col] = row + 1;
-        self.move_history.push_back(col);
+        self.move_history.push_back(PlayedMove { col, millis: self.last_move_at.elapsed().as_millis() as u64 });
+        self.last_move_at = Instant::now();
+        self.move_scores.push(None);
 
         match self.cells[(row, col)].set_state(player, window)? {
             true => {
-                let result = self.evaluate();
+                window.map(|w| emit_update(Update::Move {
+                    row: row as u8,
+                    col: col as u8,
+                    player: player as i8,
+                    kind
+                }, w));
+                let result = self.evaluate()?;
                 
                 if result.eval.finished {
-                    self.state = GameState::Finished;
+                    self.state = match result.eval.winner {
+                        Some(_) => GameState::Finished,
+                        None => GameState::Draw,
+                    };
                 }
                 
                 window.map(|w| emit_update(Update::State { 
@@ -209,6 +997,11 @@ impl Game {
                     }
                 });
 
+                if let (Some(clock), GameState::Running) = (self.clock.as_mut(), self.state) {
+                    clock.start(player.opponent());
+                }
+                self.emit_last_moves(window);
+                self.emit_clock(window);
                 Ok(self.state)
             }
             false => {
@@ -217,13 +1010,193 @@ impl Game {
         }
     }
 
-    pub fn auto_play(&mut self, player:CellState, window:Option<&Window>) -> Result<(), String> {
+    /// Plays a move like `play_col` and, if annotations are enabled, judges it by comparing the evaluation
+    /// before and after it. The judgement is searched on other threads and emitted once it is known.
+    pub fn play_annotated(&mut self, col:usize, player:CellState, window:Option<&Window>) -> Result<GameState, GameError> {
+        let (state, judgement) = self.annotate(col, player, window)?;
+        if let (Some(pending), Some(w)) = (judgement, window) {
+            let w = w.clone();
+            thread::spawn(move || match pending.wait() {
+                Ok((quality, swing)) => {
+                    let _ = emit_update(Update::Annotation { col: col as u8, player: player as i8, quality, swing }, &w);
+                },
+                Err(e) => debug!(error = e, "move not judged"),
+            });
+        }
+        Ok(state)
+    }
+
+    fn annotate(&mut self, col:usize, player:CellState, window:Option<&Window>) -> Result<(GameState, Option<PendingJudgement>), GameError> {
+        if !self.annotate {
+            return Ok((self.play_col(col, player, window)?, None));
+        }
+        let before = self.judge(player);
+        let state = match self.play_col(col, player, window) {
+            Ok(state) => state,
+            Err(e) => {
+                if let Ok(before) = before {
+                    before.cancel.store(true, Ordering::Relaxed);
+                }
+                return Err(e);
+            },
+        };
+        let before = match before {
+            Ok(before) => before,
+            Err(e) => {
+                debug!(error = e, "move not judged");
+                return Ok((state, None));
+            },
+        };
+        let after = match state {
+            GameState::Finished => match self.winner() {
+                Some(winner) if winner == player as i8 => After::Won,
+                _ => After::Lost,
+            },
+            GameState::Draw => After::Draw,
+            _ => match self.judge(player.opponent()) {
+                Ok(search) => After::Search(search),
+                Err(e) => {
+                    before.cancel.store(true, Ordering::Relaxed);
+                    debug!(error = e, "move not judged");
+                    return Ok((state, None));
+                },
+            },
+        };
+        Ok((state, Some(PendingJudgement { player, before, after })))
+    }
+
+    /// Starts a short search for `player` to move, see `PendingJudgement`.
+    fn judge(&self, player:CellState) -> Result<JudgeSearch, String> {
+        let mut options = SearchOptions::new(ANNOTATION_LEVEL, self.evaluator.build(&self.weights));
+        options.rules = self.rules;
+        options.handicap = self.handicap_balance();
+        let cancel = CancelToken::default();
+        options.cancel = Some(cancel.clone());
+        let handle = engine::evaluate_state_async(Some(self.map_values()), player as i8, options)?;
+        Ok(JudgeSearch { handle, cancel })
+    }
+
+    /// Evaluates the position in the background, if enabled, and emits the result as `Update::Balance` unless
+    /// another move was played or taken back in the meantime.
+    pub fn start_live_eval(&mut self, window:Option<&Window>) {
+        if let Some(w) = window {
+            let w = w.clone();
+            self.run_live_eval(move |value| {
+                let _ = emit_update(Update::Balance { value }, &w);
+            });
+        }
+    }
+
+    fn run_live_eval(&mut self, on_result:impl FnOnce(f32) + Send + 'static) {
+        self.stop_live_eval();
+        if !self.live_eval || self.live_eval_paused || self.state != GameState::Running {
+            return;
+        }
+        let mut options = SearchOptions::new(LIVE_EVAL_LEVEL, self.evaluator.build(&self.weights));
+        options.rules = self.rules;
+        options.handicap = self.handicap_balance();
+        let cancel = CancelToken::default();
+        options.cancel = Some(cancel.clone());
+        let to_move = self.current_player.opponent();
+        let handle = match engine::evaluate_state_async(Some(self.map_values()), to_move as i8, options) {
+            Ok(handle) => handle,
+            Err(e) => return debug!(error = e, "live evaluation not started"),
+        };
+        self.live_eval_cancel = Some(cancel.clone());
+        thread::spawn(move || {
+            if let Ok(Some(res)) = handle.wait() {
+                if !cancel.load(Ordering::Relaxed) {
+                    on_result(engine::to_points(res.score));
+                }
+            }
+        });
+    }
+
+    /// Aborts a search of `start_auto_play` whose result is not wanted any more.
+    fn stop_search(&mut self) {
+        if self.state == GameState::Calculating {
+            self.cancel.store(true, Ordering::Relaxed);
+            self.searches += 1;
+        }
+    }
+
+    /// Aborts the background evaluation and the preview, whose positions are gone once a move is played.
+    fn stop_live_eval(&mut self) {
+        if let Some(cancel) = self.live_eval_cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+        self.stop_preview();
+    }
+
+    fn stop_preview(&mut self) {
+        if let Some(cancel) = self.preview_cancel.take() {
+            cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Starts a quick evaluation of the move of the player to move into `col`, to show the human where it lands and
+    /// how it turns out. The last preview is cancelled, this one is by the next preview, move or search.
+    pub fn start_preview(&mut self, col:usize) -> Result<PendingPreview, GameError> {
+        match self.state {
+            GameState::Finished | GameState::Draw => return Err(GameError::GameFinished),
+            // the computer's search is not slowed down by previews
+            GameState::Calculating => return Err(GameError::EngineBusy),
+            GameState::Blank | GameState::Running => {}
+        }
+        let row = self.legal_moves().get(col).ok_or(GameError::InvalidColumn(col))?.ok_or(GameError::ColumnFull(col))?;
+        self.stop_preview();
+        let player = self.position().to_move;
+        let mut values = self.map_values();
+        values[(row, col)] = player as i8;
+
+        let result = engine::evaluate_action(
+            Some(values.clone()),
+            player.opponent() as i8,
+            col,
+            self.evaluator.build(&self.weights),
+            self.rules,
+            self.handicap_balance()
+        )?;
+        let mut preview = ColumnPreview { row: row as u8, col: col as u8, score: 0., finished: result.eval.finished };
+        let cancel = CancelToken::default();
+        if preview.finished {
+            preview.score = match result.eval.winner {
+                Some(winner) if winner == player as i8 => engine::WIN_POINTS,
+                Some(_) => -engine::WIN_POINTS,
+                None => 0.,
+            };
+            return Ok(PendingPreview { player, preview, handle: None, cancel });
+        }
+
+        let mut options = SearchOptions::new(PREVIEW_LEVEL, self.evaluator.build(&self.weights));
+        options.rules = self.rules;
+        options.handicap = self.handicap_balance();
+        options.cancel = Some(cancel.clone());
+        let handle = engine::evaluate_state_async(Some(values), player.opponent() as i8, options)?;
+        self.preview_cancel = Some(cancel.clone());
+        Ok(PendingPreview { player, preview, handle: Some(handle), cancel })
+    }
+
+    /// Lets the engine move for `player` and returns the column it played, waiting for the search.
+    #[cfg(test)]
+    pub fn auto_play(&mut self, player:CellState, window:Option<&Window>) -> Result<usize, GameError> {
+        let pending = self.start_auto_play(player, window)?;
+        self.finish_auto_play(pending.run(), window)
+    }
+
+    /// Starts the search for the engine's move for `player`. The game is `Calculating` until the result is passed
+    /// to `finish_auto_play`, so it may be unlocked while the computer thinks.
+    pub fn start_auto_play(&mut self, player:CellState, window:Option<&Window>) -> Result<PendingMove, GameError> {
+        if self.hotseat {
+            return Err(GameError::Hotseat);
+        }
         match self.state {
             GameState::Blank => self.state = GameState::Running,
-            GameState::Finished => return Err("Already solved".into()),
-            GameState::Calculating => return Err("calculating".into()),
+            GameState::Finished | GameState::Draw => return Err(GameError::GameFinished),
+            GameState::Calculating => return Err(GameError::EngineBusy),
             GameState::Running => {}        
         };
+        self.stop_live_eval();
 
         window.map(|w| emit_update(Update::State { 
             state: GameState::Calculating as i8,
@@ -231,36 +1204,863 @@ impl Game {
         }, w));
         
         
-        let res = engine::evaluate_state(Some(self.map_values()), player as i8, self.level, true)?;
-        let best_action = res.best_action.ok_or("no result")?;
+        let status = Arc::new(Mutex::new(SearchStatus::default()));
+        let on_progress = {
+            let (w, status) = (window.cloned(), status.clone());
+            Box::new(move |p:Progress| {
+                {
+                    let mut status = status.lock().unwrap();
+                    status.nodes = p.ops_count as u64;
+                    status.col = p.best.map(|best| best.action as u8);
+                    if p.phase == ProgressPhase::Started {
+                        status.depth = p.depth;
+                    }
+                }
+                let Some(w) = &w else {
+                    return;
+                };
+                let _ = match (p.phase, p.best) {
+                    (ProgressPhase::Started, _) => emit_update(Update::Thinking { phase: ThinkingPhase::Searching { depth: p.depth } }, w),
+                    (ProgressPhase::Completed, Some(best)) => emit_update(Update::Progress {
+                        depth: p.depth,
+                        col: best.action as u8,
+                        score: engine::to_points(best.score),
+                        nodes: p.ops_count as u64,
+                    }, w),
+                    (ProgressPhase::Completed, None) => Ok(()),
+                };
+            }) as Box<dyn Fn(Progress) + Send>
+        };
+        let policy = self.move_policy(player);
+        let mut options = SearchOptions::new(policy.level, self.evaluator.build(&self.weights));
+        options.max_depth = policy.max_depth;
+        options.randomized = true;
+        if let Some(personality) = self.personality {
+            options.temperature = personality.temperature();
+        }
+        options.on_progress = Some(on_progress);
+        options.banned_column = self.banned_column;
+        options.rules = self.rules;
+        options.handicap = self.handicap_balance();
+        // each search starts its own generator, so the seed is varied by the move number
+        options.seed = self.seed.map(|seed| seed.wrapping_add(self.move_history.len() as u64));
+        options.multi_pv = ANALYSIS_MOVES;
+        options.record_tree = self.record_tree;
+        options.time_limit_millis = self.clock.as_ref().map(|clock| {
+            // an even share of the time left for the moves the computer has left, plus the increment it gets back
+            let moves_left = ((TOTAL_FIELDS - self.move_history.len()) / 2).max(1) as u128;
+            let left = clock.remaining(player).as_millis();
+            let share = left / moves_left + clock.settings().increment_millis as u128;
+            share.min(left / 2).max(MIN_CLOCK_THINK_MILLIS)
+        });
+        self.cancel.store(false, Ordering::Relaxed);
+        options.cancel = Some(self.cancel.clone());
+        let prepared = engine::prepare_search(Some(self.map_values()), player as i8, options)?;
+        self.searches += 1;
+        self.search_status = Some((Instant::now(), status));
+        self.state = GameState::Calculating;
+        Ok(PendingMove { player, prepared, search: self.searches })
+    }
+
+    /// Plays the move found by a search of `start_auto_play` and returns its column. The result is rejected if the
+    /// game was reset or ended during the search.
+    pub fn finish_auto_play(&mut self, searched:SearchedMove, window:Option<&Window>) -> Result<usize, GameError> {
+        if searched.search != self.searches || self.state != GameState::Calculating {
+            return Err(GameError::SearchDiscarded);
+        }
+        self.state = GameState::Running;
+        let player = searched.player;
+        let res = searched.result?.ok_or("no legal move")?;
+        if res.termination == Termination::Cancelled && self.cancel_mode == CancelMode::TakeBack {
+            info!("computer move cancelled");
+            self.undo(player.opponent(), window)?;
+            return Err(GameError::Cancelled);
+        }
+        let (best_action, score) = self.pick_move(player, &res)?;
+        info!(termination = codes::termination(res.termination), score, nodes = res.ops_count as u64, "computer move searched");
+        window.map(|w| emit_update(Update::Searched {
+            depth: res.stats.depth,
+            elapsed_millis: (res.stats.elapsed_micros / 1000) as u64,
+            nodes: res.ops_count as u64,
+            reason: codes::termination(res.termination),
+        }, w));
+        self.eval_history.push(score);
+        self.last_stats = Some(res.stats.clone());
+        self.last_config = Some(res.config.clone());
+        self.last_tree = res.tree;
+
+        window.map(|w| emit_update(Update::Thinking { phase: ThinkingPhase::Finalizing }, w));
         self.play_col(best_action, player, window)?;
+        if let Some(last) = self.move_scores.last_mut() {
+            *last = Some(score);
+        }
 
-        window.map(|w| emit_update(Update::Balance { value: res.score }, w));
-        Ok(())
+        window.map(|w| emit_update(Update::Balance { value: score }, w));
+        window.map(|w| emit_update(Update::Analysis {
+            moves: res.top_moves.iter().map(|m| AnalysisMove { col: m.action as u8, score: engine::to_points(m.score) }).collect()
+        }, w));
+        Ok(best_action)
     }
 
-    pub fn reset(&mut self, level:u8, window:Option<&Window>) -> Result<(), String> {
-        for h in self.col_heights.iter_mut() {
-            *h = 0;
+    /// The column `player` plays after the search `res` and the score of the position after it. That is the best
+    /// move, unless the mistake model or rubber-banding picks one of the runners-up.
+    fn pick_move(&self, player:CellState, res:&StateEvaluation) -> Result<(usize, f32), String> {
+        let best = (res.best_action.ok_or("no result")?, engine::to_points(res.score));
+        let policy = self.move_policy(player);
+        if !self.mistakes && !policy.slips {
+            return Ok(best);
         }
+        let scores: Vec<f32> = res.top_moves.iter().map(|m| engine::to_points(m.score)).collect();
+        let index = match self.seed {
+            Some(seed) => mistakes::choose(policy.level, &scores, &mut StdRng::seed_from_u64(seed.wrapping_add(self.move_history.len() as u64))),
+            None => mistakes::choose(policy.level, &scores, &mut rand::thread_rng()),
+        };
+        Ok(match res.top_moves.get(index) {
+            Some(chosen) if index > 0 => {
+                debug!(col = chosen.action, best = best.0, "computer picks a weaker move");
+                (chosen.action, scores[index])
+            },
+            _ => best,
+        })
+    }
+
+    /// Takes back moves until one of `player` was taken back, i.e. the opponent's reply and the last move
+    /// of `player`, so it is `player`'s turn again. Returns the columns taken back, latest first.
+    pub fn undo(&mut self, player:CellState, window:Option<&Window>) -> Result<Vec<usize>, GameError> {
+        match self.state {
+            GameState::Calculating => return Err(GameError::EngineBusy),
+            GameState::Blank => return Err(GameError::NotStarted),
+            GameState::Running | GameState::Finished | GameState::Draw => {}
+        };
+        self.stop_live_eval();
 
         for (row, col) in (0..engine::HEIGHT).flat_map(|r| (0..engine::WIDTH).map(move |c| (r,c))) {
             let cell = self.cells[(row, col)].borrow_mut();
-            cell.reset(window);
+            if cell.winning {
+                cell.winning = false;
+                cell.emit_update(window);
+            }
         }
 
-        self.state = GameState::Blank;
-        self.current_player = CellState::P1;
-        self.level = level;
+        self.resigned = None;
+        let mut undone = Vec::with_capacity(2);
+        while let Some(PlayedMove { col, .. }) = self.move_history.pop_back() {
+            if let Some(Some(_)) = self.move_scores.pop() {
+                self.eval_history.pop();
+            }
+            let row = self.col_heights[col] - 1;
+            self.col_heights[col] = row;
+            let mover = self.cells[(row, col)].state;
+            self.cells[(row, col)].reset(window);
+            undone.push(col);
+            if mover == player {
+                break;
+            }
+        }
 
-        window.map_or(Ok(()), |w| emit_update(Update::State { 
+        self.last_move_at = Instant::now();
+        self.current_player = match self.move_history.back() {
+            Some(&PlayedMove { col, .. }) => self.cells[(self.col_heights[col] - 1, col)].state,
+            None => CellState::P1,
+        };
+        self.state = match self.move_history.is_empty() {
+            true => GameState::Blank,
+            false => GameState::Running,
+        };
+        debug!(?undone, "moves taken back");
+        if let Some(clock) = self.clock.as_mut() {
+            match self.state {
+                GameState::Running => clock.start(self.current_player.opponent()),
+                _ => clock.pause(),
+            }
+        }
+        self.emit_last_moves(window);
+        self.emit_clock(window);
+
+        window.map_or(Ok(()), |w| emit_update(Update::State {
             state: self.state as i8,
             winner: None,
         }, w))?;
-
-        window.map_or(Ok(()), |w| emit_update(Update::Balance { value: 0. }, w))
+        let balance = self.eval_history.last().copied().unwrap_or(0.);
+        window.map_or(Ok(()), |w| emit_update(Update::Balance { value: balance }, w))?;
+        Ok(undone)
     }
-}
+
+    /// Takes back the computer's last reply together with the move of `human` before it, so the human can try another
+    /// move. Unlike `undo` it fails if the last move was not a reply of the computer. Returns the columns taken back.
+    pub fn takeback(&mut self, human:CellState, window:Option<&Window>) -> Result<Vec<usize>, GameError> {
+        if self.hotseat {
+            return Err(GameError::Hotseat);
+        }
+        if self.move_history.len() < 2 || self.current_player != human.opponent() {
+            return Err(GameError::NoComputerReply);
+        }
+        self.undo(human, window)
+    }
+
+    /// Ends the game with a win for the opponent of `player`.
+    pub fn resign(&mut self, player:CellState, window:Option<&Window>) -> Result<(), GameError> {
+        match self.state {
+            GameState::Running => {},
+            GameState::Blank => return Err(GameError::NotStarted),
+            GameState::Finished | GameState::Draw => return Err(GameError::GameFinished),
+            GameState::Calculating => return Err(GameError::EngineBusy),
+        };
+        self.state = GameState::Finished;
+        self.resigned = Some(player);
+        if let Some(clock) = self.clock.as_mut() {
+            clock.pause();
+        }
+        info!(player = player as i8, moves = self.move_history.len(), "resigned");
+
+        window.map_or(Ok(()), |w| emit_update(Update::State {
+            state: self.state as i8,
+            winner: self.winner(),
+        }, w))?;
+        Ok(())
+    }
+
+    /// Ends the game with a win for the opponent of `player`, whose time ran out.
+    fn lose_on_time(&mut self, player:CellState, window:Option<&Window>) {
+        self.stop_search();
+        self.state = GameState::Finished;
+        self.resigned = Some(player);
+        info!(player = player as i8, moves = self.move_history.len(), "lost on time");
+        window.map(|w| emit_update(Update::State {
+            state: self.state as i8,
+            winner: self.winner(),
+        }, w));
+        self.emit_clock(window);
+    }
+
+    /// Time control of the next game, set before `reset`.
+    pub fn set_clock(&mut self, settings:Option<ClockSettings>) -> Result<(), String> {
+        settings.as_ref().map_or(Ok(()), ClockSettings::validate)?;
+        self.clock_settings = settings;
+        Ok(())
+    }
+
+    /// The cells of the latest move of player 1 and 2.
+    pub fn last_moves(&self) -> [Option<MovedCell>; 2] {
+        let mut heights = self.col_heights;
+        let mut last = [None, None];
+        for col in self.cols().into_iter().rev() {
+            heights[col] -= 1;
+            let index = match self.cells[(heights[col], col)].state {
+                CellState::P1 => 0,
+                _ => 1,
+            };
+            last[index] = last[index].or(Some(MovedCell { row: heights[col] as u8, col: col as u8 }));
+            if last.iter().all(Option::is_some) {
+                break;
+            }
+        }
+        last
+    }
+
+    /// Emits the cells changed since `before` in a single `Update::Board`.
+    fn emit_board(&self, before:&Array2D<Cell>, window:Option<&Window>) {
+        let cells = board_diff(before, &self.cells);
+        if let (Some(w), false) = (window, cells.is_empty()) {
+            let _ = emit_update(Update::Board { cells }, w);
+        }
+    }
+
+    fn emit_last_moves(&self, window:Option<&Window>) {
+        if let Some(w) = window {
+            let [p1, p2] = self.last_moves();
+            let _ = emit_update(Update::LastMove { p1, p2 }, w);
+        }
+    }
+
+    fn emit_clock(&self, window:Option<&Window>) {
+        if let (Some(clock), Some(w)) = (self.clock.as_ref(), window) {
+            let _ = emit_update(Update::Clock {
+                p1_millis: clock.remaining(CellState::P1).as_millis() as u64,
+                p2_millis: clock.remaining(CellState::P2).as_millis() as u64,
+                running: clock.running().map(|player| player as i8),
+            }, w);
+        }
+    }
+
+    /// Emits the time left and the progress of the computer's search, and ends a running game whose player to move
+    /// ran out of time, who is returned.
+    pub fn tick(&mut self, window:Option<&Window>) -> Option<CellState> {
+        if !matches!(self.state, GameState::Running | GameState::Calculating) {
+            return None;
+        }
+        self.emit_search_progress(window);
+        let flagged = self.clock.as_ref()?.flagged();
+        match flagged {
+            Some(player) => self.lose_on_time(player, window),
+            None => self.emit_clock(window),
+        }
+        flagged
+    }
+
+    fn emit_search_progress(&self, window:Option<&Window>) {
+        let (Some((started, status)), Some(w), GameState::Calculating) = (&self.search_status, window, self.state) else {
+            return;
+        };
+        let status = *status.lock().unwrap();
+        let _ = emit_update(Update::SearchProgress {
+            depth: status.depth,
+            nodes: status.nodes,
+            col: status.col,
+            elapsed_millis: started.elapsed().as_millis() as u64,
+        }, w);
+    }
+
+    /// Token which aborts the computer's search when set. It can be shared with other threads, e.g. to abort a search
+    /// of `auto_play` while the game is locked.
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    /// How the computer plays the next move of `player`. With rubber-banding, it eases off by the score of its last
+    /// search, see `Policy::eased`.
+    fn move_policy(&self, player:CellState) -> Policy {
+        match (self.rubber_band, self.eval_history.last()) {
+            (true, Some(score)) => Policy::eased(self.level, score * player as i8 as f32),
+            _ => Policy::full(self.level),
+        }
+    }
+
+    /// Handicap pieces of player 1 minus those of player 2, as expected by the engine.
+    fn handicap_balance(&self) -> i8 {
+        self.handicap.iter().map(|cell| cell.player as i8).sum()
+    }
+
+    /// Places `cells` on the empty board before the first move, e.g. to give the weaker side a head start. The pieces
+    /// have to rest on the bottom row or on each other and must not complete a row. They are cleared by `reset`.
+    pub fn set_handicap(&mut self, cells:&[HandicapCell], window:Option<&Window>) -> Result<(), String> {
+        if self.state != GameState::Blank || self.col_heights.iter().any(|h| *h > 0) {
+            return Err("a handicap can only be given on an empty board".into());
+        }
+        let mut board = vec![0; TOTAL_FIELDS];
+        for cell in cells {
+            if cell.row >= HEIGHT || cell.col >= WIDTH {
+                return Err(format!("cell ({}, {}) is not on the board", cell.row, cell.col));
+            }
+            if cell.player == CellState::Blank {
+                return Err("a handicap piece needs a player".into());
+            }
+            let field = &mut board[cell.row * WIDTH + cell.col];
+            if *field != 0 {
+                return Err(format!("cell ({}, {}) is given twice", cell.row, cell.col));
+            }
+            *field = cell.player as i8;
+        }
+        let heights = col_heights(&board)?;
+        if has_row(&board, self.rules) {
+            return Err("the handicap completes a row".into());
+        }
+        if heights.iter().all(|h| *h == HEIGHT) {
+            return Err("the handicap fills the board".into());
+        }
+
+        let mut cells = cells.to_vec();
+        cells.sort_by_key(|cell| cell.row);
+        for cell in &cells {
+            self.cells[(cell.row, cell.col)].set_state(cell.player, window)?;
+        }
+        self.col_heights = heights;
+        self.handicap = cells;
+        Ok(())
+    }
+
+    /// Keeps the computer out of `col` until the next reset.
+    pub fn set_banned_column(&mut self, col:Option<usize>) {
+        self.banned_column = col;
+    }
+
+    pub fn moves_played(&self) -> usize {
+        self.move_history.len()
+    }
+
+    /// The row a piece played into each column would land in, `None` for full columns and once the game is over.
+    pub fn legal_moves(&self) -> [Option<usize>; WIDTH] {
+        let open = matches!(self.state, GameState::Blank | GameState::Running);
+        self.col_heights.map(|height| (open && height < HEIGHT).then_some(height))
+    }
+
+    /// The winner of a finished game, `None` while running or for a draw.
+    pub fn winner(&self) -> Option<i8> {
+        match (self.state, self.resigned) {
+            (GameState::Finished, Some(player)) => Some(-(player as i8)),
+            (GameState::Finished, None) => self.evaluate().ok().and_then(|r| r.eval.winner),
+            _ => None
+        }
+    }
+
+    /// Statistics of the computer's last search in this game.
+    pub fn last_stats(&self) -> Option<&SearchStats> {
+        self.last_stats.as_ref()
+    }
+
+    pub fn last_config(&self) -> Option<&ConfigSnapshot> {
+        self.last_config.as_ref()
+    }
+
+    /// Explored tree of the computer's last search, if recording was enabled.
+    pub fn last_tree(&self) -> Option<&SearchTree> {
+        self.last_tree.as_ref()
+    }
+
+    pub fn set_tree_recording(&mut self, enabled:bool) {
+        self.record_tree = enabled;
+    }
+
+    pub fn set_annotations(&mut self, enabled:bool) {
+        self.annotate = enabled;
+    }
+
+    pub fn set_cancel_mode(&mut self, mode:CancelMode) {
+        self.cancel_mode = mode;
+    }
+
+    pub fn set_live_eval(&mut self, enabled:bool) {
+        self.live_eval = enabled;
+        if !enabled {
+            self.stop_live_eval();
+        }
+    }
+
+    /// Suspends the background evaluation, or resumes it with an evaluation of the current position.
+    pub fn pause_live_eval(&mut self, paused:bool, window:Option<&Window>) {
+        if paused == self.live_eval_paused {
+            return;
+        }
+        self.live_eval_paused = paused;
+        if paused {
+            self.stop_live_eval();
+        } else {
+            self.start_live_eval(window);
+        }
+    }
+
+    /// Makes the computer's choice among equally good moves reproducible, `None` picks randomly.
+    pub fn set_seed(&mut self, seed:Option<u64>) {
+        self.seed = seed;
+    }
+
+    pub fn set_rubber_band(&mut self, rubber_band:bool) {
+        self.rubber_band = rubber_band;
+    }
+
+    pub fn rubber_band(&self) -> bool {
+        self.rubber_band
+    }
+
+    pub fn set_mistakes(&mut self, mistakes:bool) {
+        self.mistakes = mistakes;
+    }
+
+    pub fn set_hotseat(&mut self, hotseat:bool) {
+        self.hotseat = hotseat;
+    }
+
+    pub fn hotseat(&self) -> bool {
+        self.hotseat
+    }
+
+    /// Rules of the next game, set before `reset` as the board is not checked against them.
+    pub fn set_rules(&mut self, rules:Rules) -> Result<(), String> {
+        rules.validate()?;
+        self.rules = rules;
+        Ok(())
+    }
+
+    pub fn set_weights(&mut self, weights:EvalWeights) {
+        self.weights = weights;
+        self.personality = None;
+    }
+
+    /// Plays in the style of `personality`, replacing the weights.
+    pub fn set_personality(&mut self, personality:Personality) {
+        self.weights = personality.weights();
+        self.personality = Some(personality);
+    }
+
+    pub fn personality(&self) -> Option<Personality> {
+        self.personality
+    }
+
+    /// Cell states in row major order, row 0 is the bottom row.
+    pub fn board(&self) -> Vec<i8> {
+        self.map_values().as_row_major()
+    }
+
+    /// The moves played so far, in order.
+    pub fn history(&self) -> Vec<MoveRecord> {
+        let mut heights = [0; WIDTH];
+        self.move_history.iter().zip(&self.move_scores).enumerate().map(|(i, (&PlayedMove { col, millis }, &score))| {
+            let row = heights[col];
+            heights[col] += 1;
+            MoveRecord { number: i + 1, col, player: self.cells[(row, col)].state, score, millis }
+        }).collect()
+    }
+
+    /// The columns played so far, in order.
+    fn cols(&self) -> Vec<usize> {
+        self.move_history.iter().map(|played| played.col).collect()
+    }
+
+    /// The moves in the usual notation of solvers and other tools, one 1-based column digit per move, e.g. "44453".
+    pub fn notation(&self) -> String {
+        self.move_history.iter().map(|played| char::from(b'1' + played.col as u8)).collect()
+    }
+
+    /// Starts over with the moves of a column digit string, see `notation`. Player 1 moves first. The whole string
+    /// is checked before the board is touched, an illegal move is reported with its number counting from 1.
+    pub fn import_notation(&mut self, moves:&str, window:Option<&Window>) -> Result<(), String> {
+        let cols = moves.trim().chars().enumerate().map(|(i, c)| match c.to_digit(10) {
+            Some(digit @ 1..) if (digit as usize) <= WIDTH => Ok(digit as usize - 1),
+            _ => Err(format!("move {}: '{}' is not a column between 1 and {}", i + 1, c, WIDTH)),
+        }).collect::<Result<Vec<usize>, String>>()?;
+        self.start_from(&cols, CellState::P1, window)
+    }
+
+    /// The position on the board, see `Position`.
+    pub fn position(&self) -> Position {
+        Position {
+            board: self.board(),
+            to_move: match self.move_history.is_empty() {
+                true => CellState::P1,
+                false => self.current_player.opponent(),
+            },
+            moves: self.move_history.len(),
+        }
+    }
+
+    /// Starts over with a position, played in an order found by `Position::move_order`.
+    pub fn set_position(&mut self, position:&Position, window:Option<&Window>) -> Result<(), String> {
+        let cols = position.move_order(self.rules)?;
+        let first = match position.moves % 2 {
+            0 => position.to_move,
+            _ => position.to_move.opponent(),
+        };
+        self.start_from(&cols, first, window)
+    }
+
+    /// Resets the board and plays `cols` with alternating players, after checking them on a copy.
+    fn start_from(&mut self, cols:&[usize], first:CellState, window:Option<&Window>) -> Result<(), String> {
+        let player = |i:usize| match i % 2 {
+            0 => first,
+            _ => first.opponent(),
+        };
+
+        let mut game = Game::new(self.level);
+        game.rules = self.rules;
+        for (i, &col) in cols.iter().enumerate() {
+            game.play_col(col, player(i), None).map_err(|e| format!("move {}: {}", i + 1, e))?;
+        }
+
+        self.reset(self.level, self.evaluator, window)?;
+        for (i, &col) in cols.iter().enumerate() {
+            self.play_col(col, player(i), window)?;
+        }
+        // the moves were not thought about, so they neither cost time nor earn increments
+        self.move_history.iter_mut().for_each(|played| played.millis = 0);
+        self.clock = self.clock_settings.map(Clock::new);
+        self.emit_clock(window);
+        Ok(())
+    }
+
+    pub fn snapshot(&self) -> BoardSnapshot {
+        BoardSnapshot {
+            cells: self.cells.as_row_major(),
+            col_heights: self.col_heights,
+            state: self.state,
+            current_player: self.current_player,
+            move_history: self.cols(),
+            winner: self.winner(),
+            last_moves: self.last_moves(),
+        }
+    }
+
+    pub fn status(&self) -> GameStatus {
+        GameStatus {
+            state: self.state,
+            to_move: match self.state.is_over() {
+                true => None,
+                false => Some(self.position().to_move),
+            },
+            level: self.level,
+            rules: self.rules,
+            hotseat: self.hotseat,
+            moves: self.move_history.len(),
+            calculating: self.state == GameState::Calculating,
+        }
+    }
+
+    /// The game as written to a save file.
+    pub fn to_saved(&self) -> Result<SavedGame, GameError> {
+        if self.state == GameState::Calculating {
+            return Err(GameError::EngineBusy);
+        }
+        Ok(SavedGame {
+            version: savegame::FORMAT_VERSION,
+            level: self.level,
+            evaluator: self.evaluator,
+            weights: self.weights,
+            rubber_band: self.rubber_band,
+            mistakes: self.mistakes,
+            personality: self.personality,
+            state: self.state,
+            current_player: self.current_player,
+            board: self.board(),
+            move_history: self.cols(),
+            move_scores: self.move_scores.clone(),
+            move_millis: self.move_history.iter().map(|played| played.millis).collect(),
+            resigned: self.resigned,
+            banned_column: self.banned_column,
+            rules: self.rules,
+            hotseat: self.hotseat,
+            handicap: self.handicap.clone(),
+        })
+    }
+
+    /// Continues a saved game. The moves are replayed to check the file, which is rejected without changing
+    /// the game unless they are legal and lead to the saved board and state. Emits every cell, the state and the balance.
+    /// Saved games continue without a clock.
+    pub fn load(&mut self, saved:&SavedGame, window:Option<&Window>) -> Result<(), LoadError> {
+        if !(engine::MIN_LEVEL..=engine::MAX_LEVEL).contains(&saved.level) {
+            return Err(LoadError::Settings(format!("level must be between {} and {}", engine::MIN_LEVEL, engine::MAX_LEVEL)));
+        }
+        saved.weights.validate().map_err(LoadError::Settings)?;
+        saved.rules.validate().map_err(LoadError::Settings)?;
+        if saved.banned_column.is_some_and(|col| col >= WIDTH) {
+            return Err(LoadError::Settings("banned column does not exist".into()));
+        }
+        if saved.board.len() != TOTAL_FIELDS {
+            return Err(LoadError::Inconsistent("board size"));
+        }
+
+        let mut game = Game::new(saved.level);
+        game.configure(saved.level, saved.evaluator, saved.weights);
+        game.rules = saved.rules;
+        game.set_handicap(&saved.handicap, None).map_err(LoadError::Settings)?;
+        for (i, &col) in saved.move_history.iter().enumerate() {
+            let illegal = |reason:String| LoadError::IllegalMove { number: i + 1, reason };
+            if col >= WIDTH || game.col_heights[col] >= HEIGHT {
+                return Err(illegal(format!("column {} is full or does not exist", col)));
+            }
+            let player = match saved.board[game.col_heights[col] * WIDTH + col] {
+                1 => CellState::P1,
+                -1 => CellState::P2,
+                _ => return Err(LoadError::Inconsistent("board does not match the move history")),
+            };
+            game.play_col(col, player, None).map_err(|e| illegal(e.to_string()))?;
+        }
+        if game.board() != saved.board {
+            return Err(LoadError::Inconsistent("board does not match the move history"));
+        }
+        if saved.move_scores.len() != saved.move_history.len() {
+            return Err(LoadError::Inconsistent("move scores"));
+        }
+        if !saved.move_millis.is_empty() && saved.move_millis.len() != saved.move_history.len() {
+            return Err(LoadError::Inconsistent("move times"));
+        }
+        let state = match saved.resigned {
+            Some(_) if game.state == GameState::Running => GameState::Finished,
+            _ => game.state,
+        };
+        // files written before draws had their own state call them finished
+        let saved_state = match (saved.state, state) {
+            (GameState::Finished, GameState::Draw) => GameState::Draw,
+            (saved_state, _) => saved_state,
+        };
+        if state != saved_state {
+            return Err(LoadError::Inconsistent("game state"));
+        }
+        if !game.move_history.is_empty() && game.current_player != saved.current_player {
+            return Err(LoadError::Inconsistent("current player"));
+        }
+
+        self.stop_search();
+        self.stop_live_eval();
+        let before = mem::replace(&mut self.cells, game.cells);
+        self.col_heights = game.col_heights;
+        self.handicap = game.handicap;
+        self.state = state;
+        self.current_player = game.current_player;
+        self.move_history = game.move_history;
+        // files without times count every move as instant
+        for (played, &millis) in self.move_history.iter_mut().zip(saved.move_millis.iter().chain(iter::repeat(&0))) {
+            played.millis = millis;
+        }
+        self.last_move_at = Instant::now();
+        self.move_scores = saved.move_scores.clone();
+        self.eval_history = saved.move_scores.iter().flatten().copied().collect();
+        self.configure(saved.level, saved.evaluator, saved.weights);
+        self.rubber_band = saved.rubber_band;
+        self.mistakes = saved.mistakes;
+        self.personality = saved.personality;
+        self.rules = saved.rules;
+        self.hotseat = saved.hotseat;
+        self.clock = None;
+        self.resigned = saved.resigned;
+        self.banned_column = saved.banned_column;
+        self.last_stats = None;
+        self.last_config = None;
+        self.last_tree = None;
+        info!(moves = self.move_history.len(), "game loaded");
+
+        self.emit_board(&before, window);
+        self.emit_last_moves(window);
+        window.map(|w| emit_update(Update::State {
+            state: self.state as i8,
+            winner: self.winner(),
+        }, w));
+        let balance = self.eval_history.last().copied().unwrap_or(0.);
+        window.map(|w| emit_update(Update::Balance { value: balance }, w));
+        Ok(())
+    }
+
+    /// Name of the opening played so far, if the first moves match a known one.
+    pub fn opening(&self) -> Option<&'static str> {
+        openings::recognize(&self.cols()).map(|o| o.name)
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    pub fn evaluator(&self) -> EvaluatorKind {
+        self.evaluator
+    }
+
+    pub fn weights(&self) -> EvalWeights {
+        self.weights
+    }
+
+    /// Changes the engine settings without touching the board.
+    pub fn configure(&mut self, level:u8, evaluator:EvaluatorKind, weights:EvalWeights) {
+        self.level = level;
+        self.evaluator = evaluator;
+        self.weights = weights;
+    }
+
+    pub fn reset(&mut self, level:u8, evaluator:EvaluatorKind, window:Option<&Window>) -> Result<(), String> {
+        self.stop_search();
+        self.stop_live_eval();
+        for h in self.col_heights.iter_mut() {
+            *h = 0;
+        }
+
+        let before = self.cells.clone();
+        for (row, col) in (0..engine::HEIGHT).flat_map(|r| (0..engine::WIDTH).map(move |c| (r,c))) {
+            let cell = self.cells[(row, col)].borrow_mut();
+            cell.reset(None);
+        }
+        self.emit_board(&before, window);
+
+        self.state = GameState::Blank;
+        self.current_player = CellState::P1;
+        self.level = level;
+        self.evaluator = evaluator;
+        self.eval_history.clear();
+        self.last_stats = None;
+        self.last_config = None;
+        self.last_tree = None;
+        self.move_history.clear();
+        self.last_move_at = Instant::now();
+        self.move_scores.clear();
+        self.banned_column = None;
+        self.resigned = None;
+        self.handicap.clear();
+        self.clock = self.clock_settings.map(Clock::new);
+        self.emit_clock(window);
+        self.emit_last_moves(window);
+
+        window.map_or(Ok(()), |w| emit_update(Update::State { 
+            state: self.state as i8,
+            winner: None,
+        }, w))?;
+
+        window.map_or(Ok(()), |w| emit_update(Update::Balance { value: 0. }, w))
+    }
+}
+
+/// Steps through the moves of a finished game on a board of its own, emitting the events of a live game.
+pub struct Replay {
+    moves: Vec<MoveRecord>,
+    board: Game,
+}
+
+impl Replay {
+    /// Starts before the first move, the board is cleared except for the handicap.
+    pub fn new(game:&Game, window:Option<&Window>) -> Result<Replay, String> {
+        if !game.state().is_over() {
+            return Err("only finished games can be replayed".into());
+        }
+        let mut board = Game::new(game.level);
+        board.rules = game.rules;
+        board.reset(game.level, game.evaluator, window)?;
+        board.set_handicap(&game.handicap, window)?;
+        board.set_weights(game.weights);
+        Ok(Replay { moves: game.history(), board })
+    }
+
+    /// Number of moves shown on the board.
+    pub fn position(&self) -> usize {
+        self.board.moves_played()
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Shows the board after `n` moves, returns `n`.
+    pub fn jump(&mut self, n:usize, window:Option<&Window>) -> Result<usize, String> {
+        if n > self.moves.len() {
+            return Err(format!("the game has only {} moves", self.moves.len()));
+        }
+        while self.position() < n {
+            let record = &self.moves[self.position()];
+            self.board.play_col(record.col, record.player, window)?;
+            if let Some(score) = record.score {
+                self.board.move_scores[record.number - 1] = Some(score);
+                self.board.eval_history.push(score);
+            }
+            let balance = self.board.eval_history.last().copied().unwrap_or(0.);
+            window.map(|w| emit_update(Update::Balance { value: balance }, w));
+        }
+        while self.position() > n {
+            // taking back a move of the last mover takes back just that move
+            let record = &self.moves[self.position() - 1];
+            self.board.undo(record.player, window)?;
+        }
+        Ok(n)
+    }
+
+    pub fn step_forward(&mut self, window:Option<&Window>) -> Result<usize, String> {
+        self.jump(self.position() + 1, window)
+    }
+
+    pub fn step_back(&mut self, window:Option<&Window>) -> Result<usize, String> {
+        match self.position() {
+            0 => Err("already at the start of the game".into()),
+            position => self.jump(position - 1, window),
+        }
+    }
+}
+
+impl MemoryUsage for Game {
+    fn heap_bytes(&self) -> usize {
+        self.cells.num_elements() * size_of::<Cell>()
+            + self.move_history.capacity() * size_of::<PlayedMove>()
+            + vec_bytes(&self.eval_history)
+            + vec_bytes(&self.move_scores)
+            + self.last_stats.as_ref().map_or(0, |s| vec_bytes(&s.root_nodes))
+            + self.last_tree.as_ref().map_or(0, |t| vec_bytes(&t.nodes))
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -269,12 +2069,11 @@ mod tests {
     use super::*;
 
     fn evaluate_state(game:&Game, player:CellState) -> Result<StateEvaluation, String> {
-        engine::evaluate_state(
+        engine::prepare_search(
             Option::Some(game.map_values()),
             player as i8,
-            game.level,
-            false
-        )
+            SearchOptions::new(game.level, game.evaluator.build(&game.weights))
+        )?.run()?.ok_or("no legal move".into())
     }
 
     #[test]
@@ -282,6 +2081,683 @@ mod tests {
         assert_eq!(CellState::P2 as i8, -1);
     }
 
+    #[test]
+    fn test_piece_drop() {
+        assert_eq!(PieceDrop { col: 3, row: 0, distance: HEIGHT as u8 }, PieceDrop::new(0, 3));
+        assert_eq!(1, PieceDrop::new(HEIGHT - 1, 0).distance);
+    }
+
+    #[test]
+    fn test_rubber_band() {
+        let mut g = Game::new(9);
+        let o = CellState::P2;
+        g.eval_history.push(-60.);
+        assert_eq!(Policy::full(9), g.move_policy(o));
+
+        g.set_rubber_band(true);
+        assert_eq!(3, g.move_policy(o).level);
+        assert!(g.move_policy(o).slips);
+        g.eval_history.push(-15.);
+        assert_eq!(6, g.move_policy(o).level);
+        assert_eq!(Some(6), g.move_policy(o).max_depth);
+        g.eval_history.push(20.);
+        assert_eq!(Policy::full(9), g.move_policy(o));
+
+        g.reset(9, EvaluatorKind::default(), None).unwrap();
+        assert_eq!(Policy::full(9), g.move_policy(o));
+    }
+
+    #[test]
+    fn test_heap_bytes() {
+        let mut g = Game::new(1);
+        let empty = g.heap_bytes();
+        assert!(empty >= TOTAL_FIELDS * size_of::<Cell>());
+        g.eval_history.push(1.);
+        assert!(g.heap_bytes() > empty);
+    }
+
+    #[test]
+    fn test_banned_column() {
+        let mut g = Game::new(1);
+        g.set_banned_column(Some(0));
+        for _ in 0..3 {
+            g.play_col(0, CellState::P1, None).unwrap();
+            // the threat cannot be blocked
+            assert_ne!(0, g.auto_play(CellState::P2, None).unwrap());
+            assert_eq!(None, g.winner());
+        }
+        assert_eq!(GameState::Finished, g.play_col(0, CellState::P1, None).unwrap());
+        assert_eq!(Some(CellState::P1 as i8), g.winner());
+        assert_eq!(7, g.moves_played());
+
+        g.reset(1, EvaluatorKind::default(), None).unwrap();
+        assert_eq!(0, g.moves_played());
+        assert_eq!(None, g.banned_column);
+    }
+
+    #[test]
+    fn test_opening() {
+        let mut g = Game::new(1);
+        let (x,o) = (CellState::P1, CellState::P2);
+        assert!(g.opening().is_none());
+        g.play_col(3, x, None).unwrap();
+        g.play_col(3, o, None).unwrap();
+        assert_eq!(Some("Center Opening, Stacked Reply"), g.opening());
+
+        g.reset(1, EvaluatorKind::default(), None).unwrap();
+        g.play_col(6, x, None).unwrap();
+        assert_eq!(Some("Edge Opening"), g.opening());
+    }
+
+    #[test]
+    fn test_undo() {
+        let mut g = Game::new(1);
+        let (x,o) = (CellState::P1, CellState::P2);
+        assert!(g.undo(x, None).is_err());
+
+        for col in [3, 3, 4, 4, 5, 5] {
+            let player = if g.moves_played() % 2 == 0 { x } else { o };
+            g.play_col(col, player, None).unwrap();
+        }
+        for i in [1, 3, 5] {
+            g.move_scores[i] = Some(0.);
+        }
+        g.eval_history.extend([0., 0., 0.]);
+        assert_eq!(GameState::Finished, g.play_col(6, x, None).unwrap());
+
+        // the winning move has no reply yet
+        assert_eq!(vec![6], g.undo(x, None).unwrap());
+        assert_eq!(GameState::Running, g.state());
+        assert!(g.cells.elements_row_major_iter().all(|c| !c.winning));
+        assert_eq!(3, g.eval_history.len());
+
+        assert_eq!(vec![5, 5], g.undo(x, None).unwrap());
+        assert_eq!(o, g.current_player);
+        assert_eq!([0, 0, 0, 2, 2, 0, 0], g.col_heights);
+        assert_eq!(2, g.eval_history.len());
+        assert_eq!(GameState::Running, g.play_col(5, x, None).unwrap());
+
+        g.reset(1, EvaluatorKind::default(), None).unwrap();
+        g.play_col(3, x, None).unwrap();
+        assert_eq!(vec![3], g.undo(x, None).unwrap());
+        assert_eq!(GameState::Blank, g.state());
+        assert_eq!(vec![0; TOTAL_FIELDS], g.board());
+    }
+
+    #[test]
+    fn test_board_diff() {
+        let mut g = Game::new(1);
+        let empty = g.cells.clone();
+        assert!(board_diff(&empty, &g.cells).is_empty());
+        g.play_col(3, CellState::P1, None).unwrap();
+        g.play_col(3, CellState::P2, None).unwrap();
+        assert_eq!(vec![
+            CellChange { row: 0, col: 3, state: 1, winning: false },
+            CellChange { row: 1, col: 3, state: -1, winning: false },
+        ], board_diff(&empty, &g.cells));
+
+        let played = g.cells.clone();
+        g.reset(1, EvaluatorKind::default(), None).unwrap();
+        assert_eq!(2, board_diff(&played, &g.cells).len());
+        assert!(board_diff(&played, &g.cells).iter().all(|c| c.state == 0));
+    }
+
+    #[test]
+    fn test_search_status() {
+        let mut g = Game::new(3);
+        let pending = g.start_auto_play(CellState::P1, None).unwrap();
+        let (_, status) = g.search_status.clone().unwrap();
+        let searched = pending.run();
+        let status = *status.lock().unwrap();
+        assert!(status.nodes > 0);
+        assert!(status.col.is_some_and(|col| (col as usize) < WIDTH));
+        g.finish_auto_play(searched, None).unwrap();
+    }
+
+    #[test]
+    fn test_handicap() {
+        let (x, o) = (CellState::P1, CellState::P2);
+        let cell = |row, col, player| HandicapCell { row, col, player };
+        let mut g = Game::new(1);
+        assert!(g.set_handicap(&[cell(1, 3, x)], None).is_err());
+        assert!(g.set_handicap(&[cell(0, 3, x), cell(0, 3, o)], None).is_err());
+        assert!(g.set_handicap(&[cell(0, 7, x)], None).is_err());
+        assert!(g.set_handicap(&[cell(0, 0, x), cell(0, 1, x), cell(0, 2, x), cell(0, 3, x)], None).is_err());
+        assert_eq!(GameState::Blank, g.state());
+
+        g.set_handicap(&[cell(1, 3, x), cell(0, 3, x)], None).unwrap();
+        assert_eq!(x as i8, g.map_values()[(1, 3)]);
+        g.play_col(3, o, None).unwrap();
+        assert_eq!(o as i8, g.map_values()[(2, 3)]);
+        assert!(g.set_handicap(&[cell(0, 0, x)], None).is_err());
+
+        let saved = g.to_saved().unwrap();
+        let mut copy = Game::new(1);
+        copy.load(&saved, None).unwrap();
+        assert_eq!(g.map_values(), copy.map_values());
+        assert_eq!(2, copy.handicap.len());
+
+        assert_eq!(vec![3], g.undo(o, None).unwrap());
+        assert_eq!(x as i8, g.map_values()[(0, 3)]);
+        g.reset(1, EvaluatorKind::default(), None).unwrap();
+        assert!(g.map_values().elements_row_major_iter().all(|v| *v == 0));
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut g = Game::new(1);
+        let moves = "030613054111124352331005302662266462445545";
+        let mut player = CellState::P1;
+        for (i, col) in moves.chars().enumerate() {
+            let state = g.play_col(col.to_digit(10).unwrap() as usize, player, None).unwrap();
+            assert_eq!(i + 1 == TOTAL_FIELDS, state == GameState::Draw);
+            player = player.opponent();
+        }
+        assert!(g.state().is_over());
+        assert_eq!(None, g.winner());
+        assert!(g.play_col(0, player, None).is_err());
+        assert!(g.resign(player, None).is_err());
+
+        let saved = g.to_saved().unwrap();
+        let mut copy = Game::new(1);
+        copy.load(&saved, None).unwrap();
+        assert_eq!(GameState::Draw, copy.state());
+        // saved before draws had their own state
+        copy.load(&SavedGame { state: GameState::Finished, ..saved }, None).unwrap();
+        assert_eq!(GameState::Draw, copy.state());
+
+        assert_eq!(vec![5], g.undo(player.opponent(), None).unwrap());
+        assert_eq!(GameState::Running, g.state());
+    }
+
+    #[test]
+    fn test_takeback() {
+        let mut g = Game::new(1);
+        let (x,o) = (CellState::P1, CellState::P2);
+        g.play_col(3, x, None).unwrap();
+        assert!(g.takeback(x, None).is_err());
+        g.play_col(4, o, None).unwrap();
+        g.play_col(2, x, None).unwrap();
+        assert!(g.takeback(x, None).is_err());
+
+        g.play_col(4, o, None).unwrap();
+        assert_eq!(vec![4, 2], g.takeback(x, None).unwrap());
+        assert_eq!(o, g.current_player);
+        assert_eq!([0, 0, 0, 1, 1, 0, 0], g.col_heights);
+
+        // the computer's first move was no reply
+        g.reset(1, EvaluatorKind::default(), None).unwrap();
+        g.play_col(3, x, None).unwrap();
+        assert!(g.takeback(o, None).is_err());
+        g.set_hotseat(true);
+        assert_eq!(Err(GameError::Hotseat), g.takeback(x, None));
+    }
+
+    #[test]
+    fn test_resign() {
+        let mut g = Game::new(1);
+        let (x,o) = (CellState::P1, CellState::P2);
+        assert_eq!(Err(GameError::NotStarted), g.resign(x, None));
+        g.play_col(3, x, None).unwrap();
+        g.play_col(3, o, None).unwrap();
+        g.resign(x, None).unwrap();
+        assert_eq!(GameState::Finished, g.state());
+        assert_eq!(Some(o as i8), g.winner());
+        assert_eq!(Err(GameError::GameFinished), g.resign(x, None));
+        assert_eq!(Err(GameError::GameFinished), g.play_col(4, x, None));
+
+        g.undo(x, None).unwrap();
+        assert_eq!(GameState::Blank, g.state());
+        assert_eq!(None, g.winner());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut g = Game::new(1);
+        assert_eq!(Err(GameError::InvalidColumn(WIDTH)), g.play_col(WIDTH, CellState::P1, None));
+        for i in 0..HEIGHT {
+            g.play_col(0, if i % 2 == 0 { CellState::P1 } else { CellState::P2 }, None).unwrap();
+        }
+        assert_eq!(Err(GameError::ColumnFull(0)), g.play_col(0, CellState::P1, None));
+
+        let json = serde_json::to_value(GameError::ColumnFull(0)).unwrap();
+        assert_eq!(json!({ "kind": "columnFull", "code": codes::ERR_GAME_COLUMN_FULL, "message": "column 0 is full", "params": { "col": 0 } }), json);
+        assert_eq!(json!({ "detail": "no drill running" }), GameError::from("no drill running").params());
+        let error = GameError::from(BoardError::PieceCount { p1: 3, p2: 0 });
+        assert_eq!((codes::ERR_PIECE_COUNT, json!({ "p1": 3, "p2": 0 })), (codes::game_error(&error), error.params()));
+    }
+
+    #[test]
+    fn test_win_length() {
+        let mut g = Game::new(1);
+        assert!(g.set_rules(Rules { win_length: 7, ..Rules::default() }).is_err());
+        g.set_rules(Rules { win_length: 3, ..Rules::default() }).unwrap();
+        g.reset(1, EvaluatorKind::default(), None).unwrap();
+        g.import_notation("11223", None).unwrap();
+        assert_eq!(GameState::Finished, g.state());
+        assert_eq!(Some(1), g.winner());
+        assert_eq!(3, g.snapshot().cells.iter().filter(|c| c.winning).count());
+
+        let saved = g.to_saved().unwrap();
+        assert_eq!(3, saved.rules.win_length);
+        let mut copy = Game::new(1);
+        copy.load(&saved, None).unwrap();
+        assert_eq!(GameState::Finished, copy.state());
+
+        // both players connected three, which only one of them can have done when connecting three
+        let position = Position::parse("......./......./......./......./ooo..../xxx.... x 6").unwrap();
+        assert!(position.move_order(Rules { win_length: 3, ..Rules::default() }).is_err());
+        assert_eq!(6, position.move_order(Rules::default()).unwrap().len());
+    }
+
+    #[test]
+    fn test_misere() {
+        let mut g = Game::new(1);
+        g.set_rules(Rules { variant: Variant::Misere, ..Rules::default() }).unwrap();
+        g.reset(1, EvaluatorKind::default(), None).unwrap();
+        g.import_notation("1717171", None).unwrap();
+        assert_eq!(GameState::Finished, g.state());
+        assert_eq!(Some(CellState::P2 as i8), g.winner());
+        assert_eq!(Some(CellState::P2 as i8), g.snapshot().winner);
+        assert!(g.snapshot().cells.iter().filter(|c| c.winning).all(|c| c.state == CellState::P1));
+    }
+
+    #[test]
+    fn test_hotseat() {
+        let mut g = Game::new(1);
+        let (x,o) = (CellState::P1, CellState::P2);
+        g.set_hotseat(true);
+        g.play_col(3, x, None).unwrap();
+        assert_eq!(Err(GameError::OutOfTurn), g.play_col(4, x, None));
+        g.play_col(3, o, None).unwrap();
+        assert!(g.play_col(2, o, None).is_err());
+        assert_eq!(Err(GameError::Hotseat), g.auto_play(x, None));
+        assert_eq!(2, g.moves_played());
+
+        let mut copy = Game::new(1);
+        copy.load(&g.to_saved().unwrap(), None).unwrap();
+        assert!(copy.hotseat());
+    }
+
+    #[test]
+    fn test_clock() {
+        let (x,o) = (CellState::P1, CellState::P2);
+        let mut g = Game::new(5);
+        assert!(g.set_clock(Some(ClockSettings { budget_millis: 0, increment_millis: 0 })).is_err());
+        g.set_clock(Some(ClockSettings { budget_millis: 40, increment_millis: 0 })).unwrap();
+        g.reset(5, EvaluatorKind::default(), None).unwrap();
+        assert_eq!(None, g.tick(None));
+        g.play_col(3, x, None).unwrap();
+        assert_eq!(None, g.tick(None));
+        std::thread::sleep(std::time::Duration::from_millis(60));
+        assert_eq!(Some(o), g.tick(None));
+        assert_eq!(GameState::Finished, g.state());
+        assert_eq!(Some(x as i8), g.winner());
+
+        // a move after the time ran out is not played
+        g.reset(5, EvaluatorKind::default(), None).unwrap();
+        g.play_col(3, x, None).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(60));
+        assert_eq!(Err(GameError::TimeUp), g.play_col(3, o, None));
+        assert_eq!(Some(x as i8), g.winner());
+        assert_eq!(1, g.moves_played());
+
+        // the computer thinks for a share of its time instead of the full level
+        g.set_clock(Some(ClockSettings { budget_millis: 2000, increment_millis: 100 })).unwrap();
+        g.reset(5, EvaluatorKind::default(), None).unwrap();
+        g.play_col(3, x, None).unwrap();
+        g.auto_play(o, None).unwrap();
+        let limit = g.last_config().unwrap().time_limit_millis.unwrap();
+        assert!(limit < 500 && limit >= 100, "{}", limit);
+        assert!(g.clock.as_ref().unwrap().remaining(o) > std::time::Duration::from_millis(1500));
+        assert_eq!(Some(x), g.clock.as_ref().unwrap().running());
+    }
+
+    #[test]
+    fn test_history() {
+        let mut g = Game::new(1);
+        let (x,o) = (CellState::P1, CellState::P2);
+        g.play_col(3, x, None).unwrap();
+        let col = g.auto_play(o, None).unwrap();
+        g.play_col(0, x, None).unwrap();
+
+        let history = g.history();
+        assert_eq!(3, history.len());
+        assert_eq!(MoveRecord { number: 1, col: 3, player: x, score: None, millis: history[0].millis }, history[0]);
+        assert_eq!((2, col, o), (history[1].number, history[1].col, history[1].player));
+        assert_eq!(g.eval_history.last().copied(), history[1].score);
+        assert_eq!(None, history[2].score);
+
+        g.undo(x, None).unwrap();
+        assert_eq!(2, g.history().len());
+        g.reset(1, EvaluatorKind::default(), None).unwrap();
+        assert!(g.history().is_empty());
+    }
+
+    #[test]
+    fn test_move_times() {
+        let mut g = Game::new(1);
+        let (x,o) = (CellState::P1, CellState::P2);
+        std::thread::sleep(std::time::Duration::from_millis(30));
+        g.play_col(3, x, None).unwrap();
+        g.play_col(3, o, None).unwrap();
+        let history = g.history();
+        assert!(history[0].millis >= 30);
+        assert!(history[1].millis < 30);
+
+        let saved = g.to_saved().unwrap();
+        assert_eq!(vec![history[0].millis, history[1].millis], saved.move_millis);
+        let mut copy = Game::new(1);
+        copy.load(&saved, None).unwrap();
+        assert_eq!(history, copy.history());
+        copy.load(&SavedGame { move_millis: vec![], ..saved.clone() }, None).unwrap();
+        assert!(copy.history().iter().all(|m| m.millis == 0));
+        assert_eq!(Err(LoadError::Inconsistent("move times")), copy.load(&SavedGame { move_millis: vec![1], ..saved }, None));
+
+        g.import_notation("44", None).unwrap();
+        assert!(g.history().iter().all(|m| m.millis == 0));
+    }
+
+    #[test]
+    fn test_annotations() {
+        let (x,o) = (CellState::P1, CellState::P2);
+        let quality = |g:&mut Game, col, player| g.annotate(col, player, None).unwrap().1.map(|pending| pending.wait().unwrap().0);
+        let setup = |annotate| {
+            let mut g = Game::new(1);
+            g.set_annotations(annotate);
+            for (col, player) in [(3, x), (6, o), (4, x), (4, o), (5, x), (5, o)] {
+                g.play_col(col, player, None).unwrap();
+            }
+            g
+        };
+        assert_eq!(None, quality(&mut setup(false), 2, x));
+        // completing the row wins, while the edge lets the chance go
+        let mut g = setup(true);
+        assert_eq!(Some(MoveQuality::Best), quality(&mut g, 2, x));
+        assert_eq!(GameState::Finished, g.state());
+        assert_eq!(Some(MoveQuality::Blunder), quality(&mut setup(true), 0, x));
+        // player 2 has to block in turn
+        let mut g = setup(true);
+        g.play_col(0, x, None).unwrap();
+        assert_eq!(Some(MoveQuality::Blunder), quality(&mut g, 1, o));
+    }
+
+    #[test]
+    fn test_live_eval() {
+        let (x,o) = (CellState::P1, CellState::P2);
+        let mut g = Game::new(1);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let send = |sender:&std::sync::mpsc::Sender<f32>| {
+            let sender = sender.clone();
+            move |value| sender.send(value).unwrap()
+        };
+        g.play_col(3, x, None).unwrap();
+        g.run_live_eval(send(&sender));
+        assert!(g.live_eval_cancel.is_none());
+
+        g.set_live_eval(true);
+        for (col, player) in [(0, o), (3, x), (3, o), (3, x)] {
+            g.play_col(col, player, None).unwrap();
+        }
+        // player 1 holds the centre
+        g.run_live_eval(send(&sender));
+        let value = receiver.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+        assert!(value > 0.);
+
+        g.run_live_eval(send(&sender));
+        let cancel = g.live_eval_cancel.clone().unwrap();
+        g.play_col(3, o, None).unwrap();
+        assert!(cancel.load(Ordering::Relaxed));
+        assert!(g.live_eval_cancel.is_none());
+
+        g.run_live_eval(send(&sender));
+        let cancel = g.live_eval_cancel.clone().unwrap();
+        g.pause_live_eval(true, None);
+        assert!(cancel.load(Ordering::Relaxed));
+        g.run_live_eval(send(&sender));
+        assert!(g.live_eval_cancel.is_none());
+        g.pause_live_eval(false, None);
+        g.run_live_eval(send(&sender));
+        assert!(g.live_eval_cancel.is_some());
+    }
+
+    #[test]
+    fn test_cancel_mode() {
+        let (x,o) = (CellState::P1, CellState::P2);
+        let cancel_soon = |g:&Game| {
+            let cancel = g.cancel_token();
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                cancel.store(true, Ordering::Relaxed);
+            })
+        };
+        let mut g = Game::new(engine::MAX_LEVEL);
+        g.play_col(3, x, None).unwrap();
+        let canceller = cancel_soon(&g);
+        let started = Instant::now();
+        assert!(g.auto_play(o, None).is_ok());
+        assert!(started.elapsed().as_millis() < 1000);
+        assert_eq!(2, g.moves_played());
+        canceller.join().unwrap();
+
+        g.set_cancel_mode(CancelMode::TakeBack);
+        g.play_col(3, x, None).unwrap();
+        let canceller = cancel_soon(&g);
+        assert_eq!(Err(GameError::Cancelled), g.auto_play(o, None));
+        assert_eq!(2, g.moves_played());
+        assert_eq!(Ok(GameState::Running), g.play_col(2, x, None));
+        canceller.join().unwrap();
+    }
+
+    #[test]
+    fn test_pending_move() {
+        let (x,o) = (CellState::P1, CellState::P2);
+        let mut g = Game::new(1);
+        g.play_col(3, x, None).unwrap();
+        let pending = g.start_auto_play(o, None).unwrap();
+        assert_eq!(GameState::Calculating, g.state());
+        assert!(g.play_col(2, x, None).is_err());
+        assert!(g.start_auto_play(o, None).is_err());
+        let col = g.finish_auto_play(pending.run(), None).unwrap();
+        assert_eq!(vec![3, col], g.cols());
+        assert_eq!(GameState::Running, g.state());
+
+        // a reset during the search discards its result
+        g.play_col(3, x, None).unwrap();
+        let pending = g.start_auto_play(o, None).unwrap();
+        g.reset(1, EvaluatorKind::default(), None).unwrap();
+        assert!(g.finish_auto_play(pending.run(), None).is_err());
+        assert_eq!(0, g.moves_played());
+        assert_eq!(GameState::Blank, g.state());
+    }
+
+    #[test]
+    fn test_legal_moves() {
+        let (x,o) = (CellState::P1, CellState::P2);
+        let mut g = Game::new(1);
+        assert_eq!([Some(0); WIDTH], g.legal_moves());
+        for i in 0..HEIGHT {
+            g.play_col(0, if i % 2 == 0 { x } else { o }, None).unwrap();
+        }
+        g.play_col(1, x, None).unwrap();
+        assert_eq!([None, Some(1), Some(0), Some(0), Some(0), Some(0), Some(0)], g.legal_moves());
+        g.resign(o, None).unwrap();
+        assert_eq!([None; WIDTH], g.legal_moves());
+    }
+
+    #[test]
+    fn test_preview() {
+        let (x,o) = (CellState::P1, CellState::P2);
+        let mut g = Game::new(1);
+        for (col, player) in [(3, x), (3, o), (4, x), (4, o), (5, x), (0, o)] {
+            g.play_col(col, player, None).unwrap();
+        }
+        let preview = g.start_preview(5).unwrap().wait().unwrap();
+        assert_eq!((1, 5, false), (preview.row, preview.col, preview.finished));
+        assert!(preview.score > 0., "{}", preview.score);
+        assert_eq!(ColumnPreview { row: 0, col: 6, score: engine::WIN_POINTS, finished: true }, g.start_preview(6).unwrap().wait().unwrap());
+        assert_eq!(Err(GameError::InvalidColumn(WIDTH)), g.start_preview(WIDTH).map(|_| ()));
+
+        let first = g.start_preview(0).unwrap();
+        let second = g.start_preview(1).unwrap();
+        assert_eq!(Err(GameError::Cancelled), first.wait());
+        g.play_col(1, x, None).unwrap();
+        assert_eq!(Err(GameError::Cancelled), second.wait());
+
+        let pending = g.start_auto_play(o, None).unwrap();
+        assert_eq!(Err(GameError::EngineBusy), g.start_preview(0).map(|_| ()));
+        g.finish_auto_play(pending.run(), None).unwrap();
+    }
+
+    #[test]
+    fn test_notation() {
+        let mut g = Game::new(1);
+        let (x,o) = (CellState::P1, CellState::P2);
+        assert_eq!("", g.notation());
+        for (col, player) in [(3, x), (3, o), (3, x), (4, o), (2, x), (0, o), (6, x)] {
+            g.play_col(col, player, None).unwrap();
+        }
+        assert_eq!("4445317", g.notation());
+    }
+
+    #[test]
+    fn test_import_notation() {
+        let mut g = Game::new(1);
+        g.import_notation("4445317", None).unwrap();
+        assert_eq!("4445317", g.notation());
+        assert_eq!(CellState::P1, g.history()[6].player);
+        assert_eq!(GameState::Running, g.state());
+
+        assert_eq!("move 3: '8' is not a column between 1 and 7", g.import_notation("448", None).unwrap_err());
+        assert!(g.import_notation("44a", None).is_err());
+        // the column is full after six moves
+        assert!(g.import_notation("4444444", None).unwrap_err().starts_with("move 7:"));
+        // the game is over after player 1 connects four
+        assert!(g.import_notation("1212121", None).is_ok());
+        assert_eq!(GameState::Finished, g.state());
+        assert!(g.import_notation("12121213", None).unwrap_err().starts_with("move 8:"));
+        assert_eq!("1212121", g.notation());
+
+        g.import_notation("", None).unwrap();
+        assert_eq!(GameState::Blank, g.state());
+    }
+
+    #[test]
+    fn test_position() {
+        let mut g = Game::new(1);
+        assert_eq!("......./......./......./......./......./....... x 0", g.position().to_string());
+        g.import_notation("4445", None).unwrap();
+        let text = "......./......./......./...x.../...o.../...xo.. x 4";
+        assert_eq!(text, g.position().to_string());
+        assert_eq!(g.position(), Position::parse(text).unwrap());
+
+        let mut copy = Game::new(1);
+        copy.set_position(&Position::parse(text).unwrap(), None).unwrap();
+        assert_eq!(g.board(), copy.board());
+        assert_eq!(g.position(), copy.position());
+
+        // the computer started
+        copy.set_position(&Position::parse("......./......./......./......./......./...o... x 1").unwrap(), None).unwrap();
+        assert_eq!(CellState::P2, copy.history()[0].player);
+
+        // four in a row is only possible with the last move
+        let won = "......./......./......./......./ooo..../xxxx... o 7";
+        copy.set_position(&Position::parse(won).unwrap(), None).unwrap();
+        assert_eq!(GameState::Finished, copy.state());
+        assert!(Position::parse("......./......./......./......./ooo..../xxxx... x 7").is_err());
+        let twice = Position::parse("......./......./......./......./oooo.../xxxx... x 8").unwrap();
+        assert!(twice.move_order(Rules::default()).is_err());
+
+        assert!(Position::parse("......./......./......./......./......./...x... x 1").is_err());
+        assert!(Position::parse("......./......./......./......./......./...x... o 2").is_err());
+        assert!(Position::parse("......./......./......./......./...x.../....... o 1").unwrap().move_order(Rules::default()).is_err());
+        assert!(Position::parse("......./......./......./......./......./...y... o 1").is_err());
+        assert!(Position::parse("......./....... x 0").is_err());
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut g = Game::new(1);
+        g.import_notation("445566", None).unwrap();
+        assert!(Replay::new(&g, None).is_err());
+        g.move_scores[5] = Some(-2.);
+        g.play_col(6, CellState::P1, None).unwrap();
+
+        let mut replay = Replay::new(&g, None).unwrap();
+        assert_eq!((0, 7), (replay.position(), replay.len()));
+        assert!(replay.step_back(None).is_err());
+        assert_eq!(1, replay.step_forward(None).unwrap());
+        assert_eq!(7, replay.jump(7, None).unwrap());
+        assert_eq!(g.board(), replay.board.board());
+        assert_eq!(GameState::Finished, replay.board.state());
+        assert!(replay.step_forward(None).is_err());
+
+        assert_eq!(6, replay.step_back(None).unwrap());
+        assert_eq!(GameState::Running, replay.board.state());
+        assert_eq!(vec![-2.], replay.board.eval_history);
+        assert_eq!(5, replay.step_back(None).unwrap());
+        assert!(replay.board.eval_history.is_empty());
+        assert_eq!(2, replay.jump(2, None).unwrap());
+        assert_eq!("44", replay.board.notation());
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let mut g = Game::new(1);
+        let (x,o) = (CellState::P1, CellState::P2);
+        for col in [3, 3, 4, 4, 5, 5, 6] {
+            let player = if g.moves_played() % 2 == 0 { x } else { o };
+            g.play_col(col, player, None).unwrap();
+        }
+        let snapshot = g.snapshot();
+        assert_eq!(TOTAL_FIELDS, snapshot.cells.len());
+        assert_eq!(o, snapshot.cells[WIDTH + 3].state);
+        assert_eq!(4, snapshot.cells.iter().filter(|c| c.winning).count());
+        assert_eq!([0, 0, 0, 2, 2, 2, 1], snapshot.col_heights);
+        assert_eq!(GameState::Finished, snapshot.state);
+        assert_eq!(x, snapshot.current_player);
+        assert_eq!(vec![3, 3, 4, 4, 5, 5, 6], snapshot.move_history);
+        assert_eq!(Some(x as i8), snapshot.winner);
+        assert_eq!([Some(MovedCell { row: 0, col: 6 }), Some(MovedCell { row: 1, col: 5 })], snapshot.last_moves);
+    }
+
+    #[test]
+    fn test_status() {
+        let mut g = Game::new(2);
+        let status = g.status();
+        assert_eq!((GameState::Blank, Some(CellState::P1), 2, 0), (status.state, status.to_move, status.level, status.moves));
+        g.play_col(3, CellState::P1, None).unwrap();
+        let pending = g.start_auto_play(CellState::P2, None).unwrap();
+        assert!(g.status().calculating);
+        assert_eq!(Some(CellState::P2), g.status().to_move);
+        g.finish_auto_play(pending.run(), None).unwrap();
+        assert_eq!(GameStatus {
+            state: GameState::Running,
+            to_move: Some(CellState::P1),
+            level: 2,
+            rules: Rules::default(),
+            hotseat: false,
+            moves: 2,
+            calculating: false,
+        }, g.status());
+        g.resign(CellState::P1, None).unwrap();
+        assert_eq!(None, g.status().to_move);
+    }
+
+    #[test]
+    fn test_last_moves() {
+        let (x,o) = (CellState::P1, CellState::P2);
+        let mut g = Game::new(1);
+        assert_eq!([None, None], g.last_moves());
+        g.play_col(2, o, None).unwrap();
+        assert_eq!([None, Some(MovedCell { row: 0, col: 2 })], g.last_moves());
+        g.play_col(2, x, None).unwrap();
+        g.play_col(4, o, None).unwrap();
+        assert_eq!([Some(MovedCell { row: 1, col: 2 }), Some(MovedCell { row: 0, col: 4 })], g.last_moves());
+        g.undo(o, None).unwrap();
+        assert_eq!([Some(MovedCell { row: 1, col: 2 }), Some(MovedCell { row: 0, col: 2 })], g.last_moves());
+    }
+
     #[test]
     fn test_play() {
         let mut g = Game::new(1);
@@ -319,7 +2795,7 @@ mod tests {
 
         g.play_col(4, x, None).unwrap();
 
-        let result = g.evaluate();
+        let result = g.evaluate().unwrap();
         assert_eq!(result.eval.winner.unwrap(), x as i8); 
     }
 
@@ -0,0 +1,49 @@
+//! Whether the computer runs on battery, which pauses background work depending on the `PausePolicy` of the settings.
+use std::{fs, path::Path};
+
+/// Where Linux lists the power supplies, one directory each.
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// Whether the computer runs on battery. Only known on Linux, elsewhere it counts as plugged in.
+pub fn on_battery() -> bool {
+    on_battery_in(Path::new(POWER_SUPPLY_DIR))
+}
+
+/// On battery if there are mains adapters and all of them are offline, a desktop without any is always plugged in.
+fn on_battery_in(dir:&Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    let read = |path:&Path| fs::read_to_string(path).map(|s| s.trim().to_owned()).ok();
+    let mains: Vec<_> = entries.flatten()
+        .map(|entry| entry.path())
+        .filter(|supply| read(&supply.join("type")).as_deref() == Some("Mains"))
+        .collect();
+    !mains.is_empty() && mains.iter().all(|supply| read(&supply.join("online")).as_deref() == Some("0"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_battery() {
+        let dir = std::env::temp_dir().join(format!("connect-four-power-{}", std::process::id()));
+        let supply = |name:&str, kind:&str, online:Option<&str>| {
+            let path = dir.join(name);
+            fs::create_dir_all(&path).unwrap();
+            fs::write(path.join("type"), format!("{}\n", kind)).unwrap();
+            if let Some(online) = online {
+                fs::write(path.join("online"), format!("{}\n", online)).unwrap();
+            }
+        };
+        assert!(!on_battery_in(&dir));
+        supply("BAT0", "Battery", None);
+        assert!(!on_battery_in(&dir));
+        supply("AC", "Mains", Some("0"));
+        assert!(on_battery_in(&dir));
+        supply("AC", "Mains", Some("1"));
+        assert!(!on_battery_in(&dir));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use serde::{Serialize, Deserialize};
+
+use crate::engine::{EvalWeights, EvaluatorKind, MAX_LEVEL, MIN_LEVEL};
+
+const EXTENSION:&str = "toml";
+
+/// A shareable engine configuration.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub level: u8,
+    pub evaluator: EvaluatorKind,
+    pub weights: EvalWeights,
+}
+
+impl Preset {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("preset name must not be empty".into());
+        }
+        if !self.name.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_') {
+            return Err("preset name may only contain letters, digits, spaces, '-' and '_'".into());
+        }
+        if !(MIN_LEVEL..=MAX_LEVEL).contains(&self.level) {
+            return Err(format!("level must be between {} and {}", MIN_LEVEL, MAX_LEVEL));
+        }
+        self.weights.validate()
+    }
+
+    pub fn load(path:&Path) -> Result<Preset, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let preset: Preset = toml::from_str(&content).map_err(|e| e.to_string())?;
+        preset.validate()?;
+        Ok(preset)
+    }
+
+    pub fn save(&self, path:&Path) -> Result<(), String> {
+        self.validate()?;
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(path, content).map_err(|e| e.to_string())
+    }
+}
+
+/// Keeps imported presets as one file per preset in a directory.
+pub struct PresetManager {
+    dir: PathBuf,
+}
+
+impl PresetManager {
+    pub fn new(dir:PathBuf) -> PresetManager {
+        PresetManager { dir }
+    }
+
+    fn path_of(&self, name:&str) -> PathBuf {
+        self.dir.join(name).with_extension(EXTENSION)
+    }
+
+    /// All valid presets sorted by name. Files which cannot be read are skipped.
+    pub fn list(&self) -> Vec<Preset> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut presets: Vec<Preset> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == EXTENSION))
+            .filter_map(|p| Preset::load(&p).ok())
+            .collect();
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+        presets
+    }
+
+    pub fn get(&self, name:&str) -> Result<Preset, String> {
+        self.list().into_iter().find(|p| p.name == name).ok_or(format!("unknown preset {}", name))
+    }
+
+    /// Stores a preset in the managed directory, replacing one with the same name.
+    pub fn store(&self, preset:&Preset) -> Result<(), String> {
+        preset.validate()?;
+        fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        preset.save(&self.path_of(&preset.name))
+    }
+
+    /// Validates a preset file shared by somebody else and adds it to the managed presets.
+    pub fn import(&self, path:&Path) -> Result<Preset, String> {
+        let preset = Preset::load(path)?;
+        self.store(&preset)?;
+        Ok(preset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name:&str) -> Preset {
+        Preset {
+            name: name.into(),
+            level: 5,
+            evaluator: EvaluatorKind::Threats,
+            weights: EvalWeights::default(),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(preset("aggressive").validate().is_ok());
+        assert!(preset("").validate().is_err());
+        assert!(preset("../escape").validate().is_err());
+        assert!(Preset { level: 0, ..preset("a") }.validate().is_err());
+        let mut weights = EvalWeights::default();
+        weights.open_three = f32::NAN;
+        assert!(Preset { weights, ..preset("a") }.validate().is_err());
+    }
+
+    #[test]
+    fn test_import_export() {
+        let dir = std::env::temp_dir().join(format!("connect-four-presets-{}", std::process::id()));
+        let manager = PresetManager::new(dir.join("managed"));
+        assert!(manager.list().is_empty());
+
+        let shared = dir.join("shared.toml");
+        fs::create_dir_all(&dir).unwrap();
+        preset("b").save(&shared).unwrap();
+        manager.import(&shared).unwrap();
+        manager.store(&preset("a")).unwrap();
+
+        fs::write(dir.join("broken.toml"), "level = 3").unwrap();
+        assert!(manager.import(&dir.join("broken.toml")).is_err());
+
+        let names: Vec<String> = manager.list().into_iter().map(|p| p.name).collect();
+        assert_eq!(vec!["a", "b"], names);
+        assert_eq!(preset("b"), manager.get("b").unwrap());
+        assert!(manager.get("c").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
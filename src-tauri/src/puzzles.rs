@@ -0,0 +1,99 @@
+use serde::Serialize;
+
+/// A position in which the side to move can force a win, see `Position` for the notation.
+#[derive(Serialize, Debug)]
+pub struct Puzzle {
+    pub id: &'static str,
+    pub position: &'static str,
+    /// The win has to be completed within this many own moves.
+    pub moves: u32,
+}
+
+pub const PUZZLES: &[Puzzle] = &[
+    Puzzle { id: "win-in-1-a", position: "......./......./......./......./.o..o../xxx.oxo x 8", moves: 1 },
+    Puzzle { id: "win-in-1-b", position: "......./......./.....x./.....o./....oxo/o..xxxo x 10", moves: 1 },
+    Puzzle { id: "win-in-2-a", position: "......./......./.....o./.....x./.....oo/.xx..xo x 8", moves: 2 },
+    Puzzle { id: "win-in-2-b", position: "......./o....../x....../o..ox../x..xx.o/oooxoxx x 16", moves: 2 },
+    Puzzle { id: "win-in-2-c", position: "o....x./o....x./ox...x./xo...o./ox...x./xxo..o. o 17", moves: 2 },
+    Puzzle { id: "win-in-3-a", position: "......./...o.../...x.../...x.../.o.oo.x/.x.oxox x 12", moves: 3 },
+    Puzzle { id: "win-in-3-b", position: "......./......./......./....xo./..ooox./x.xoxx. o 11", moves: 3 },
+    Puzzle { id: "win-in-3-c", position: "......./......./......./.xx...o/.oox..x/oooxoxx x 14", moves: 3 },
+];
+
+pub fn find(id:&str) -> Result<&'static Puzzle, String> {
+    PUZZLES.iter().find(|p| p.id == id).ok_or(format!("unknown puzzle {}", id))
+}
+
+/// A puzzle being solved against the computer's defence.
+pub struct PuzzleAttempt {
+    puzzle: &'static Puzzle,
+    /// Moves played in the game when the puzzle was set up.
+    start_moves: usize,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PuzzleResult {
+    pub id: &'static str,
+    pub move_limit: u32,
+    /// Own moves played since the puzzle was set up.
+    pub moves: u32,
+    pub solved: bool,
+    /// The win was missed, by losing, a draw or running out of moves.
+    pub failed: bool,
+}
+
+impl PuzzleAttempt {
+    pub fn new(puzzle:&'static Puzzle, start_moves:usize) -> PuzzleAttempt {
+        PuzzleAttempt { puzzle, start_moves }
+    }
+
+    /// Checks the attempt after `moves_played` moves of the game, the solver moves first.
+    pub fn result(&self, won:bool, finished:bool, moves_played:usize) -> PuzzleResult {
+        let moves = (moves_played.saturating_sub(self.start_moves) as u32 + 1) / 2;
+        let solved = won && moves <= self.puzzle.moves;
+        PuzzleResult {
+            id: self.puzzle.id,
+            move_limit: self.puzzle.moves,
+            moves,
+            solved,
+            failed: !solved && (finished || moves >= self.puzzle.moves),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::engine::Rules;
+    use crate::playfield::Position;
+
+    use super::*;
+
+    #[test]
+    fn test_puzzles() {
+        let ids: HashSet<&str> = PUZZLES.iter().map(|p| p.id).collect();
+        assert_eq!(PUZZLES.len(), ids.len());
+        for puzzle in PUZZLES {
+            let position = Position::parse(puzzle.position).unwrap();
+            assert!(position.move_order(Rules::default()).is_ok(), "{}", puzzle.id);
+            assert!(puzzle.moves > 0);
+        }
+        assert!(find("win-in-2-b").is_ok());
+        assert!(find("win-in-9").is_err());
+    }
+
+    #[test]
+    fn test_attempt() {
+        let attempt = PuzzleAttempt::new(find("win-in-2-a").unwrap(), 8);
+        assert_eq!((0, false, false), {
+            let r = attempt.result(false, false, 8);
+            (r.moves, r.solved, r.failed)
+        });
+        assert!(!attempt.result(false, false, 10).failed);
+        assert!(attempt.result(true, true, 11).solved);
+        assert!(attempt.result(false, false, 11).failed);
+        assert!(attempt.result(false, true, 10).failed);
+        assert!(!attempt.result(true, true, 13).solved);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::engine::MIN_LEVEL;
+
+/// Advantage of the computer at which rubber-banding eases off the most.
+const FULL_EASE_SCORE:f32 = 30.;
+/// Share of the levels above `MIN_LEVEL` which rubber-banding gives up at most.
+const MAX_LEVEL_EASE:f32 = 0.75;
+/// Depth limit of the search as soon as the computer is ahead.
+const MAX_EASED_DEPTH:u8 = 10;
+/// Depth limit of the search while easing off the most.
+const MIN_EASED_DEPTH:u8 = 2;
+
+/// How the computer plays its next move.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Policy {
+    pub level: u8,
+    /// Caps the depth of the search, `None` at full strength.
+    pub max_depth: Option<u8>,
+    /// Whether the move is picked by `mistakes::choose` on `level` instead of always taking the best one.
+    pub slips: bool,
+}
+
+impl Policy {
+    pub fn full(level:u8) -> Policy {
+        Policy { level, max_depth: None, slips: false }
+    }
+
+    /// The policy for `level` when the computer's last search scored `advantage` points in its favour. The further
+    /// it is ahead, the lower the level and the shallower the search, and the full strength is used when behind.
+    pub fn eased(level:u8, advantage:f32) -> Policy {
+        let ease = (advantage / FULL_EASE_SCORE).clamp(0., 1.);
+        if ease == 0. {
+            return Policy::full(level);
+        }
+        let span = level.saturating_sub(MIN_LEVEL) as f32;
+        let depth_span = (MAX_EASED_DEPTH - MIN_EASED_DEPTH) as f32;
+        Policy {
+            level: level - (span * ease * MAX_LEVEL_EASE).round() as u8,
+            max_depth: Some(MAX_EASED_DEPTH - (depth_span * ease).round() as u8),
+            slips: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eased() {
+        assert_eq!(Policy::full(9), Policy::eased(9, -40.));
+        assert_eq!(Policy::full(9), Policy::eased(9, 0.));
+        assert_eq!(Policy { level: 6, max_depth: Some(6), slips: true }, Policy::eased(9, 15.));
+        assert_eq!(Policy { level: 3, max_depth: Some(MIN_EASED_DEPTH), slips: true }, Policy::eased(9, 90.));
+        assert_eq!(MIN_LEVEL, Policy::eased(MIN_LEVEL, 90.).level);
+    }
+}
@@ -0,0 +1,233 @@
+use std::{fmt, fs, path::{Path, PathBuf}};
+
+use serde::{Serialize, Deserialize};
+
+use crate::engine::{EvalWeights, EvaluatorKind, Rules};
+use crate::personality::Personality;
+use crate::playfield::{CellState, GameState, HandicapCell};
+
+/// Version of the save file format, increased whenever a field changes meaning.
+pub const FORMAT_VERSION:u32 = 1;
+
+/// Everything needed to continue a game after restarting the app.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct SavedGame {
+    pub version: u32,
+    pub level: u8,
+    pub evaluator: EvaluatorKind,
+    pub weights: EvalWeights,
+    pub rubber_band: bool,
+    /// See `Game::set_mistakes`, off in files written before the mistake model existed.
+    #[serde(default)]
+    pub mistakes: bool,
+    /// The style the weights come from, `None` in files written before personalities existed.
+    #[serde(default)]
+    pub personality: Option<Personality>,
+    pub state: GameState,
+    /// The player who made the last move.
+    pub current_player: CellState,
+    /// Cell states in row major order, row 0 is the bottom row.
+    pub board: Vec<i8>,
+    pub move_history: Vec<usize>,
+    /// Search score of each move, `None` for moves the computer did not search.
+    pub move_scores: Vec<Option<f32>>,
+    /// Time taken for each move, empty in files written before moves were timed.
+    #[serde(default)]
+    pub move_millis: Vec<u64>,
+    pub resigned: Option<CellState>,
+    pub banned_column: Option<usize>,
+    /// Files written before other win lengths existed hold standard games.
+    #[serde(default)]
+    pub rules: Rules,
+    #[serde(default)]
+    pub hotseat: bool,
+    /// Pieces placed before the first move, none in files written before handicaps existed.
+    #[serde(default)]
+    pub handicap: Vec<HandicapCell>,
+}
+
+/// Why a save file was rejected.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoadError {
+    Io(String),
+    Format(String),
+    Version(u32),
+    Settings(String),
+    /// A move of the history cannot be played, `number` counts from 1.
+    IllegalMove { number: usize, reason: String },
+    /// The history is legal but does not lead to the saved board or state.
+    Inconsistent(&'static str),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "could not read save file: {}", e),
+            LoadError::Format(e) => write!(f, "save file is corrupt: {}", e),
+            LoadError::Version(version) => write!(f, "save file version {} is not supported, expected {}", version, FORMAT_VERSION),
+            LoadError::Settings(e) => write!(f, "invalid settings in save file: {}", e),
+            LoadError::IllegalMove { number, reason } => write!(f, "move {} of the save file is illegal: {}", number, reason),
+            LoadError::Inconsistent(what) => write!(f, "save file is inconsistent: {}", what),
+        }
+    }
+}
+
+impl From<LoadError> for String {
+    fn from(e:LoadError) -> String {
+        e.to_string()
+    }
+}
+
+impl SavedGame {
+    pub fn save(&self, path:&Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, content).map_err(|e| e.to_string())
+    }
+
+    /// Reads a save file of the current version. The game itself is checked by `Game::load`.
+    pub fn load(path:&Path) -> Result<SavedGame, LoadError> {
+        let content = fs::read_to_string(path).map_err(|e| LoadError::Io(e.to_string()))?;
+        let saved: SavedGame = serde_json::from_str(&content).map_err(|e| LoadError::Format(e.to_string()))?;
+        match saved.version {
+            FORMAT_VERSION => Ok(saved),
+            version => Err(LoadError::Version(version)),
+        }
+    }
+}
+
+/// Keeps the game in progress in a file, so it can be continued after a crash or an accidental close.
+pub struct Autosave {
+    path: PathBuf,
+}
+
+impl Autosave {
+    pub fn new(path:PathBuf) -> Autosave {
+        Autosave { path }
+    }
+
+    /// Whether a game was left unfinished.
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Replaces the stored game. It is written next to the file first, so a crash while writing keeps the last one.
+    pub fn store(&self, saved:&SavedGame) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let written = self.path.with_extension("tmp");
+        saved.save(&written)?;
+        fs::rename(&written, &self.path).map_err(|e| e.to_string())
+    }
+
+    pub fn load(&self) -> Result<SavedGame, LoadError> {
+        SavedGame::load(&self.path)
+    }
+
+    /// Removes the stored game, once there is nothing to continue.
+    pub fn clear(&self) -> Result<(), String> {
+        match self.exists() {
+            true => fs::remove_file(&self.path).map_err(|e| e.to_string()),
+            false => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::WIDTH;
+    use crate::playfield::Game;
+
+    use super::*;
+
+    fn saved_game() -> SavedGame {
+        let mut game = Game::new(3);
+        for (col, player) in [(3, CellState::P1), (3, CellState::P2), (4, CellState::P1)] {
+            game.play_col(col, player, None).unwrap();
+        }
+        game.to_saved().unwrap()
+    }
+
+    #[test]
+    fn test_save() {
+        let mut game = Game::new(3);
+        game.play_col(3, CellState::P1, None).unwrap();
+        game.auto_play(CellState::P2, None).unwrap();
+        let saved = game.to_saved().unwrap();
+        assert_eq!(FORMAT_VERSION, saved.version);
+        assert_eq!(2, saved.move_history.len());
+        assert!(saved.move_scores[1].is_some());
+
+        let path = std::env::temp_dir().join(format!("connect-four-save-{}.json", std::process::id()));
+        saved.save(&path).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(saved, serde_json::from_str::<SavedGame>(&content).unwrap());
+    }
+
+    #[test]
+    fn test_load() {
+        let saved = saved_game();
+        let path = std::env::temp_dir().join(format!("connect-four-load-{}.json", std::process::id()));
+        saved.save(&path).unwrap();
+        assert_eq!(saved, SavedGame::load(&path).unwrap());
+
+        let mut game = Game::new(1);
+        game.load(&saved, None).unwrap();
+        assert_eq!(saved, game.to_saved().unwrap());
+        assert_eq!(GameState::Running, game.state());
+        assert_eq!(CellState::P2, game.history()[1].player);
+
+        fs::write(&path, "{\"version\": 1").unwrap();
+        assert!(matches!(SavedGame::load(&path), Err(LoadError::Format(_))));
+        fs::write(&path, serde_json::to_string(&SavedGame { version: 0, ..saved.clone() }).unwrap()).unwrap();
+        assert_eq!(Err(LoadError::Version(0)), SavedGame::load(&path));
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(SavedGame::load(&path), Err(LoadError::Io(_))));
+    }
+
+    #[test]
+    fn test_autosave() {
+        let dir = std::env::temp_dir().join(format!("connect-four-autosave-{}", std::process::id()));
+        let autosave = Autosave::new(dir.join("autosave.json"));
+        assert!(!autosave.exists());
+        autosave.clear().unwrap();
+
+        let saved = saved_game();
+        autosave.store(&saved).unwrap();
+        autosave.store(&saved).unwrap();
+        assert!(autosave.exists());
+        assert_eq!(saved, autosave.load().unwrap());
+        assert!(!dir.join("autosave.tmp").exists());
+
+        autosave.clear().unwrap();
+        assert!(!autosave.exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_invalid() {
+        let saved = saved_game();
+        let mut game = Game::new(1);
+        let mut load = |saved:SavedGame| game.load(&saved, None).unwrap_err();
+
+        assert!(matches!(load(SavedGame { level: 0, ..saved.clone() }), LoadError::Settings(_)));
+        assert!(matches!(load(SavedGame { banned_column: Some(7), ..saved.clone() }), LoadError::Settings(_)));
+        assert!(matches!(load(SavedGame { rules: Rules { win_length: 6, ..Rules::default() }, ..saved.clone() }), LoadError::Settings(_)));
+        assert_eq!(LoadError::Inconsistent("board size"), load(SavedGame { board: vec![], ..saved.clone() }));
+        // the second piece in column 3 belongs to player 1 as well
+        let mut board = saved.board.clone();
+        board[WIDTH + 3] = 1;
+        assert!(matches!(load(SavedGame { board, ..saved.clone() }), LoadError::IllegalMove { number: 2, .. }));
+        let mut board = saved.board.clone();
+        board[0] = -1;
+        assert_eq!(LoadError::Inconsistent("board does not match the move history"), load(SavedGame { board, ..saved.clone() }));
+        assert!(matches!(load(SavedGame { move_history: vec![3, 3, 4, 9], ..saved.clone() }), LoadError::IllegalMove { number: 4, .. }));
+        assert_eq!(LoadError::Inconsistent("move scores"), load(SavedGame { move_scores: vec![], ..saved.clone() }));
+        assert_eq!(LoadError::Inconsistent("game state"), load(SavedGame { state: GameState::Finished, ..saved.clone() }));
+        assert_eq!(LoadError::Inconsistent("current player"), load(SavedGame { current_player: CellState::P2, ..saved.clone() }));
+
+        // a rejected file leaves the game alone
+        assert_eq!(0, game.moves_played());
+    }
+}
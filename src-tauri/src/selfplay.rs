@@ -0,0 +1,146 @@
+use std::fmt;
+
+use std::path::Path;
+
+use crate::engine::{ConnectFour, EvalWeights, EvaluatorKind};
+
+/// One side of a self-play match.
+#[derive(Clone, Copy, Debug)]
+pub struct Contender {
+    pub evaluator: EvaluatorKind,
+    pub weights: EvalWeights,
+    pub max_depth: Option<u8>,
+    /// Think time per move, the search stops at whichever limit it reaches first.
+    pub time_limit_millis: Option<u128>,
+    pub randomized: bool,
+}
+
+#[derive(Default, Debug)]
+pub struct MatchResult {
+    pub wins_a: u32,
+    pub wins_b: u32,
+    pub draws: u32,
+}
+
+impl MatchResult {
+    pub fn games(&self) -> u32 {
+        self.wins_a + self.wins_b + self.draws
+    }
+
+    pub fn win_rate_a(&self) -> f32 {
+        (self.wins_a as f32 + 0.5 * self.draws as f32) / self.games().max(1) as f32
+    }
+}
+
+impl fmt::Display for MatchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} games: A won {}, B won {}, {} draws (A scores {:.1}%)",
+            self.games(),
+            self.wins_a,
+            self.wins_b,
+            self.draws,
+            100. * self.win_rate_a()
+        )
+    }
+}
+
+/// Plays a single game without any window attached. Returns the winner (1 for `p1`, -1 for `p2`) or `None` for a draw.
+/// Fails if a contender cannot search, e.g. without any limit.
+pub fn play_game(p1:&Contender, p2:&Contender) -> Result<Option<i8>, String> {
+    let mut game = ConnectFour::empty(1, p1.evaluator.build(&p1.weights));
+    loop {
+        let contender = match game.current_player() {
+            1 => p1,
+            _ => p2,
+        };
+        game.set_evaluator(contender.evaluator.build(&contender.weights));
+        let col = game.search_within(contender.max_depth, contender.time_limit_millis, contender.randomized)?
+            .best_action
+            .ok_or("the search found no move")?;
+        game.play(col);
+
+        if let Some(winner) = game.outcome() {
+            return Ok(winner);
+        }
+    }
+}
+
+/// Plays `games` games between `a` and `b`, alternating who starts.
+pub fn run_match(a:&Contender, b:&Contender, games:u32) -> Result<MatchResult, String> {
+    let mut result = MatchResult::default();
+    for i in 0..games {
+        let a_starts = i % 2 == 0;
+        let winner = match a_starts {
+            true => play_game(a, b)?,
+            false => play_game(b, a)?,
+        };
+        match winner.map(|w| (w == 1) == a_starts) {
+            Some(true) => result.wins_a += 1,
+            Some(false) => result.wins_b += 1,
+            None => result.draws += 1,
+        }
+    }
+    Ok(result)
+}
+
+/// Parses `<evaluator>:<depth>[:<weights.toml>]`.
+fn parse_contender(arg:&str) -> Result<Contender, String> {
+    let mut parts = arg.splitn(3, ':');
+    let (Some(evaluator), Some(depth)) = (parts.next(), parts.next()) else {
+        return Err(format!("expected <evaluator>:<depth>, got {}", arg));
+    };
+    let weights = match parts.next() {
+        Some(path) => EvalWeights::load(Path::new(path))?,
+        None => EvalWeights::default(),
+    };
+    let evaluator: EvaluatorKind = evaluator.parse()?;
+    Ok(Contender {
+        evaluator,
+        weights,
+        max_depth: Some(depth.parse().map_err(|_| format!("invalid depth {}", depth))?),
+        time_limit_millis: None,
+        randomized: true,
+    })
+}
+
+/// Entry point of `connect-four selfplay <games> <contender> <contender>`, see `parse_contender`.
+pub fn run_cli(args:&[String]) -> Result<(), String> {
+    let [games, a, b] = args else {
+        return Err("usage: selfplay <games> <evaluator>:<depth> <evaluator>:<depth>".into());
+    };
+    let games: u32 = games.parse().map_err(|_| format!("invalid number of games {}", games))?;
+    let result = run_match(&parse_contender(a)?, &parse_contender(b)?, games)?;
+    println!("A = {}, B = {}", a, b);
+    println!("{}", result);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_match() {
+        let weights = EvalWeights::default();
+        let a = Contender { evaluator: EvaluatorKind::Heuristic, weights, max_depth: Some(2), time_limit_millis: None, randomized: true };
+        let b = Contender { evaluator: EvaluatorKind::Threats, weights, max_depth: Some(1), time_limit_millis: None, randomized: true };
+        let result = run_match(&a, &b, 4).unwrap();
+        assert_eq!(4, result.games());
+        assert!(result.win_rate_a() >= 0. && result.win_rate_a() <= 1.);
+
+        // a search which cannot run is an error, not a draw
+        let stuck = Contender { max_depth: Some(0), ..a };
+        assert!(play_game(&stuck, &b).is_err());
+    }
+
+    #[test]
+    fn test_parse_contender() {
+        let c = parse_contender("threats:3").unwrap();
+        assert_eq!(EvaluatorKind::Threats, c.evaluator);
+        assert_eq!(Some(3), c.max_depth);
+        assert!(parse_contender("threats").is_err());
+        assert!(parse_contender("nn:3").is_err());
+    }
+}
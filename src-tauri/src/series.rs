@@ -0,0 +1,142 @@
+use serde::Serialize;
+
+/// A match of several games against the computer. Whoever first wins more than half of `best_of` games
+/// wins the series, draws count as played games for neither side. The starting side alternates.
+pub struct Series {
+    best_of: u32,
+    human_wins: u32,
+    computer_wins: u32,
+    draws: u32,
+    human_starts: bool,
+    /// Whether the result of the current game was counted.
+    counted: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SeriesScore {
+    pub best_of: u32,
+    /// Number of the current game, counting from 1.
+    pub game: u32,
+    pub human_wins: u32,
+    pub computer_wins: u32,
+    pub draws: u32,
+    /// Whether the human starts the current game.
+    pub human_starts: bool,
+    /// `Some(true)` once the human won the series, `Some(false)` once the computer did.
+    pub human_won: Option<bool>,
+    pub over: bool,
+}
+
+impl Series {
+    pub fn new(best_of:u32, human_starts:bool) -> Result<Series, String> {
+        if best_of == 0 {
+            return Err("a series needs at least one game".into());
+        }
+        Ok(Series { best_of, human_wins: 0, computer_wins: 0, draws: 0, human_starts, counted: false })
+    }
+
+    fn played(&self) -> u32 {
+        self.human_wins + self.computer_wins + self.draws
+    }
+
+    fn needed(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.human_wins >= self.needed() || self.computer_wins >= self.needed() || self.played() >= self.best_of
+    }
+
+    /// Counts the result of the current game, `None` for a draw. Returns false if it was already counted.
+    pub fn record(&mut self, human_won:Option<bool>) -> bool {
+        if self.counted {
+            return false;
+        }
+        match human_won {
+            Some(true) => self.human_wins += 1,
+            Some(false) => self.computer_wins += 1,
+            None => self.draws += 1,
+        }
+        self.counted = true;
+        true
+    }
+
+    /// Moves on to the next game, started by the other side. Returns whether the human starts it.
+    pub fn next_game(&mut self) -> Result<bool, String> {
+        if !self.counted {
+            return Err("the current game of the series is not finished".into());
+        }
+        if self.is_over() {
+            return Err("the series is over".into());
+        }
+        self.human_starts = !self.human_starts;
+        self.counted = false;
+        Ok(self.human_starts)
+    }
+
+    pub fn score(&self) -> SeriesScore {
+        let human_won = match self.is_over() {
+            true if self.human_wins > self.computer_wins => Some(true),
+            true if self.computer_wins > self.human_wins => Some(false),
+            _ => None,
+        };
+        SeriesScore {
+            best_of: self.best_of,
+            game: self.played() + !self.counted as u32,
+            human_wins: self.human_wins,
+            computer_wins: self.computer_wins,
+            draws: self.draws,
+            human_starts: self.human_starts,
+            human_won,
+            over: self.is_over(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series() {
+        assert!(Series::new(0, true).is_err());
+        let mut series = Series::new(3, true).unwrap();
+        assert_eq!(1, series.score().game);
+        assert!(series.next_game().is_err());
+
+        assert!(series.record(Some(true)));
+        assert!(!series.record(Some(false)));
+        assert_eq!(Ok(false), series.next_game());
+        assert_eq!(2, series.score().game);
+
+        series.record(None);
+        assert_eq!(Ok(true), series.next_game());
+        series.record(Some(true));
+        let score = series.score();
+        assert_eq!((2, 0, 1), (score.human_wins, score.computer_wins, score.draws));
+        assert_eq!(Some(true), score.human_won);
+        assert!(score.over);
+        assert_eq!(3, score.game);
+        assert!(series.next_game().is_err());
+    }
+
+    #[test]
+    fn test_series_decided_early() {
+        let mut series = Series::new(5, false).unwrap();
+        for _ in 0..2 {
+            series.record(Some(false));
+            series.next_game().unwrap();
+        }
+        series.record(Some(false));
+        assert_eq!(Some(false), series.score().human_won);
+        assert!(series.next_game().is_err());
+
+        // the games ran out without a majority
+        let mut series = Series::new(2, true).unwrap();
+        series.record(Some(true));
+        series.next_game().unwrap();
+        series.record(Some(false));
+        assert!(series.score().over);
+        assert_eq!(None, series.score().human_won);
+    }
+}
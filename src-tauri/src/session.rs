@@ -0,0 +1,195 @@
+use std::{fs::{self, File}, io::{BufWriter, Write}, path::Path};
+
+use serde::{Serialize, Deserialize};
+
+use crate::engine::{EvaluatorKind, Rules};
+use crate::memory::MemoryUsage;
+use crate::playfield::{CellState, Game, GameError, GameState, HandicapCell};
+
+/// A command as received by the backend. Engine moves are recorded as `PlayCol` of the computer player,
+/// so a replay does not depend on the (randomized, time limited) search.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "command", rename_all = "camelCase")]
+pub enum Command {
+    NewGame {
+        level: u8,
+        evaluator: EvaluatorKind,
+        #[serde(default)]
+        rubber_band: bool,
+        #[serde(default)]
+        rules: Rules,
+        #[serde(default)]
+        hotseat: bool,
+        #[serde(default)]
+        handicap: Vec<HandicapCell>,
+    },
+    PlayCol {
+        col: usize,
+        player: CellState,
+    },
+    Undo {
+        player: CellState,
+    },
+    Resign {
+        player: CellState,
+    },
+}
+
+/// A command together with the state it left the game in.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Step {
+    pub command: Command,
+    /// Cell states in row major order, row 0 is the bottom row.
+    pub board: Vec<i8>,
+    pub state: GameState,
+    pub error: Option<String>,
+    /// Marks games in which the computer adapted its strength to the score.
+    #[serde(default)]
+    pub rubber_band: bool,
+    #[serde(default)]
+    pub opening: Option<String>,
+}
+
+impl Step {
+    pub fn new(command:Command, game:&Game, error:Option<String>) -> Step {
+        Step {
+            command,
+            board: game.board(),
+            state: game.state(),
+            error,
+            rubber_band: game.rubber_band(),
+            opening: game.opening().map(|o| o.to_owned()),
+        }
+    }
+}
+
+/// Appends the steps of a session to a file, one JSON object per line.
+pub struct SessionRecorder {
+    out: BufWriter<File>,
+}
+
+impl SessionRecorder {
+    pub fn create(path:&Path) -> Result<SessionRecorder, String> {
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        Ok(SessionRecorder { out: BufWriter::new(file) })
+    }
+
+    /// Flushes after each step, so the log is complete even if the app crashes afterwards.
+    pub fn record(&mut self, step:&Step) -> Result<(), String> {
+        let line = serde_json::to_string(step).map_err(|e| e.to_string())?;
+        writeln!(self.out, "{}", line).map_err(|e| e.to_string())?;
+        self.out.flush().map_err(|e| e.to_string())
+    }
+}
+
+pub fn load_session(path:&Path) -> Result<Vec<Step>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    content.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {}", i + 1, e)))
+        .collect()
+}
+
+#[derive(Serialize, Debug)]
+pub struct Divergence {
+    pub step: usize,
+    pub expected: Step,
+    pub actual: Step,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ReplayReport {
+    pub steps: usize,
+    /// The first step whose outcome differs from the recording, if any.
+    pub divergence: Option<Divergence>,
+}
+
+/// Replays the recorded commands against a fresh game without a window and compares every outcome.
+pub fn replay(steps:&[Step]) -> ReplayReport {
+    let mut game = Game::new(1);
+    for (i, expected) in steps.iter().enumerate() {
+        let result = match expected.command {
+            Command::NewGame { level, evaluator, rubber_band, rules, hotseat, ref handicap } => {
+                game.set_rubber_band(rubber_band);
+                game.set_hotseat(hotseat);
+                game.set_rules(rules)
+                    .and_then(|_| game.reset(level, evaluator, None))
+                    .and_then(|_| game.set_handicap(handicap, None))
+                    .map_err(GameError::from)
+            },
+            Command::PlayCol { col, player } => game.play_col(col, player, None).map(|_| ()),
+            Command::Undo { player } => game.undo(player, None).map(|_| ()),
+            Command::Resign { player } => game.resign(player, None),
+        };
+        let actual = Step::new(expected.command.clone(), &game, result.err().map(|e| e.to_string()));
+        if actual != *expected {
+            return ReplayReport {
+                steps: i + 1,
+                divergence: Some(Divergence { step: i, expected: expected.clone(), actual }),
+            };
+        }
+    }
+    ReplayReport { steps: steps.len(), divergence: None }
+}
+
+impl MemoryUsage for SessionRecorder {
+    fn heap_bytes(&self) -> usize {
+        self.out.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(game:&mut Game, command:Command) -> Step {
+        let result = match command {
+            Command::NewGame { level, evaluator, rubber_band, rules, hotseat, ref handicap } => {
+                game.set_rubber_band(rubber_band);
+                game.set_hotseat(hotseat);
+                game.set_rules(rules)
+                    .and_then(|_| game.reset(level, evaluator, None))
+                    .and_then(|_| game.set_handicap(handicap, None))
+                    .map_err(GameError::from)
+            },
+            Command::PlayCol { col, player } => game.play_col(col, player, None).map(|_| ()),
+            Command::Undo { player } => game.undo(player, None).map(|_| ()),
+            Command::Resign { player } => game.resign(player, None),
+        };
+        Step::new(command, game, result.err().map(|e| e.to_string()))
+    }
+
+    #[test]
+    fn test_record_replay() {
+        let (x, o) = (CellState::P1, CellState::P2);
+        let mut game = Game::new(1);
+        let mut steps = vec![record(&mut game, Command::NewGame { level: 3, evaluator: EvaluatorKind::Threats, rubber_band: true, rules: Rules::default(), hotseat: false, handicap: Vec::new() })];
+        for col in [3, 3, 4, 4, 5, 5, 6, 0] {
+            let player = if steps.len() % 2 == 1 { x } else { o };
+            steps.push(record(&mut game, Command::PlayCol { col, player }));
+        }
+        assert_eq!(GameState::Finished, steps[7].state);
+        assert!(steps[8].error.is_some());
+        assert!(steps.iter().all(|s| s.rubber_band));
+        assert_eq!(Some("Center Opening, Stacked Reply"), steps[2].opening.as_deref());
+
+        let path = std::env::temp_dir().join(format!("connect-four-session-{}.jsonl", std::process::id()));
+        let mut recorder = SessionRecorder::create(&path).unwrap();
+        steps.iter().for_each(|s| recorder.record(s).unwrap());
+        drop(recorder);
+        let loaded = load_session(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(steps, loaded);
+
+        let report = replay(&loaded);
+        assert_eq!(9, report.steps);
+        assert!(report.divergence.is_none());
+
+        let mut tampered = loaded.clone();
+        tampered[2].board[3] = 0;
+        let divergence = replay(&tampered).divergence.unwrap();
+        assert_eq!(2, divergence.step);
+        assert_eq!(1, divergence.actual.board[3]);
+    }
+}
@@ -0,0 +1,178 @@
+use std::{fs, path::PathBuf};
+
+use rand::Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::engine::{Rules, MAX_LEVEL, MIN_LEVEL};
+
+/// Level of the computer until the user picks another one.
+pub const DEFAULT_LEVEL:u8 = 8;
+
+/// How `new_game` decides whether the human or the computer moves first.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub enum StartMode {
+    /// The `starting_player` passed to `new_game`.
+    #[default]
+    Explicit,
+    Random,
+    /// The side which did not start the previous game, the explicit choice for the first one.
+    Alternate,
+}
+
+impl StartMode {
+    /// Whether the human starts, given the explicit choice and whether the human started the previous game.
+    pub fn human_starts(self, explicit:bool, last:Option<bool>, rng:&mut impl Rng) -> bool {
+        match (self, last) {
+            (StartMode::Random, _) => rng.gen(),
+            (StartMode::Alternate, Some(last)) => !last,
+            (StartMode::Explicit, _) | (StartMode::Alternate, None) => explicit,
+        }
+    }
+}
+
+/// When a background activity is suspended, it resumes once none of the enabled conditions holds.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(default)]
+pub struct PausePolicy {
+    pub when_minimized: bool,
+    /// See `power::on_battery`.
+    pub on_battery: bool,
+}
+
+impl Default for PausePolicy {
+    fn default() -> PausePolicy {
+        PausePolicy { when_minimized: true, on_battery: true }
+    }
+}
+
+impl PausePolicy {
+    pub fn paused(self, minimized:bool, on_battery:bool) -> bool {
+        self.when_minimized && minimized || self.on_battery && on_battery
+    }
+}
+
+/// What is kept between runs of the app, the preferences of the user are applied on startup.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(default)]
+pub struct Settings {
+    /// Whether the human started the previous game, `None` before the first one.
+    pub last_human_started: Option<bool>,
+    pub level: u8,
+    /// The side which moves first, 1 or -1, see `new_game`.
+    pub starting_player: i8,
+    pub start_mode: StartMode,
+    /// Variant and length of a row. The board itself always has `WIDTH` columns and `HEIGHT` rows.
+    pub rules: Rules,
+    pub sound: bool,
+    /// When the evaluation of the position in the background is suspended, see `set_live_eval`.
+    pub live_eval_pause: PausePolicy,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            last_human_started: None,
+            level: DEFAULT_LEVEL,
+            starting_player: 1,
+            start_mode: StartMode::default(),
+            rules: Rules::default(),
+            sound: true,
+            live_eval_pause: PausePolicy::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_LEVEL..=MAX_LEVEL).contains(&self.level) {
+            return Err(format!("level must be between {} and {}", MIN_LEVEL, MAX_LEVEL));
+        }
+        if self.starting_player != 1 && self.starting_player != -1 {
+            return Err(format!("unknown player {}", self.starting_player));
+        }
+        self.rules.validate()
+    }
+}
+
+/// Keeps the settings in a JSON file.
+pub struct SettingsStore {
+    path: PathBuf,
+}
+
+impl SettingsStore {
+    pub fn new(path:PathBuf) -> SettingsStore {
+        SettingsStore { path }
+    }
+
+    /// The stored settings, the defaults if nothing was stored yet.
+    pub fn load(&self) -> Result<Settings, String> {
+        if !self.path.exists() {
+            return Ok(Settings::default());
+        }
+        let content = fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        let settings: Settings = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    pub fn save(&self, settings:&Settings) -> Result<(), String> {
+        settings.validate()?;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+        fs::write(&self.path, content).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_mode() {
+        let mut rng = rand::thread_rng();
+        assert!(StartMode::Explicit.human_starts(true, Some(true), &mut rng));
+        assert!(!StartMode::Alternate.human_starts(true, Some(true), &mut rng));
+        assert!(StartMode::Alternate.human_starts(false, Some(false), &mut rng));
+        assert!(!StartMode::Alternate.human_starts(false, None, &mut rng));
+        let starts = (0..100).filter(|_| StartMode::Random.human_starts(false, None, &mut rng)).count();
+        assert!(0 < starts && starts < 100);
+    }
+
+    #[test]
+    fn test_pause_policy() {
+        // the missing conditions keep their defaults
+        let settings: Settings = serde_json::from_str(r#"{"level":5,"live_eval_pause":{"on_battery":false}}"#).unwrap();
+        assert_eq!(5, settings.level);
+        assert_eq!(PausePolicy { when_minimized: true, on_battery: false }, settings.live_eval_pause);
+        assert!(settings.live_eval_pause.paused(true, false));
+        assert!(!settings.live_eval_pause.paused(false, true));
+        assert!(PausePolicy::default().paused(false, true));
+        assert!(!PausePolicy::default().paused(false, false));
+    }
+
+    #[test]
+    fn test_store() {
+        let dir = std::env::temp_dir().join(format!("connect-four-settings-{}", std::process::id()));
+        let store = SettingsStore::new(dir.join("settings.json"));
+        assert_eq!(Settings::default(), store.load().unwrap());
+
+        let settings = Settings { last_human_started: Some(false), level: 12, sound: false, ..Settings::default() };
+        store.save(&settings).unwrap();
+        assert_eq!(settings, store.load().unwrap());
+        assert!(store.save(&Settings { level: 0, ..Settings::default() }).is_err());
+        assert!(store.save(&Settings { starting_player: 0, ..Settings::default() }).is_err());
+
+        fs::write(dir.join("settings.json"), r#"{"level": 99}"#).unwrap();
+        assert!(store.load().is_err());
+
+        // settings written by another version keep working
+        fs::write(dir.join("settings.json"), "{}").unwrap();
+        assert_eq!(Settings::default(), store.load().unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,266 @@
+use std::{collections::BTreeMap, fmt::Write, fs, path::{Path, PathBuf}, time::{SystemTime, UNIX_EPOCH}};
+
+use serde::{Serialize, Deserialize};
+
+use crate::engine::{MAX_LEVEL, MIN_LEVEL};
+use crate::playfield::MoveRecord;
+
+/// Rating of a new player, the same as that of the lowest level.
+const INITIAL_RATING:f32 = 800.;
+/// Rating difference between neighbouring levels of the engine.
+const LEVEL_RATING_STEP:f32 = 60.;
+/// Largest rating change after a single game.
+const K_FACTOR:f32 = 32.;
+
+/// A finished game of the human against the computer.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct GameRecord {
+    pub level: u8,
+    /// Side of the human, 1 or -1.
+    pub human_player: i8,
+    /// The winning side, `None` for a draw.
+    pub winner: Option<i8>,
+    pub moves: usize,
+    /// Seconds since the Unix epoch when the game ended.
+    pub date: u64,
+    /// The moves with their scores and times, empty for games recorded before the moves were kept.
+    #[serde(default)]
+    pub history: Vec<MoveRecord>,
+}
+
+impl GameRecord {
+    pub fn new(level:u8, human_player:i8, winner:Option<i8>, moves:usize) -> GameRecord {
+        let date = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        GameRecord { level, human_player, winner, moves, date, history: Vec::new() }
+    }
+
+    pub fn with_history(self, history:Vec<MoveRecord>) -> GameRecord {
+        GameRecord { history, ..self }
+    }
+
+    /// `Some(true)` if the human won, `None` for a draw.
+    pub fn human_won(&self) -> Option<bool> {
+        self.winner.map(|winner| winner == self.human_player)
+    }
+
+    /// Points of the human, 1 for a win and 0.5 for a draw.
+    fn score(&self) -> f32 {
+        match self.human_won() {
+            Some(true) => 1.,
+            Some(false) => 0.,
+            None => 0.5,
+        }
+    }
+}
+
+/// The fixed rating of the engine on `level`.
+pub fn level_rating(level:u8) -> f32 {
+    INITIAL_RATING + (level.saturating_sub(MIN_LEVEL)) as f32 * LEVEL_RATING_STEP
+}
+
+/// Elo rating of the human after playing `games` in order.
+pub fn rating(games:&[GameRecord]) -> f32 {
+    games.iter().fold(INITIAL_RATING, next_rating)
+}
+
+/// Elo rating of the human after playing `game` with `rating`.
+fn next_rating(rating:f32, game:&GameRecord) -> f32 {
+    let expected = 1. / (1. + 10f32.powf((level_rating(game.level) - rating) / 400.));
+    rating + K_FACTOR * (game.score() - expected)
+}
+
+/// The level closest to `rating`, against which the human wins about half of the games.
+pub fn adaptive_level(rating:f32) -> u8 {
+    let level = MIN_LEVEL as f32 + (rating - INITIAL_RATING) / LEVEL_RATING_STEP;
+    level.round().clamp(MIN_LEVEL as f32, MAX_LEVEL as f32) as u8
+}
+
+/// The human's record against one level.
+#[derive(Clone, Serialize, Debug, Default, PartialEq)]
+pub struct LevelRecord {
+    pub level: u8,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+#[derive(Clone, Serialize, Debug)]
+pub struct Stats {
+    pub games: Vec<GameRecord>,
+    pub levels: Vec<LevelRecord>,
+    /// The human's record over all levels.
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub rating: f32,
+}
+
+impl Stats {
+    pub fn new(games:Vec<GameRecord>) -> Stats {
+        let mut levels: BTreeMap<u8, LevelRecord> = BTreeMap::new();
+        for game in &games {
+            let record = levels.entry(game.level).or_insert(LevelRecord { level: game.level, ..Default::default() });
+            match game.human_won() {
+                Some(true) => record.wins += 1,
+                Some(false) => record.losses += 1,
+                None => record.draws += 1,
+            }
+        }
+        let levels: Vec<LevelRecord> = levels.into_values().collect();
+        let wins = levels.iter().map(|l| l.wins).sum();
+        let losses = levels.iter().map(|l| l.losses).sum();
+        let draws = levels.iter().map(|l| l.draws).sum();
+        let rating = rating(&games);
+        Stats { games, levels, wins, losses, draws, rating }
+    }
+}
+
+/// File formats of `export_archive`.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Csv,
+}
+
+/// One row per game. Lists of the moves are separated by semicolons, a move without a score has an empty entry.
+const CSV_HEADER: &str = "date,level,level_rating,human_player,result,rating,moves,notation,move_millis,scores";
+
+/// Writes `games` to `path` for analysis in other tools: the moves with their evaluations and times, the results
+/// and the human's rating after each game.
+pub fn export_archive(games:&[GameRecord], format:ArchiveFormat, path:&Path) -> Result<(), String> {
+    let content = match format {
+        ArchiveFormat::Csv => to_csv(games),
+    };
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+fn to_csv(games:&[GameRecord]) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+    let mut rating = INITIAL_RATING;
+    for game in games {
+        rating = next_rating(rating, game);
+        let result = match game.human_won() {
+            Some(true) => "win",
+            Some(false) => "loss",
+            None => "draw",
+        };
+        let notation: String = game.history.iter().map(|m| char::from(b'1' + m.col as u8)).collect();
+        let millis: Vec<String> = game.history.iter().map(|m| m.millis.to_string()).collect();
+        let scores: Vec<String> = game.history.iter().map(|m| m.score.map_or(String::new(), |s| s.to_string())).collect();
+        let _ = writeln!(
+            csv, "{},{},{},{},{},{:.1},{},{},{},{}",
+            game.date, game.level, level_rating(game.level), game.human_player, result, rating, game.moves, notation,
+            millis.join(";"), scores.join(";"),
+        );
+    }
+    csv
+}
+
+/// Keeps the finished games in a JSON file.
+pub struct StatsStore {
+    path: PathBuf,
+}
+
+impl StatsStore {
+    pub fn new(path:PathBuf) -> StatsStore {
+        StatsStore { path }
+    }
+
+    /// All recorded games, none if nothing was recorded yet.
+    pub fn games(&self) -> Result<Vec<GameRecord>, String> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&content).map_err(|e| e.to_string())
+    }
+
+    pub fn add(&self, game:GameRecord) -> Result<(), String> {
+        let mut games = self.games()?;
+        games.push(game);
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string(&games).map_err(|e| e.to_string())?;
+        fs::write(&self.path, content).map_err(|e| e.to_string())
+    }
+
+    pub fn reset(&self) -> Result<(), String> {
+        match self.path.exists() {
+            true => fs::remove_file(&self.path).map_err(|e| e.to_string()),
+            false => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store() {
+        let dir = std::env::temp_dir().join(format!("connect-four-stats-{}", std::process::id()));
+        let store = StatsStore::new(dir.join("stats.json"));
+        assert!(store.games().unwrap().is_empty());
+
+        store.add(GameRecord::new(5, 1, Some(1), 21)).unwrap();
+        store.add(GameRecord::new(5, -1, Some(1), 14)).unwrap();
+        store.add(GameRecord::new(3, 1, None, 42)).unwrap();
+        let stats = Stats::new(store.games().unwrap());
+        assert_eq!(3, stats.games.len());
+        assert_eq!(vec![
+            LevelRecord { level: 3, wins: 0, losses: 0, draws: 1 },
+            LevelRecord { level: 5, wins: 1, losses: 1, draws: 0 },
+        ], stats.levels);
+        assert_eq!((1, 1, 1), (stats.wins, stats.losses, stats.draws));
+
+        store.reset().unwrap();
+        assert!(store.games().unwrap().is_empty());
+        store.reset().unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_export_archive() {
+        use crate::playfield::CellState;
+
+        let history = vec![
+            MoveRecord { number: 1, col: 3, player: CellState::P1, score: None, millis: 1200 },
+            MoveRecord { number: 2, col: 2, player: CellState::P2, score: Some(-0.5), millis: 300 },
+        ];
+        let games = vec![
+            GameRecord { date: 100, ..GameRecord::new(MIN_LEVEL, 1, Some(1), 2).with_history(history) },
+            GameRecord { date: 200, ..GameRecord::new(MIN_LEVEL, -1, None, 42) },
+        ];
+        let path = std::env::temp_dir().join(format!("connect-four-archive-{}.csv", std::process::id()));
+        export_archive(&games, ArchiveFormat::Csv, &path).unwrap();
+        let csv = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(vec![
+            CSV_HEADER,
+            "100,1,800,1,win,816.0,2,43,1200;300,;-0.5",
+            "200,1,800,-1,draw,815.3,42,,,",
+        ], rows);
+    }
+
+    #[test]
+    fn test_rating() {
+        assert_eq!(INITIAL_RATING, rating(&[]));
+        assert_eq!(MIN_LEVEL, adaptive_level(rating(&[])));
+        assert_eq!(MAX_LEVEL, adaptive_level(10_000.));
+        assert_eq!(5, adaptive_level(level_rating(5) + 20.));
+
+        // an even game moves the rating by half the K factor
+        let win = GameRecord::new(MIN_LEVEL, 1, Some(1), 7);
+        assert_eq!(INITIAL_RATING + K_FACTOR / 2., rating(std::slice::from_ref(&win)));
+        let draw = GameRecord { winner: None, ..win.clone() };
+        assert_eq!(INITIAL_RATING, rating(&[draw]));
+
+        // losing to a much stronger level costs little
+        let loss = GameRecord { level: MAX_LEVEL, winner: Some(-1), ..win.clone() };
+        assert!(INITIAL_RATING - rating(&[loss]) < 1.);
+        let wins = vec![GameRecord { level: 8, ..win }; 20];
+        assert!(adaptive_level(rating(&wins)) > MIN_LEVEL);
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use crate::{corpus::{self, LabeledPosition}, engine::{EvalWeights, EvaluatorKind}, objective::objective};
+
+const INITIAL_STEP:f32 = 0.5;
+const MIN_STEP:f32 = 0.01;
+const MAX_ITERATIONS:u32 = 1000;
+
+pub struct TuningResult {
+    pub weights: EvalWeights,
+    pub error: f32,
+    pub iterations: u32,
+}
+
+/// Texel-style local search: nudges one weight at a time by +-step and keeps every change that lowers
+/// the objective. When no weight can be improved any more, the step is halved until it drops below `MIN_STEP`.
+pub fn tune(corpus:&[LabeledPosition], evaluator:EvaluatorKind, initial:&EvalWeights) -> TuningResult {
+    let mut params = initial.to_vec();
+    let mut best_error = objective(corpus, evaluator, initial);
+    let mut step = INITIAL_STEP;
+    let mut iterations = 0;
+
+    while step >= MIN_STEP && iterations < MAX_ITERATIONS {
+        iterations += 1;
+        let mut improved = false;
+        for i in 0..params.len() {
+            for delta in [step, -step] {
+                params[i] += delta;
+                let error = objective(corpus, evaluator, &EvalWeights::from_slice(&params));
+                if error < best_error {
+                    best_error = error;
+                    improved = true;
+                    break;
+                }
+                params[i] -= delta;
+            }
+        }
+
+        if !improved {
+            step /= 2.;
+        }
+    }
+
+    TuningResult {
+        weights: EvalWeights::from_slice(&params),
+        error: best_error,
+        iterations,
+    }
+}
+
+/// Entry point of `connect-four tune <corpus> <output.toml> [evaluator]`.
+/// The written file is picked up at startup when placed in the app config dir as `eval_weights.toml`.
+pub fn run_cli(args:&[String]) -> Result<(), String> {
+    let (corpus_path, output_path, evaluator) = match args {
+        [c, o] => (c, o, EvaluatorKind::default()),
+        [c, o, e] => (c, o, e.parse()?),
+        _ => return Err("usage: tune <corpus> <output.toml> [evaluator]".into()),
+    };
+
+    let corpus = corpus::load_corpus(Path::new(corpus_path))?;
+    let initial = EvalWeights::default();
+    println!("{} positions, initial error {:.5}", corpus.len(), objective(&corpus, evaluator, &initial));
+
+    let result = tune(&corpus, evaluator, &initial);
+    println!("final error {:.5} after {} iterations", result.error, result.iterations);
+    result.weights.save(Path::new(output_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tune_improves() {
+        let corpus: Vec<LabeledPosition> = ["4 1", "44 0", "444 1", "11 -1", "1 -1", "7 -1", "4455 1"]
+            .iter()
+            .filter_map(|l| corpus::parse_line(l).unwrap())
+            .collect();
+        let initial = EvalWeights::default();
+        let before = objective(&corpus, EvaluatorKind::Threats, &initial);
+        let result = tune(&corpus, EvaluatorKind::Threats, &initial);
+        assert!(result.error < before);
+        assert_eq!(result.error, objective(&corpus, EvaluatorKind::Threats, &result.weights));
+    }
+}
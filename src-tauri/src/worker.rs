@@ -0,0 +1,46 @@
+use std::thread;
+
+use tauri::async_runtime::{self, Receiver, Sender};
+use tracing::debug;
+
+use crate::playfield::{PendingMove, SearchedMove};
+
+/// Searches which may wait for the engine thread before a command has to wait to hand over another one.
+const QUEUE_LENGTH:usize = 16;
+
+struct Job {
+    pending: PendingMove,
+    reply: Sender<SearchedMove>,
+}
+
+/// The thread which all computer moves are searched on, one after the other. Commands hand a search over and await its result, so
+/// neither the IPC thread nor a thread of the async runtime is blocked while the computer thinks.
+pub struct EngineWorker {
+    jobs: Sender<Job>,
+}
+
+impl EngineWorker {
+    pub fn start() -> Result<EngineWorker, String> {
+        let (jobs, queue) = async_runtime::channel(QUEUE_LENGTH);
+        thread::Builder::new()
+            .name("engine".into())
+            .spawn(move || run(queue))
+            .map_err(|e| e.to_string())?;
+        Ok(EngineWorker { jobs })
+    }
+
+    /// Waits for the result of a search started by `Game::start_auto_play`.
+    pub async fn search(&self, pending:PendingMove) -> Result<SearchedMove, String> {
+        let (reply, mut result) = async_runtime::channel(1);
+        self.jobs.send(Job { pending, reply }).await.map_err(|_| "the engine thread has stopped")?;
+        result.recv().await.ok_or("the engine thread has stopped".into())
+    }
+}
+
+fn run(mut queue:Receiver<Job>) {
+    while let Some(Job { pending, reply }) = queue.blocking_recv() {
+        if reply.blocking_send(pending.run()).is_err() {
+            debug!("search result dropped, the command is gone");
+        }
+    }
+}
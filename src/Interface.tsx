@@ -1,27 +1,36 @@
 import { event, invoke } from "@tauri-apps/api";
 import { UnlistenFn, listen } from "@tauri-apps/api/event";
+import { open, save } from "@tauri-apps/api/dialog";
+// generated from the Rust types by `npm run bindings`
+import type { BoardSnapshot } from "./bindings/BoardSnapshot";
+import type { CancelMode } from "./bindings/CancelMode";
+import type { Capabilities } from "./bindings/Capabilities";
+import type { ClockSettings } from "./bindings/ClockSettings";
+import type { ColumnPreview } from "./bindings/ColumnPreview";
+import type { EvaluatorKind } from "./bindings/EvaluatorKind";
+import type { GameError } from "./bindings/GameError";
+import type { GameStatus } from "./bindings/GameStatus";
+import type { HandicapCell } from "./bindings/HandicapCell";
+import type { MoveRecord } from "./bindings/MoveRecord";
+import type { MovedCell } from "./bindings/MovedCell";
+import type { NewGameOptions } from "./bindings/NewGameOptions";
+import type { PausePolicy } from "./bindings/PausePolicy";
+import type { Personality } from "./bindings/Personality";
+import type { Settings } from "./bindings/Settings";
+import type { StartMode } from "./bindings/StartMode";
+import type { Update as UpdateEvent } from "./bindings/Update";
+import type { Variant } from "./bindings/Variant";
 
-export interface Update {
-    Cell: CellUpdate,
-    State: StateUpdate,
-    Balance: BalanceUpdate,
-}
-
-export interface CellUpdate {
-    row: number,
-    col: number,
-    state: number,
-    winning: boolean,
-}
+export type {
+    BoardSnapshot, CancelMode, Capabilities, ClockSettings, ColumnPreview, EvaluatorKind, GameError, GameStatus,
+    HandicapCell, MoveRecord, MovedCell, NewGameOptions, PausePolicy, Personality, Settings, StartMode, Variant,
+};
 
-export interface StateUpdate {
-    state: number,
-    winner: number,
-}
+/// the keys of each member of a union
+type KeysOf<T> = T extends unknown ? keyof T : never;
 
-export interface BalanceUpdate {
-    value: number,
-}
+/// an update with the fields of every kind, only the one of the event's kind is set
+export type Update = { [K in KeysOf<UpdateEvent>]: Extract<UpdateEvent, Record<K, unknown>>[K] };
 
 export const CellState = {
     Blank: 0,
@@ -34,35 +43,576 @@ export const GameState = {
     Running: 1,
     Finished: 2,
     Calculating: 3,
+    /// the board filled up without a winner
+    Draw: 4,
+}
+
+/// Passes the message of a rejected command on, together with the error for callers which branch on its kind.
+function failed(onError: (msg:string, error:GameError) => void) {
+    return (error:GameError | string) => typeof error === 'string'
+        ? onError(error, {kind: 'other', code: 'game.other', message: error, params: {detail: error}})
+        : onError(error.message, error);
+}
+
+/// The game the functions below refer to, the main game if undefined, see `createGame`.
+let activeGame: number | undefined = undefined;
+
+export function setActiveGame(game?:number) {
+    activeGame = game;
+}
+
+/// Opens another game, e.g. an analysis board, whose updates are sent to this window and passes on its id.
+export function createGame(level:number | null, onError: (msg:string) => void, onSuccess: (game:number) => void) {
+    invoke<number>('create_game', {level}).then(onSuccess).catch(failed(onError));
+}
+
+export function closeGame(game:number, onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('close_game', {game}).then(onSuccess).catch(failed(onError));
 }
 
 export function playCol(
     col:number,
-    onError: (msg:string) => void
+    onError: (msg:string) => void,
+    player?:'P1' | 'P2',
 ) {
     invoke(
         'play_col', 
         {
             col:col,
+            player:player,
+            game:activeGame,
         }
     ).then(_ => {})
-    .catch(onError);
+    .catch(failed(onError));
 }
 
+export function undo(
+    onError: (msg:string) => void
+) {
+    invoke('undo', {game: activeGame})
+    .then(_ => {})
+    .catch(failed(onError));
+}
+
+/// Takes back the computer's last reply and the move before it, fails if the last move was not the computer's.
+export function takeback(
+    onError: (msg:string) => void
+) {
+    invoke('takeback', {game: activeGame})
+    .then(_ => {})
+    .catch(failed(onError));
+}
+
+export function resign(
+    onError: (msg:string) => void
+) {
+    invoke('resign', {game: activeGame})
+    .then(_ => {})
+    .catch(failed(onError));
+}
+
+export function swapSides(
+    onError: (msg:string) => void,
+    onSuccess: (human:'P1' | 'P2') => void,
+) {
+    invoke<'P1' | 'P2'>('swap_sides', {game: activeGame})
+    .then(onSuccess)
+    .catch(failed(onError));
+}
+
+/// the options of a new game, the ones left out get their defaults
+export type NewGameRequest = Pick<NewGameOptions, 'level' | 'starting_player'> & Partial<NewGameOptions>;
+
 export function newGame(
-    level:number,
-    startingPlayer:number,
+    options:NewGameRequest,
     onError: (msg:string) => void,
     onSuccess: () => void, 
 ) {
-    invoke(
-        'new_game',
-        {
-            level:level,
-            startingPlayer:startingPlayer
+    invoke('new_game', {options, game: activeGame})
+    .then(onSuccess)
+    .catch(failed(onError));
+}
+
+export interface SeriesScore {
+    best_of: number,
+    game: number,
+    human_wins: number,
+    computer_wins: number,
+    draws: number,
+    human_starts: boolean,
+    human_won: boolean | null,
+    over: boolean,
+}
+
+export interface Entrant {
+    name: string,
+    kind: 'Human' | 'Engine',
+}
+
+export interface RoundSettings {
+    level: number,
+    evaluator: EvaluatorKind,
+}
+
+export type Outcome = 'P1Wins' | 'P2Wins' | 'Draw';
+
+export interface Pairing {
+    id: number,
+    round: number,
+    p1: number,
+    p2: number | null,
+    result: Outcome | null,
+}
+
+export interface Standing {
+    entrant: number,
+    name: string,
+    played: number,
+    wins: number,
+    draws: number,
+    losses: number,
+    points: number,
+    eliminated: boolean,
+}
+
+export function createTournament(
+    format: 'RoundRobin' | 'Knockout',
+    entrants: Entrant[],
+    roundSettings: RoundSettings[],
+    onError: (msg:string) => void,
+    onSuccess: (pairings:Pairing[]) => void,
+) {
+    invoke<Pairing[]>('create_tournament', {format, entrants, roundSettings})
+    .then(onSuccess)
+    .catch(failed(onError));
+}
+
+export function getPairings(onError: (msg:string) => void, onSuccess: (pairings:Pairing[]) => void) {
+    invoke<Pairing[]>('get_pairings').then(onSuccess).catch(failed(onError));
+}
+
+export function getStandings(onError: (msg:string) => void, onSuccess: (standings:Standing[]) => void) {
+    invoke<Standing[]>('get_standings').then(onSuccess).catch(failed(onError));
+}
+
+export function recordTournamentResult(
+    pairingId:number,
+    outcome:Outcome,
+    onError: (msg:string) => void,
+    onSuccess: () => void,
+) {
+    invoke('record_tournament_result', {pairingId, outcome}).then(onSuccess).catch(failed(onError));
+}
+
+export function startTournamentGame(
+    pairingId:number,
+    onError: (msg:string) => void,
+    onSuccess: (outcome:Outcome | null) => void,
+) {
+    invoke<Outcome | null>('start_tournament_game', {pairingId, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+
+export interface EvalWeights {
+    col_bonus: number[],
+    open_two: number,
+    open_three: number,
+    blocked_three: number,
+}
+
+export interface Preset {
+    name: string,
+    level: number,
+    evaluator: EvaluatorKind,
+    weights: EvalWeights,
+}
+
+export function listPresets(onError: (msg:string) => void, onSuccess: (presets:Preset[]) => void) {
+    invoke<Preset[]>('list_presets').then(onSuccess).catch(failed(onError));
+}
+
+export function applyPreset(name:string, onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('apply_preset', {name, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+export function exportPreset(name:string, path:string, onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('export_preset', {name, path, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+export function importPreset(path:string, onError: (msg:string) => void, onSuccess: (preset:Preset) => void) {
+    invoke<Preset>('import_preset', {path}).then(onSuccess).catch(failed(onError));
+}
+
+
+export interface BenchmarkReport {
+    perft_depth: number,
+    perft_nodes: number,
+    perft_millis: number,
+    perft_nodes_per_sec: number,
+    search_depth: number,
+    search_nodes: number,
+    search_millis: number,
+    search_nodes_per_sec: number,
+}
+
+export function runBenchmark(perftDepth:number | null, onError: (msg:string) => void, onSuccess: (report:BenchmarkReport) => void) {
+    invoke<BenchmarkReport>('run_benchmark', {perftDepth}).then(onSuccess).catch(failed(onError));
+}
+
+
+export interface SessionStep {
+    command: {command: 'newGame', level: number, evaluator: 'Heuristic' | 'Threats' | 'Windows', rubber_band: boolean, rules: {win_length: number, variant: 'Standard' | 'Cylinder' | 'Misere'}, hotseat: boolean} | {command: 'playCol', col: number, player: string} | {command: 'undo', player: string} | {command: 'resign', player: string},
+    board: number[],
+    state: string,
+    error: string | null,
+    rubber_band: boolean,
+}
+
+export interface ReplayReport {
+    steps: number,
+    divergence: {step: number, expected: SessionStep, actual: SessionStep} | null,
+}
+
+export function recordSession(path:string | null, onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('record_session', {path}).then(onSuccess).catch(failed(onError));
+}
+
+export function replaySession(path:string, onError: (msg:string) => void, onSuccess: (report:ReplayReport) => void) {
+    invoke<ReplayReport>('replay_session', {path}).then(onSuccess).catch(failed(onError));
+}
+
+
+export function getOpeningName(onError: (msg:string) => void, onSuccess: (name:string | null) => void) {
+    invoke<string | null>('get_opening_name', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+
+export interface StartupProfile {
+    phases: {name: string, micros: number, detail: string}[],
+    total_micros: number,
+}
+
+export function profileStartup(onError: (msg:string) => void, onSuccess: (profile:StartupProfile) => void) {
+    invoke<StartupProfile>('profile_startup').then(onSuccess).catch(failed(onError));
+}
+
+
+export interface MemoryReport {
+    entries: {name: string, bytes: number}[],
+    total_bytes: number,
+}
+
+export function getMemoryReport(onError: (msg:string) => void, onSuccess: (report:MemoryReport) => void) {
+    invoke<MemoryReport>('get_memory_report').then(onSuccess).catch(failed(onError));
+}
+
+
+export interface DrillScore {
+    column: number | null,
+    won: boolean,
+    moves: number,
+    guessed_after: number | null,
+    wrong_guesses: number,
+    points: number,
+}
+
+export function startDrill(level:number, onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('start_drill', {level: level, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+export function guessDrillColumn(col:number, onError: (msg:string) => void, onSuccess: (correct:boolean) => void) {
+    invoke<boolean>('guess_drill_column', {col: col, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+export function getDrillScore(onError: (msg:string) => void, onSuccess: (score:DrillScore) => void) {
+    invoke<DrillScore>('get_drill_score', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+
+export interface GameRecord {
+    level: number,
+    human_player: number,
+    winner: number | null,
+    moves: number,
+    date: number,
+    history: MoveRecord[],
+}
+
+export interface LevelRecord {
+    level: number,
+    wins: number,
+    losses: number,
+    draws: number,
+}
+
+export interface Stats {
+    games: GameRecord[],
+    levels: LevelRecord[],
+    wins: number,
+    losses: number,
+    draws: number,
+    rating: number,
+}
+
+export function getStats(onError: (msg:string) => void, onSuccess: (stats:Stats) => void) {
+    invoke<Stats>('get_stats').then(onSuccess).catch(failed(onError));
+}
+
+const ARCHIVE_FILTERS = [{name: 'CSV', extensions: ['csv']}];
+
+/// Asks for a file and writes all recorded games to it, with their moves, evaluations, times and the rating after each.
+export function exportArchive(onError: (msg:string) => void, onSuccess: () => void) {
+    save({filters: ARCHIVE_FILTERS}).then(path => {
+        if (path !== null) {
+            invoke('export_archive', {format: 'Csv', path}).then(onSuccess).catch(failed(onError));
         }
-    ).then(onSuccess)
-    .catch(onError);
+    }).catch(failed(onError));
+}
+
+export function resetStats(onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('reset_stats').then(onSuccess).catch(failed(onError));
+}
+
+/// preferences of the user, level and rules apply to the main game when the app starts
+export function getSettings(onError: (msg:string) => void, onSuccess: (settings:Settings) => void) {
+    invoke<Settings>('get_settings').then(onSuccess).catch(failed(onError));
+}
+
+export function setSettings(settings:Settings, onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('set_settings', {settings:settings}).then(onSuccess).catch(failed(onError));
+}
+
+
+export interface Puzzle {
+    id: string,
+    position: string,
+    moves: number,
+}
+
+export interface PuzzleResult {
+    id: string,
+    move_limit: number,
+    moves: number,
+    solved: boolean,
+    failed: boolean,
+}
+
+export function listPuzzles(onError: (msg:string) => void, onSuccess: (puzzles:Puzzle[]) => void) {
+    invoke<Puzzle[]>('list_puzzles').then(onSuccess).catch(failed(onError));
+}
+
+/// Sets up the puzzle's position, the human plays the side to move.
+export function loadPuzzle(id:string, onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('load_puzzle', {id: id, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+export function checkPuzzleSolution(onError: (msg:string) => void, onSuccess: (result:PuzzleResult) => void) {
+    invoke<PuzzleResult>('check_puzzle_solution', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+/// Sets the level of the engine's search log ("off", "info", "debug" or "trace") and passes the path of the log file.
+export function setSearchLog(level:string, onError: (msg:string) => void, onSuccess: (path:string) => void) {
+    invoke<string>('set_search_log', {level: level}).then(onSuccess).catch(failed(onError));
+}
+
+/// What the backend supports, to build the settings from.
+export function getCapabilities(onError: (msg:string) => void, onSuccess: (capabilities:Capabilities) => void) {
+    invoke<Capabilities>('get_capabilities').then(onSuccess).catch(failed(onError));
+}
+
+export interface Code {
+    name: string,
+    kind: 'Event' | 'Error' | 'Reason',
+    description: string,
+}
+
+export function listCodes(onError: (msg:string) => void, onSuccess: (codes:Code[]) => void) {
+    invoke<Code[]>('list_codes').then(onSuccess).catch(failed(onError));
+}
+
+
+export interface SearchStats {
+    depth: number,
+    cutoffs: number,
+    tt_hits: number,
+    elapsed_micros: number,
+    root_nodes: [number, number][],
+}
+
+export function getBoard(onError: (msg:string) => void, onSuccess: (board:BoardSnapshot) => void) {
+    invoke<BoardSnapshot>('get_board', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+/// Where a piece dropped into the column would land and how the position after it looks, for hovering over a column.
+/// Fails with kind 'cancelled' once another column is previewed or a move is played.
+export function previewCol(col:number, onError: (msg:string, error:GameError) => void, onSuccess: (preview:ColumnPreview) => void) {
+    invoke<ColumnPreview>('preview_col', {col, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+/// Whose turn it is, the settings of the game and whether the computer is thinking.
+export function getStatus(onError: (msg:string) => void, onSuccess: (status:GameStatus) => void) {
+    invoke<GameStatus>('get_status', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+/// The landing row of each column, null for columns which cannot be played.
+export function getLegalMoves(onError: (msg:string) => void, onSuccess: (rows:(number | null)[]) => void) {
+    invoke<(number | null)[]>('get_legal_moves', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+export function getHistory(onError: (msg:string) => void, onSuccess: (history:MoveRecord[]) => void) {
+    invoke<MoveRecord[]>('get_history', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+const SAVE_FILTERS = [{name: 'Connect Four game', extensions: ['json']}];
+
+/// Asks for a file and writes the current game to it, does nothing if the dialog is cancelled.
+export function saveGame(onError: (msg:string) => void, onSuccess: () => void) {
+    save({filters: SAVE_FILTERS}).then(path => {
+        if (path !== null) {
+            invoke('save_game', {path, game: activeGame}).then(onSuccess).catch(failed(onError));
+        }
+    }).catch(failed(onError));
+}
+
+/// Asks for a saved game and continues it, the board is redrawn through the usual events.
+export function loadGame(onError: (msg:string) => void, onSuccess: () => void) {
+    open({filters: SAVE_FILTERS, multiple: false}).then(path => {
+        if (typeof path === 'string') {
+            invoke('load_game', {path, game: activeGame}).then(onSuccess).catch(failed(onError));
+        }
+    }).catch(failed(onError));
+}
+
+/// Whether the main game was left unfinished by the last run of the app, to offer resuming it on startup.
+export function hasAutosave(onError: (msg:string) => void, onSuccess: (exists:boolean) => void) {
+    invoke<boolean>('has_autosave').then(onSuccess).catch(failed(onError));
+}
+
+/// Continues the main game of the last run, the board is redrawn through the usual events.
+export function resumeAutosave(onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('resume_autosave').then(onSuccess).catch(failed(onError));
+}
+
+/// The moves as 1-based column digits, e.g. "44453".
+export function exportNotation(onError: (msg:string) => void, onSuccess: (moves:string) => void) {
+    invoke<string>('export_notation', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+/// Starts over with the position of a column digit string, the board is redrawn through the usual events.
+export function importNotation(moves:string, onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('import_notation', {moves, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+/// Copies the position in its text form, e.g. "......./......./......./......./...o.../...x... x 2", to the clipboard.
+export function copyPosition(onError: (msg:string) => void, onSuccess: (position:string) => void) {
+    invoke<string>('copy_position', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+/// Starts over with the position on the clipboard.
+export function pastePosition(onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('paste_position', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+/// Starts setting up a position from the current board; moves are rejected until the editing is finished.
+export function enterEditMode(onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('enter_edit_mode', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+export function setCell(row:number, col:number, state:'Blank' | 'P1' | 'P2', onError: (msg:string) => void) {
+    invoke('set_cell', {row: row, col: col, cellState: state, game: activeGame}).catch(failed(onError));
+}
+
+/// Starts over with the edited position, which is rejected if it cannot occur in a game.
+export function finishEdit(sideToMove:'P1' | 'P2', onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('finish_edit', {sideToMove: sideToMove, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+/// Clears the board to step through the finished game, the number of its moves is passed on.
+export function startReplay(onError: (msg:string) => void, onSuccess: (moves:number) => void) {
+    invoke<number>('start_replay', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+/// The replay functions pass on the number of moves shown, the board is updated through the usual events.
+export function replayStepForward(onError: (msg:string) => void, onSuccess: (position:number) => void) {
+    invoke<number>('replay_step_forward', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+export function replayStepBack(onError: (msg:string) => void, onSuccess: (position:number) => void) {
+    invoke<number>('replay_step_back', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+export function replayJump(n:number, onError: (msg:string) => void, onSuccess: (position:number) => void) {
+    invoke<number>('replay_jump', {n, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+export function getLastSearchStats(onError: (msg:string) => void, onSuccess: (stats:SearchStats) => void) {
+    invoke<SearchStats>('get_last_search_stats', {game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+
+export function setTreeRecording(enabled:boolean, onError: (msg:string) => void) {
+    invoke('set_tree_recording', {enabled, game: activeGame}).catch(failed(onError));
+}
+
+/// Stops the computer's search, the pending command then fails with kind 'cancelled' if the move is taken back.
+export function cancelAi(onError: (msg:string) => void) {
+    invoke('cancel_ai', {game: activeGame}).catch(failed(onError));
+}
+
+export function setCancelMode(mode:CancelMode, onError: (msg:string) => void) {
+    invoke('set_cancel_mode', {mode, game: activeGame}).catch(failed(onError));
+}
+
+/// Also emits balance updates while the human thinks, from a short background evaluation.
+export function setLiveEval(enabled:boolean, onError: (msg:string) => void) {
+    invoke('set_live_eval', {enabled, game: activeGame}).catch(failed(onError));
+}
+
+export interface ChatMessage {
+    /// the label of the window it was sent from
+    sender: string,
+    text: string,
+}
+
+/// Posts a message to every window, see `onChatMessage`. Rejected if empty, too long, sent too often or filtered.
+export function sendChat(text:string, onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('send_chat', {text}).then(onSuccess).catch(failed(onError));
+}
+
+/// Judges each move of the human, see `onUpdateAnnotation`.
+export function setAnnotations(enabled:boolean, onError: (msg:string) => void) {
+    invoke('set_annotations', {enabled, game: activeGame}).catch(failed(onError));
+}
+
+/// Writes the tree of the engine's last search to a file, as Graphviz DOT if the path ends with ".dot" and as JSON otherwise.
+export function exportSearchTree(path:string, onError: (msg:string) => void, onSuccess: () => void) {
+    invoke('export_search_tree', {path, game: activeGame}).then(onSuccess).catch(failed(onError));
+}
+
+
+export interface SearchConfig {
+    time_limit_millis: number | null,
+    max_depth: number | null,
+    randomized: boolean,
+    temperature: number,
+    min_score: number,
+    epsilon: number,
+    multi_pv: number,
+    max_nodes: number | null,
+    threads: number,
+    futility_margin: number | null,
+    late_move_reduction: boolean,
+    forced_move_extensions: boolean,
+    move_ordering: boolean,
+    opponent_accuracy: number | null,
+    tie_break: 'CenterFirst' | 'Random' | 'ShallowestWin' | 'MostRobust',
+    memoize: boolean,
+    draw_score: number,
+    deterministic: number | null,
+}
+
+export function getLastSearchConfig(onError: (msg:string) => void, onSuccess: (config:SearchConfig) => void) {
+    invoke<SearchConfig>('get_last_search_config', {game: activeGame}).then(onSuccess).catch(failed(onError));
 }
 
 
@@ -71,6 +621,10 @@ export function onUpdateCell(row:number, col:number, onTrigger: (event:Update) =
     return listen<Update>('updateCell-' + row + '-' + col, event => onTrigger(event.payload));
 }
 
+export function onUpdateBoard(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
+    return listen<Update>('updateBoard', event => onTrigger(event.payload));
+}
+
 export function onUpdateState(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
     console.log('update state', event);
     return listen<Update>('updateState', event => onTrigger(event.payload));
@@ -79,4 +633,48 @@ export function onUpdateState(onTrigger: (event:Update) => void): Promise<Unlist
 export function onUpdateBalance(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
     console.log('update balance', event);
     return listen<Update>('updateBalance', event => onTrigger(event.payload));
+}
+
+export function onUpdateThinking(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
+    return listen<Update>('updateThinking', event => onTrigger(event.payload));
+}
+
+export function onUpdateAnalysis(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
+    return listen<Update>('updateAnalysis', event => onTrigger(event.payload));
+}
+
+export function onUpdateProgress(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
+    return listen<Update>('updateProgress', event => onTrigger(event.payload));
+}
+
+export function onUpdateMove(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
+    return listen<Update>('updateMove', event => onTrigger(event.payload));
+}
+
+export function onUpdateSearched(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
+    return listen<Update>('updateSearched', event => onTrigger(event.payload));
+}
+
+export function onUpdateClock(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
+    return listen<Update>('updateClock', event => onTrigger(event.payload));
+}
+
+export function onUpdateAnnotation(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
+    return listen<Update>('updateAnnotation', event => onTrigger(event.payload));
+}
+
+export function onUpdateLastMove(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
+    return listen<Update>('updateLastMove', event => onTrigger(event.payload));
+}
+
+export function onSearchProgress(onTrigger: (event:Update) => void): Promise<UnlistenFn> {
+    return listen<Update>('searchProgress', event => onTrigger(event.payload));
+}
+
+export function onUpdateSeries(onTrigger: (score:SeriesScore) => void): Promise<UnlistenFn> {
+    return listen<SeriesScore>('updateSeries', event => onTrigger(event.payload));
+}
+
+export function onChatMessage(onTrigger: (message:ChatMessage) => void): Promise<UnlistenFn> {
+    return listen<ChatMessage>('chatMessage', event => onTrigger(event.payload));
 }
\ No newline at end of file
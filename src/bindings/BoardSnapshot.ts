@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Cell } from "./Cell";
+import type { CellState } from "./CellState";
+import type { GameState } from "./GameState";
+import type { MovedCell } from "./MovedCell";
+
+/**
+ * Everything the frontend shows of a game, to resynchronize it in one go.
+ */
+export type BoardSnapshot = { 
+/**
+ * Cells in row major order, row 0 is the bottom row.
+ */
+cells: Array<Cell>, col_heights: [number, number, number, number, number, number, number], state: GameState, 
+/**
+ * The player who made the last move.
+ */
+current_player: CellState, move_history: Array<number>, winner: number | null, 
+/**
+ * The latest move of player 1 and 2.
+ */
+last_moves: [MovedCell | null, MovedCell | null], };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the computer does when its search is cancelled.
+ */
+export type CancelMode = "PlayBest" | "TakeBack";
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BoardSize } from "./BoardSize";
+import type { EvaluatorKind } from "./EvaluatorKind";
+import type { Personality } from "./Personality";
+import type { Variant } from "./Variant";
+
+/**
+ * What this backend supports, so the frontend can offer the matching settings.
+ */
+export type Capabilities = { 
+/**
+ * Version of the app, which the engine is released with.
+ */
+engine_version: string, board_sizes: Array<BoardSize>, min_win_length: number, max_win_length: number, variants: Array<Variant>, min_level: number, max_level: number, evaluators: Array<EvaluatorKind>, personalities: Array<Personality>, 
+/**
+ * Whether positions can be solved perfectly instead of searched to a depth.
+ */
+perfect_solver: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A cell of `Update::Board`, with the fields of `Update::Cell` but the drop.
+ */
+export type CellChange = { row: number, col: number, state: number, winning: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Time control of a game: the budget of each player and the time added after each of their moves.
+ */
+export type ClockSettings = { budget_millis: number, increment_millis: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where a piece dropped into a column would land and how the position after it looks.
+ */
+export type ColumnPreview = { row: number, col: number, 
+/**
+ * Evaluation after the move in points, from the perspective of the player who would make it.
+ */
+score: number, 
+/**
+ * Whether the move would end the game.
+ */
+finished: boolean, };
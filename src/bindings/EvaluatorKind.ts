@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Available evaluation backends, selectable per game. Further backends, e.g. a neural network or an endgame
+ * tablebase, implement `Evaluator`, get a variant here and are created in `build` and parsed in `from_str`; the
+ * search only calls the `Evaluator` methods and needs no change. `build` cannot fail, so a backend which needs a
+ * model or tables loads them once beforehand and shares them with the evaluators it builds, e.g. through an `Arc`.
+ */
+export type EvaluatorKind = "Heuristic" | "Threats" | "Windows";
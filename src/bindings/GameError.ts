@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a `GameError` is sent to the frontend.
+ */
+export type GameError = { kind: string, code: string, message: string, params: Record<string, unknown>, };
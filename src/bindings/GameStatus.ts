@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CellState } from "./CellState";
+import type { GameState } from "./GameState";
+import type { Rules } from "./Rules";
+
+/**
+ * The state of a game apart from its board, see `Game::status`.
+ */
+export type GameStatus = { state: GameState, 
+/**
+ * The player whose turn it is, `None` once the game is over.
+ */
+to_move: CellState | null, level: number, rules: Rules, hotseat: boolean, moves: number, 
+/**
+ * Whether the computer is searching its move.
+ */
+calculating: boolean, };
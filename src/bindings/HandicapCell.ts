@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CellState } from "./CellState";
+
+/**
+ * A piece placed before the first move of a game, see `Game::set_handicap`.
+ */
+export type HandicapCell = { row: number, col: number, player: CellState, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Quick, search free judgement of a move, e.g. to pick a sound for it.
+ */
+export type MoveKind = "Quiet" | "CreatesThreat" | "BlocksThreat" | "Winning" | "Blunder";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Judgement of a move by the points it lost compared to the evaluation before it.
+ */
+export type MoveQuality = "Best" | "Good" | "Inaccuracy" | "Blunder";
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CellState } from "./CellState";
+
+/**
+ * A move of the game, as listed in the move history.
+ */
+export type MoveRecord = { 
+/**
+ * Counting from 1.
+ */
+number: number, col: number, player: CellState, 
+/**
+ * Score of the computer's search for its moves from the perspective of player 1, `None` for other moves.
+ */
+score: number | null, 
+/**
+ * Time taken for the move, from the previous move or the start of the game.
+ */
+millis: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClockSettings } from "./ClockSettings";
+import type { EvaluatorKind } from "./EvaluatorKind";
+import type { HandicapCell } from "./HandicapCell";
+import type { Personality } from "./Personality";
+import type { StartMode } from "./StartMode";
+import type { Variant } from "./Variant";
+
+/**
+ * Settings of `new_game`, all but `level` and `starting_player` may be left out.
+ * `start_mode` decides who moves first, by default `starting_player` does. `best_of` starts a series of that many
+ * games, `continue_series` starts the next game of the running series instead, in which the starting player
+ * alternates and overrides both. With `adaptive` the level matching the human's rating replaces `level`.
+ */
+export type NewGameOptions = { level: number, starting_player: number, start_mode: StartMode | null, evaluator: EvaluatorKind | null, rubber_band: boolean | null, mistakes: boolean | null, personality: Personality | null, seed: number | null, win_length: number | null, variant: Variant | null, hotseat: boolean | null, clock: ClockSettings | null, best_of: number | null, continue_series: boolean | null, adaptive: boolean | null, handicap: Array<HandicapCell> | null, };
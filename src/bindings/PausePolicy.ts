@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * When a background activity is suspended, it resumes once none of the enabled conditions holds.
+ */
+export type PausePolicy = { when_minimized: boolean, 
+/**
+ * See `power::on_battery`.
+ */
+on_battery: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A playing style of the computer, made of evaluation weights and how freely it strays from the best move.
+ */
+export type Personality = "Balanced" | "Aggressive" | "Defensive" | "Trappy";
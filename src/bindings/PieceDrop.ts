@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a piece fell into its cell, so the frontend can animate it and time its sound.
+ */
+export type PieceDrop = { 
+/**
+ * The column the piece was dropped into.
+ */
+col: number, 
+/**
+ * The row it landed in, row 0 is the bottom row.
+ */
+row: number, 
+/**
+ * Number of rows it fell through, counted from above the top row.
+ */
+distance: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Variant } from "./Variant";
+
+/**
+ * Rules of the variant played.
+ */
+export type Rules = { 
+/**
+ * Pieces in a row needed to win, from `MIN_WIN_LENGTH` to `MAX_WIN_LENGTH`.
+ */
+win_length: number, variant: Variant, };
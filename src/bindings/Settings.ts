@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PausePolicy } from "./PausePolicy";
+import type { Rules } from "./Rules";
+import type { StartMode } from "./StartMode";
+
+/**
+ * What is kept between runs of the app, the preferences of the user are applied on startup.
+ */
+export type Settings = { 
+/**
+ * Whether the human started the previous game, `None` before the first one.
+ */
+last_human_started: boolean | null, level: number, 
+/**
+ * The side which moves first, 1 or -1, see `new_game`.
+ */
+starting_player: number, start_mode: StartMode, 
+/**
+ * Variant and length of a row. The board itself always has `WIDTH` columns and `HEIGHT` rows.
+ */
+rules: Rules, sound: boolean, 
+/**
+ * When the evaluation of the position in the background is suspended, see `set_live_eval`.
+ */
+live_eval_pause: PausePolicy, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How `new_game` decides whether the human or the computer moves first.
+ */
+export type StartMode = "Explicit" | "Random" | "Alternate";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Sub-states reported while the game is `Calculating`. There is no phase for a book lookup, as the engine plays
+ * no opening book: `openings` only names the first moves of a game, every move of the computer is searched.
+ */
+export type ThinkingPhase = { "Searching": { depth: number, } } | "Finalizing";
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AnalysisMove } from "./AnalysisMove";
+import type { CellChange } from "./CellChange";
+import type { MoveKind } from "./MoveKind";
+import type { MoveQuality } from "./MoveQuality";
+import type { MovedCell } from "./MovedCell";
+import type { PieceDrop } from "./PieceDrop";
+import type { ThinkingPhase } from "./ThinkingPhase";
+
+export type Update = { "Cell": { row: number, col: number, state: number, winning: boolean, drop: PieceDrop | null, } } | { "State": { state: number, winner: number | null, } } | { "Balance": { value: number, } } | { "Thinking": { phase: ThinkingPhase, } } | { "Analysis": { moves: Array<AnalysisMove>, } } | { "Progress": { depth: number, col: number, score: number, nodes: number, } } | { "Move": { row: number, col: number, player: number, kind: MoveKind, } } | { "Searched": { depth: number, elapsed_millis: number, nodes: number, reason: string, } } | { "Clock": { p1_millis: number, p2_millis: number, running: number | null, } } | { "SearchProgress": { depth: number, nodes: number, col: number | null, elapsed_millis: number, } } | { "Board": { cells: Array<CellChange>, } } | { "LastMove": { p1: MovedCell | null, p2: MovedCell | null, } } | { "Annotation": { col: number, player: number, quality: MoveQuality, swing: number, } };
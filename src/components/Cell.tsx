@@ -1,5 +1,5 @@
 import { useEffect, useState } from "react";
-import { onUpdateCell } from "../Interface";
+import { onUpdateBoard, onUpdateCell } from "../Interface";
 
 export const State = {
     Blank: 0,
@@ -53,8 +53,17 @@ const Cell = ({ row, col }: Props) => {
             }
         });
     
+        const unlistenBoard = onUpdateBoard(event => {
+            const cell = event.Board.cells.find(c => c.row == row && c.col == col);
+            if (cell) {
+                setState(cell.state);
+                setWinning(cell.winning);
+            }
+        });
+    
         return () => {
             unlisten.then(f => f());
+            unlistenBoard.then(f => f());
         };
     });
     
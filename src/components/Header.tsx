@@ -1,9 +1,11 @@
-import { useEffect } from 'react'
+import { useEffect, useState } from 'react'
 import { AppState, useStore } from '../store';
+import { getOpeningName, onUpdateProgress, onUpdateSearched, onUpdateState, onUpdateThinking } from '../Interface';
 
 const Header = () => {
     const setMessage = useStore(state => state.changeMessage);
     const appState = useStore(state => state.appState);
+    const [opening, setOpening] = useState<string | null>(null);
     
     useEffect(() => {
         const interval = setInterval(
@@ -17,11 +19,54 @@ const Header = () => {
         return () => clearInterval(interval);
     })
 
+    useEffect(() => {
+        const unlisten = onUpdateThinking(event => {
+            const phase = event.Thinking.phase;
+            setMessage(phase == 'Finalizing' ? 'Finalizing move...' : 'Searching depth ' + phase.Searching.depth + '...');
+        });
+
+        return () => {
+            unlisten.then(f => f());
+        };
+    }, [setMessage])
+
+    useEffect(() => {
+        const unlisten = onUpdateProgress(event => {
+            const progress = event.Progress;
+            setMessage('Depth ' + progress.depth + ': best column ' + (progress.col + 1) + ' (' + progress.nodes + ' nodes)');
+        });
+
+        return () => {
+            unlisten.then(f => f());
+        };
+    }, [setMessage])
+
+    useEffect(() => {
+        const unlisten = onUpdateSearched(event => {
+            const searched = event.Searched;
+            const seconds = (searched.elapsed_millis / 1000).toFixed(1);
+            const suffix = searched.reason == 'search.exhausted' ? ', every line solved' : '';
+            setMessage('Computer searched to depth ' + searched.depth + ' in ' + seconds + 's' + suffix);
+        });
+
+        return () => {
+            unlisten.then(f => f());
+        };
+    }, [setMessage])
+
+    useEffect(() => {
+        const unlisten = onUpdateState(_ => getOpeningName(setMessage, setOpening));
+
+        return () => {
+            unlisten.then(f => f());
+        };
+    }, [setMessage])
+
     const message = useStore(state => state.message);
 
     return (
         <div id='header'>
-            {message}
+            {message || opening}
         </div>
     )
 };
@@ -14,6 +14,7 @@ const NewGameModal = () => {
   const onError = useStore(state => state.changeMessage);
   const level = useStore(state => state.level);
   const [computerStarts, setComputerStarts] = useState(false);
+  const [rubberBand, setRubberBand] = useState(false);
 
   return (
     <div className='modal-background'>
@@ -27,12 +28,19 @@ const NewGameModal = () => {
                 name='Computer starts'
                 onStateToggle={setComputerStarts}
             />
+            <Checkbox 
+                name='Adaptive difficulty'
+                onStateToggle={setRubberBand}
+            />
             <Button
                 name='start'
                 onClick={() => {
                   newGame(
-                    level,
-                    computerStarts ? CellState.P2 : CellState.P1,
+                    {
+                      level,
+                      starting_player: computerStarts ? CellState.P2 : CellState.P1,
+                      rubber_band: rubberBand,
+                    },
                     onError, 
                     () => {
                       changeAppState(AppState.Playing) 